@@ -0,0 +1,14 @@
+-- Stores the periods during which the WAN was down
+create table core.wan_outages (
+    outage_id uuid primary key,
+    started_at timestamptz not null,
+    ended_at timestamptz, -- null while the outage is ongoing
+    created_at timestamptz default now(),
+    updated_at timestamptz default now(),
+
+    check (ended_at is null or ended_at >= started_at)
+);
+
+-- At most one outage can be ongoing
+create unique index wan_outages_ongoing_idx on core.wan_outages ((true)) where ended_at is null;
+create index wan_outages_started_at_idx on core.wan_outages (started_at);
@@ -0,0 +1,11 @@
+-- Associates the port forwarding rules of the router with the service ports they expose
+create table core.port_forward_links (
+    port_forward_id varchar(255) primary key, -- identifier given by the router
+    service_id uuid not null references core.services(service_id) on delete cascade,
+    port integer not null,
+    transport_protocol varchar(3) not null check(transport_protocol in ('TCP', 'UDP')),
+    created_at timestamptz default now(),
+    updated_at timestamptz default now()
+);
+
+create index port_forward_links_service_id_idx on core.port_forward_links (service_id);
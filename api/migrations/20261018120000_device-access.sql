@@ -0,0 +1,20 @@
+-- Stores the internet access rules of the devices (parental controls), as enforced by the router
+create table core.device_access (
+    mac_address macaddr primary key references core.devices(mac_address) on delete cascade,
+    is_blocked boolean not null default false,
+    created_at timestamptz default now(),
+    updated_at timestamptz default now()
+);
+
+-- Weekly slots during which the internet access of a device is blocked
+create table core.device_access_slots (
+    mac_address macaddr not null references core.device_access(mac_address) on delete cascade,
+    day smallint not null check (day between 0 and 6), -- 0 is Monday
+    start_time time not null,
+    end_time time not null,
+    created_at timestamptz default now(),
+    updated_at timestamptz default now(),
+
+    primary key (mac_address, day, start_time),
+    check (end_time > start_time)
+);
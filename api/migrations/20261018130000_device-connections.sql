@@ -0,0 +1,11 @@
+-- Stores how the devices are connected to the network, as last reported by the router
+create table core.device_connections (
+    mac_address macaddr primary key references core.devices(mac_address) on delete cascade,
+    connection_type varchar(5) not null check(connection_type in ('Wired', 'Wifi')),
+    band varchar(6) check(band in ('2.4GHz', '5GHz', '6GHz')),
+    rssi smallint, -- in dBm
+    link_rate integer, -- in Mbit/s
+    access_point varchar(255),
+    created_at timestamptz default now(),
+    updated_at timestamptz default now()
+);
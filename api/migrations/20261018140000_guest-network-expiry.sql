@@ -0,0 +1,7 @@
+-- Stores when the guest Wi-Fi network must be disabled, routers cannot do it on their own
+create table core.guest_network_expiry (
+    id boolean primary key default true check (id), -- the router has a single guest network
+    expires_at timestamptz not null,
+    created_at timestamptz default now(),
+    updated_at timestamptz default now()
+);
@@ -0,0 +1,10 @@
+-- Stores the periodic backups of the router configuration
+create table core.config_snapshots (
+    snapshot_id uuid primary key,
+    taken_at timestamptz not null,
+    content text not null,
+    created_at timestamptz default now(),
+    updated_at timestamptz default now()
+);
+
+create index config_snapshots_taken_at_idx on core.config_snapshots (taken_at);
@@ -0,0 +1,23 @@
+-- Stores the byte counters of each device as of their last sample, to compute the next deltas
+create table core.device_traffic_counters (
+    mac_address macaddr primary key references core.devices(mac_address) on delete cascade,
+    rx_bytes bigint not null,
+    tx_bytes bigint not null,
+    sampled_at timestamptz not null,
+    created_at timestamptz default now(),
+    updated_at timestamptz default now()
+);
+
+-- Stores the traffic of each device between two samples of its counters
+create table core.device_traffic (
+    mac_address macaddr references core.devices(mac_address) on delete cascade,
+    sampled_at timestamptz not null,
+    rx_bytes bigint not null, -- received by the device
+    tx_bytes bigint not null, -- sent by the device
+    created_at timestamptz default now(),
+    updated_at timestamptz default now(),
+
+    primary key (mac_address, sampled_at)
+);
+
+create index device_traffic_sampled_at_idx on core.device_traffic (sampled_at);
@@ -0,0 +1,28 @@
+-- Stores the samples of the WAN bandwidth, rolled up in core.wan_stats_buckets
+create table core.wan_stats_samples (
+    sampled_at timestamptz primary key,
+    download_bandwidth bigint not null, -- in kbps
+    upload_bandwidth bigint not null, -- in kbps
+    status varchar(4) check(status in ('Up', 'Down')), -- null if the router does not report it
+    created_at timestamptz default now(),
+    updated_at timestamptz default now()
+);
+
+-- Stores the WAN bandwidth rolled up in 1m, 1h and 1d buckets
+create table core.wan_stats_buckets (
+    bucket varchar(2) not null check(bucket in ('1m', '1h', '1d')),
+    bucket_start timestamptz not null,
+    samples integer not null,
+    download_min bigint not null, -- in kbps
+    download_avg double precision not null, -- in kbps
+    download_max bigint not null, -- in kbps
+    upload_min bigint not null, -- in kbps
+    upload_avg double precision not null, -- in kbps
+    upload_max bigint not null, -- in kbps
+    status_samples integer not null, -- samples reporting the WAN status
+    up_samples integer not null,
+    created_at timestamptz default now(),
+    updated_at timestamptz default now(),
+
+    primary key (bucket, bucket_start)
+);
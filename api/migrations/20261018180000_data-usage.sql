@@ -0,0 +1,31 @@
+-- Stores the WAN counters as of the last sample, to compute the next deltas (single row)
+create table core.data_usage_counters (
+    id boolean primary key default true check (id),
+    download_bytes bigint not null,
+    upload_bytes bigint not null,
+    sampled_at timestamptz not null,
+    created_at timestamptz default now(),
+    updated_at timestamptz default now()
+);
+
+-- Stores the WAN traffic accumulated over each billing period
+create table core.data_usage_periods (
+    period_start date primary key,
+    period_end date not null, -- excluded
+    download_bytes bigint not null default 0,
+    upload_bytes bigint not null default 0,
+    created_at timestamptz default now(),
+    updated_at timestamptz default now()
+);
+
+-- Stores the quota alerts, raised once per threshold and billing period
+create table core.data_usage_alerts (
+    period_start date references core.data_usage_periods(period_start) on delete cascade,
+    threshold smallint not null, -- in percent of the quota
+    usage_bytes bigint not null,
+    raised_at timestamptz not null,
+    created_at timestamptz default now(),
+    updated_at timestamptz default now(),
+
+    primary key (period_start, threshold)
+);
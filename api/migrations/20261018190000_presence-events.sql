@@ -0,0 +1,18 @@
+-- Stores the presence transitions of the devices detected when syncing them
+create table core.presence_events (
+    mac_address macaddr references core.devices(mac_address) on delete cascade,
+    occurred_at timestamptz not null,
+    kind varchar(16) not null, -- new, disconnected or reconnected
+    is_online boolean not null, -- after the event
+    created_at timestamptz default now(),
+    updated_at timestamptz default now(),
+
+    primary key (mac_address, occurred_at)
+);
+
+create index presence_events_occurred_at_idx on core.presence_events (occurred_at);
+
+-- The presence of the known devices is only known from now on
+insert into core.presence_events (mac_address, occurred_at, kind, is_online)
+select mac_address, now(), 'new', is_online
+from core.devices;
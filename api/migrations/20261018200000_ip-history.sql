@@ -0,0 +1,19 @@
+-- Stores the addresses held by each device, from the first to the last scan that saw them
+create table core.ip_history (
+    mac_address macaddr references core.devices(mac_address) on delete cascade,
+    ip_address inet not null,
+    first_seen timestamptz not null,
+    last_seen timestamptz not null,
+    created_at timestamptz default now(),
+    updated_at timestamptz default now(),
+
+    primary key (mac_address, ip_address, first_seen)
+);
+
+create index ip_history_ip_address_idx on core.ip_history (ip_address, first_seen);
+
+-- The history starts with the last known address of each device
+insert into core.ip_history (mac_address, ip_address, first_seen, last_seen)
+select mac_address, last_known_ip, coalesce(last_seen, now()), coalesce(last_seen, now())
+from core.devices
+where last_known_ip is not null;
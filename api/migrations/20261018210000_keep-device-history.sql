@@ -0,0 +1,3 @@
+-- The history of a deleted device is kept, and continues if the router sees it again
+alter table core.presence_events drop constraint presence_events_mac_address_fkey;
+alter table core.ip_history drop constraint ip_history_mac_address_fkey;
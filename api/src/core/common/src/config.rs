@@ -1,6 +1,7 @@
-use std::{net::IpAddr, str::FromStr};
+use std::{net::IpAddr, ops::Deref, path::PathBuf, str::FromStr};
 
 use config_macro::config;
+use ipnetwork::IpNetwork;
 use strum::EnumString;
 use url::Url;
 
@@ -18,9 +19,33 @@ where
             .ok()
             .as_deref()
             .or(default)
-            .expect(&format!("Missing environment variable: {}", key))
+            .unwrap_or_else(|| panic!("Missing environment variable: {}", key))
             .parse()
-            .expect(&format!("Failed to parse environment variable: {}", key))
+            .unwrap_or_else(|_| panic!("Failed to parse environment variable: {}", key))
+    }
+}
+
+/// A comma-separated list of values (e.g. `192.168.1.0/24,10.0.0.0/24`), empty items are ignored.
+pub struct List<T>(pub Vec<T>);
+
+impl<T: FromStr> FromStr for List<T> {
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl<T> Deref for List<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
     }
 }
 
@@ -36,6 +61,12 @@ pub struct Config {
     pub scanning: ScanningConfig,
     #[env("AGENT")]
     pub agents: AgentsConfig,
+    #[env("CONFIG_SNAPSHOTS")]
+    pub config_snapshots: ConfigSnapshotsConfig,
+    #[env("WAN_HISTORY")]
+    pub wan_history: WanHistoryConfig,
+    #[env("DATA_USAGE")]
+    pub data_usage: DataUsageConfig,
 }
 
 #[config]
@@ -53,21 +84,101 @@ pub struct RouterApiConfig {
     #[env("KIND")]
     pub kind: RouterKind,
     #[env("BASE_URL")]
-    pub base_url: Url,
-    #[env("PASSWORD")]
+    pub base_url: Option<Url>, // required by every backend that talks to a router
+    #[env("PASSWORD", default = "")]
     pub password: String,
+    #[env("OPENWRT")]
+    pub openwrt: OpenWrtConfig,
+    #[env("FRITZBOX")]
+    pub fritzbox: FritzboxConfig,
+    #[env("SNMP")]
+    pub snmp: SnmpConfig,
+    #[env("DISCOVERY")]
+    pub discovery: DiscoveryConfig,
+    #[env("LEASES")]
+    pub leases: LeasesConfig,
+    #[env("COMPOSITE")]
+    pub composite: CompositeConfig,
+    #[env("SIMULATED")]
+    pub simulated: SimulatedConfig,
+    #[env("RESILIENCE")]
+    pub resilience: ResilienceConfig,
+}
+
+#[config]
+pub struct OpenWrtConfig {
+    #[env("USERNAME", default = "root")]
+    pub username: String,
+    #[env("WAN_INTERFACE", default = "wan")]
+    pub wan_interface: String,
+    #[env("WAN6_INTERFACE", default = "wan6")]
+    pub wan6_interface: String,
+    #[env("LEASE_TIME", default = "43200")]
+    pub lease_time: i64, // in seconds, used to estimate when offline devices were last seen
 }
 
+/// Connections are pooled and shared by every request and job, a warning is logged whenever a
+/// transaction had to wait for the pool to free a connection.
 #[config]
 pub struct DatabaseConfig {
     #[env("URL")]
     pub url: Url,
+    #[env("MAX_CONNECTIONS", default = "10")]
+    pub max_connections: u32,
+    #[env("MIN_CONNECTIONS", default = "0")]
+    pub min_connections: u32, // kept open even when idle
+    #[env("ACQUIRE_TIMEOUT", default = "30")]
+    pub acquire_timeout: u64, // in seconds, waiting for a free connection of the pool
+    #[env("STATEMENT_TIMEOUT")]
+    pub statement_timeout: Option<u64>, // in milliseconds, statements are not limited without it
 }
 
 #[config]
 pub struct ScanningConfig {
     #[env("DEVICE_SCAN_DELAY", default = "60")]
     pub device_scan_delay: u64,
+    #[env("WAN_STATUS_DELAY", default = "30")]
+    pub wan_status_delay: u64, // in seconds
+    #[env("DEVICE_TRAFFIC_DELAY", default = "300")]
+    pub device_traffic_delay: u64, // in seconds
+}
+
+/// Periodic backups of the router configuration, only stored when the configuration changed.
+#[config]
+pub struct ConfigSnapshotsConfig {
+    #[env("INTERVAL", default = "86400")]
+    pub interval: u64, // in seconds
+    #[env("RETENTION", default = "90")]
+    pub retention: i64, // in days, the latest snapshot is always kept
+}
+
+/// Samples of the WAN bandwidth, rolled up in 1m/1h/1d buckets. Each level is rolled up from the
+/// finer one, which must be kept for at least two buckets of the coarser one.
+#[config]
+pub struct WanHistoryConfig {
+    #[env("INTERVAL", default = "10")]
+    pub interval: u64, // in seconds
+    #[env("SAMPLES_RETENTION", default = "1")]
+    pub samples_retention: i64, // in days
+    #[env("MINUTE_RETENTION", default = "7")]
+    pub minute_retention: i64, // in days
+    #[env("HOUR_RETENTION", default = "90")]
+    pub hour_retention: i64, // in days
+    #[env("DAY_RETENTION", default = "730")]
+    pub day_retention: i64, // in days
+}
+
+/// Monthly usage of metered links, accumulated across router reboots.
+#[config]
+pub struct DataUsageConfig {
+    #[env("INTERVAL", default = "300")]
+    pub interval: u64, // in seconds
+    #[env("RESET_DAY", default = "1")]
+    pub reset_day: u32, // day of the month on which the billing period starts, in UTC
+    #[env("QUOTA")]
+    pub quota: Option<u64>, // in bytes, no alert is raised without a quota
+    #[env("ALERT_THRESHOLDS", default = "80,90,100")]
+    pub alert_thresholds: List<u8>, // in percent of the quota
 }
 
 #[config]
@@ -84,10 +195,193 @@ pub struct BaseAgentConfig {
     pub download_base_url: String,
 }
 
+#[config]
+pub struct FritzboxConfig {
+    #[env("USERNAME", default = "")]
+    pub username: String,
+}
+
+/// The agent is reached at the host and port of the router API base URL (e.g. `udp://192.168.1.1:161`).
+#[config]
+pub struct SnmpConfig {
+    #[env("VERSION", default = "v2c")]
+    pub version: SnmpVersion,
+    #[env("COMMUNITY", default = "public")]
+    pub community: String,
+    #[env("USERNAME", default = "")]
+    pub username: String, // SNMPv3 only, the router API password is used as authentication password
+    #[env("AUTH_PROTOCOL", default = "sha1")]
+    pub auth_protocol: SnmpAuthProtocol,
+    #[env("PRIVACY_PROTOCOL", default = "none")]
+    pub privacy_protocol: SnmpPrivacyProtocol,
+    #[env("PRIVACY_PASSWORD", default = "")]
+    pub privacy_password: String,
+    #[env("WAN_IF_INDEX", default = "1")]
+    pub wan_if_index: u32,
+    #[env("TIMEOUT", default = "2")]
+    pub timeout: u64, // in seconds
+}
+
+#[derive(EnumString, Clone, Copy)]
+#[strum(serialize_all = "lowercase")]
+pub enum SnmpVersion {
+    V2c,
+    V3,
+}
+
+#[derive(EnumString, Clone, Copy)]
+#[strum(serialize_all = "lowercase")]
+pub enum SnmpAuthProtocol {
+    Md5,
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+#[derive(EnumString, Clone, Copy)]
+#[strum(serialize_all = "lowercase")]
+pub enum SnmpPrivacyProtocol {
+    None,
+    Des,
+    Aes128,
+    Aes192,
+    Aes256,
+}
+
+/// Finds devices from the neighbour table of the host running the API, for networks whose router
+/// has no usable API. WAN information is not available with this backend.
+#[config]
+pub struct DiscoveryConfig {
+    #[env("NEIGHBOUR_TABLE", default = "/proc/net/arp")]
+    pub neighbour_table: PathBuf,
+    #[env("SUBNETS", default = "")]
+    pub subnets: List<IpNetwork>, // swept before reading the neighbour table, no sweep if empty
+    #[env("SWEEP_METHOD", default = "arp")]
+    pub sweep_method: SweepMethod,
+    #[env("SWEEP_TIMEOUT", default = "1000")]
+    pub sweep_timeout: u64, // in milliseconds
+    #[env("SWEEP_CONCURRENCY", default = "64")]
+    pub sweep_concurrency: usize,
+    #[env("RESOLVE_HOSTNAMES", default = "true")]
+    pub resolve_hostnames: bool,
+}
+
+/// DHCP lease files (dnsmasq `dhcp.leases` or Kea memfile CSV). With the `leases` router kind, they
+/// are the only source of devices; with any other kind, they fill the names and addresses of the
+/// devices found by the router.
+#[config]
+pub struct LeasesConfig {
+    #[env("FILES", default = "")]
+    pub files: List<PathBuf>,
+    #[env("LEASE_TIME", default = "3600")]
+    pub lease_time: i64, // in seconds, used to estimate when dnsmasq leases were renewed
+}
+
+/// Simulated network, for demos and development without hardware.
+#[config]
+pub struct SimulatedConfig {
+    #[env("SCENARIO")]
+    pub scenario: Option<PathBuf>, // JSON scenario file (see assets/simulated_scenario.json), a small home network is simulated if unset
+    #[env("SEED", default = "42")]
+    pub seed: u64,
+}
+
+/// Caching, retries and circuit breaking of the calls made to the router API. Only the settings of
+/// the top-level router API apply, the members of a composite router share them.
+#[config]
+pub struct ResilienceConfig {
+    #[env(
+        "CACHE_TTLS",
+        default = "wan_connectivity=5,wan_stats=2,list_devices=10,list_port_forwards=10,lan_network=300,list_static_leases=10,list_device_access=10,list_wifi_radios=30,list_device_connections=10,guest_network=10,router_info=30"
+    )]
+    pub cache_ttls: List<CacheTtl>, // read methods missing from the list are not cached
+    #[env("RETRY_ATTEMPTS", default = "3")]
+    pub retry_attempts: u32, // including the first one, only reads are retried
+    #[env("RETRY_BACKOFF", default = "200")]
+    pub retry_backoff: u64, // in milliseconds, doubled after each attempt
+    #[env("BREAKER_THRESHOLD", default = "5")]
+    pub breaker_threshold: u32, // consecutive failures opening the circuit, 0 disables it
+    #[env("BREAKER_COOLDOWN", default = "30")]
+    pub breaker_cooldown: u64, // in seconds, before a call is let through to probe the router
+}
+
+/// How long the result of a router API method is cached (e.g. `wan_stats=2`).
+pub struct CacheTtl {
+    pub method: String,
+    pub ttl: u64, // in seconds
+}
+
+impl FromStr for CacheTtl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (method, ttl) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Missing TTL for method {s}"))?;
+
+        Ok(Self {
+            method: method.trim().to_string(),
+            ttl: ttl
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid TTL {ttl}"))?,
+        })
+    }
+}
+
+/// Router APIs merged by the `composite` router kind, listed in `MEMBERS` (e.g. `MAIN,MESH`). Each
+/// member is configured like the router API itself under its own prefix (e.g.
+/// `API_ROUTER_API_COMPOSITE_MAIN_KIND`). Members are listed by priority, the first one is the
+/// primary member which provides the WAN information.
+pub struct CompositeConfig {
+    pub members: Vec<CompositeMemberConfig>,
+}
+
+pub struct CompositeMemberConfig {
+    pub name: String,
+    pub config: RouterApiConfig,
+}
+
+impl CompositeConfig {
+    pub fn from_env(prefix: &str, _: Option<&str>) -> Self {
+        let names = List::<String>::from_env(&format!("{}_MEMBERS", prefix), Some(""));
+
+        Self {
+            members: names
+                .iter()
+                .map(|name| CompositeMemberConfig {
+                    name: name.clone(),
+                    config: RouterApiConfig::from_env(
+                        &format!("{}_{}", prefix, name.to_uppercase()),
+                        None,
+                    ),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(EnumString, Clone, Copy)]
+#[strum(serialize_all = "lowercase")]
+pub enum SweepMethod {
+    Arp,  // empty UDP datagrams, enough for the kernel to resolve the neighbour
+    Icmp, // echo requests, requires unprivileged ICMP sockets or CAP_NET_RAW
+}
+
 #[derive(EnumString)]
 #[strum(serialize_all = "camelCase")]
 pub enum RouterKind {
     Bbox,
+    #[strum(serialize = "openwrt")]
+    OpenWrt,
+    Fritzbox,
+    Snmp,
+    Discovery,
+    Leases,
+    Composite,
+    Simulated,
 }
 
 pub static CONFIG: std::sync::LazyLock<Config> = std::sync::LazyLock::new(|| {
@@ -1,6 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{Data, DeriveInput, Field, Fields, Meta, parse_macro_input};
+use syn::{
+    Data, DeriveInput, Field, Fields, GenericArgument, Ident, LitStr, PathArguments, Token, Type,
+    parse_macro_input,
+};
 
 /// A procedural macro for generating configuration structs that load values from environment variables.
 ///
@@ -30,6 +33,9 @@ use syn::{Data, DeriveInput, Field, Fields, Meta, parse_macro_input};
 /// - **Required environment fields**: `#[env("ENV_SUFFIX")]`
 ///   - Creates env var: `{prefix}_{ENV_SUFFIX}`
 ///   - Panics if env var is missing
+/// - **Optional environment fields**: `#[env("ENV_SUFFIX")]` on an `Option<FieldType>`
+///   - Creates env var: `{prefix}_{ENV_SUFFIX}`
+///   - `None` if env var is missing or empty
 ///
 /// # Examples
 ///
@@ -97,7 +103,7 @@ pub fn config(_args: TokenStream, input: TokenStream) -> TokenStream {
         });
 
         if let Some(env_attr) = get_env_attribute(field) {
-            let (env_suffix, default) = parse_env_attribute(&env_attr);
+            let (env_suffix, default) = parse_env_attribute(env_attr);
 
             let default_value = if let Some(default_value) = default {
                 quote! { Some(#default_value) }
@@ -105,12 +111,28 @@ pub fn config(_args: TokenStream, input: TokenStream) -> TokenStream {
                 quote! { None }
             };
 
-            field_initializations.push(quote! {
-                #field_name: {
-                    let env_var_name = format!("{}_{}", prefix, #env_suffix);
-                    #field_type::from_env(&env_var_name, #default_value)
-                }
-            });
+            if let Some(inner_type) = get_option_inner_type(field_type) {
+                field_initializations.push(quote! {
+                    #field_name: {
+                        let env_var_name = format!("{}_{}", prefix, #env_suffix);
+                        std::env::var(&env_var_name)
+                            .ok()
+                            .filter(|value| !value.is_empty())
+                            .map(|value| {
+                                value.parse::<#inner_type>().unwrap_or_else(|_| {
+                                    panic!("Failed to parse environment variable: {}", env_var_name)
+                                })
+                            })
+                    }
+                });
+            } else {
+                field_initializations.push(quote! {
+                    #field_name: {
+                        let env_var_name = format!("{}_{}", prefix, #env_suffix);
+                        <#field_type>::from_env(&env_var_name, #default_value)
+                    }
+                });
+            }
         }
     }
 
@@ -135,38 +157,47 @@ fn get_env_attribute(field: &Field) -> Option<&syn::Attribute> {
     field.attrs.iter().find(|attr| attr.path().is_ident("env"))
 }
 
+/// Returns `T` if the given type is `Option<T>`.
+fn get_option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => match arguments.args.first()? {
+            GenericArgument::Type(inner_type) => Some(inner_type),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 fn parse_env_attribute(attr: &syn::Attribute) -> (String, Option<String>) {
     let mut env_suffix = None;
     let mut default = None;
 
-    match &attr.meta {
-        Meta::List(meta_list) => {
-            let tokens_str = meta_list.tokens.to_string();
-            let parts: Vec<&str> = tokens_str.split(',').collect();
+    // Parsed as tokens rather than text, so that the default value may contain commas
+    attr.parse_args_with(|input: syn::parse::ParseStream| {
+        env_suffix = Some(input.parse::<LitStr>()?.value());
 
-            // First part should be the env variable suffix (a string literal)
-            if let Some(first_part) = parts.first() {
-                let trimmed = first_part.trim();
-                if trimmed.starts_with('"') && trimmed.ends_with('"') {
-                    env_suffix = Some(trimmed[1..trimmed.len() - 1].to_string());
-                }
-            }
+        while input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let name = input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+            let value = input.parse::<LitStr>()?;
 
-            // Look for default parameter in remaining parts
-            for part in parts.iter().skip(1) {
-                let trimmed = part.trim();
-                if trimmed.starts_with("default") {
-                    if let Some(equals_pos) = trimmed.find('=') {
-                        let default_part = trimmed[equals_pos + 1..].trim();
-                        if default_part.starts_with('"') && default_part.ends_with('"') {
-                            default = Some(default_part[1..default_part.len() - 1].to_string());
-                        }
-                    }
-                }
+            if name == "default" {
+                default = Some(value.value());
             }
         }
-        _ => panic!("env attribute must be a list"),
-    }
+
+        Ok(())
+    })
+    .unwrap_or_else(|err| panic!("Invalid env attribute: {err}"));
 
     (
         env_suffix
@@ -0,0 +1,64 @@
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+
+/// The internet access rules of a device, enforced by the router (parental controls).
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceAccess {
+    pub mac_address: MacAddress,
+    pub is_blocked: bool,
+    pub schedule: Vec<AccessSlot>, // weekly slots during which internet access is blocked
+}
+
+/// A weekly slot during which internet access is blocked, slots spanning midnight must be split.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessSlot {
+    pub day: Weekday,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AccessState {
+    Allowed,
+    Blocked,
+    Scheduled, // blocked by the schedule
+}
+
+impl AccessSlot {
+    pub fn is_valid(&self) -> bool {
+        self.start < self.end
+    }
+
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        at.weekday() == self.day && self.start <= at.time() && at.time() < self.end
+    }
+}
+
+impl DeviceAccess {
+    /// The rules of a device whose access is not restricted.
+    pub fn allowed(mac_address: MacAddress) -> Self {
+        Self {
+            mac_address,
+            is_blocked: false,
+            schedule: Vec::new(),
+        }
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        self.is_blocked || !self.schedule.is_empty()
+    }
+
+    /// Returns the access state at the given time, in the timezone of the router.
+    pub fn state_at(&self, at: NaiveDateTime) -> AccessState {
+        if self.is_blocked {
+            AccessState::Blocked
+        } else if self.schedule.iter().any(|slot| slot.contains(at)) {
+            AccessState::Scheduled
+        } else {
+            AccessState::Allowed
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use chrono::{DateTime, Datelike, Months, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+
+use crate::counter_delta;
+
+/// Byte counters of the WAN as reported by the router. They are reset when the router reboots.
+#[derive(Clone, Debug)]
+pub struct WanTrafficCounters {
+    pub download_bytes: u64,
+    pub upload_bytes: u64,
+}
+
+impl WanTrafficCounters {
+    /// Returns the traffic since the `previous` counters. A counter lower than before was reset,
+    /// everything it counted since is new traffic.
+    pub fn delta_since(&self, previous: &WanTrafficCounters) -> (u64, u64) {
+        (
+            counter_delta(self.download_bytes, previous.download_bytes),
+            counter_delta(self.upload_bytes, previous.upload_bytes),
+        )
+    }
+}
+
+/// A billing period, from its reset day (included) to the next one (excluded), in UTC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BillingPeriod {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl BillingPeriod {
+    /// Returns the period containing `date`. A reset day past the end of a month (e.g. 31) falls
+    /// on the last day of shorter months.
+    pub fn containing(date: NaiveDate, reset_day: u32) -> Self {
+        let this_month = reset_date(date, reset_day);
+        let start = if this_month <= date {
+            this_month
+        } else {
+            reset_date(date - Months::new(1), reset_day)
+        };
+
+        Self {
+            start,
+            end: reset_date(start + Months::new(1), reset_day),
+        }
+    }
+
+    pub fn starts_at(&self) -> DateTime<Utc> {
+        self.start.and_time(NaiveTime::MIN).and_utc()
+    }
+
+    pub fn ends_at(&self) -> DateTime<Utc> {
+        self.end.and_time(NaiveTime::MIN).and_utc()
+    }
+}
+
+/// Returns the reset day in the month of `date`.
+fn reset_date(date: NaiveDate, reset_day: u32) -> NaiveDate {
+    let first = date.with_day(1).unwrap();
+    let last = (first + Months::new(1)).pred_opt().unwrap();
+    first.with_day(reset_day.clamp(1, last.day())).unwrap()
+}
+
+/// The traffic of the WAN over a billing period.
+#[derive(Clone, Debug)]
+pub struct DataUsagePeriod {
+    pub period: BillingPeriod,
+    pub download_bytes: u64,
+    pub upload_bytes: u64,
+}
+
+impl DataUsagePeriod {
+    pub fn total_bytes(&self) -> u64 {
+        self.download_bytes + self.upload_bytes
+    }
+
+    /// Projects the usage at the end of the period, assuming the traffic keeps its average rate.
+    pub fn projected_bytes(&self, now: DateTime<Utc>) -> u64 {
+        let elapsed = (now - self.period.starts_at()).num_seconds();
+        let length = (self.period.ends_at() - self.period.starts_at()).num_seconds();
+        if elapsed <= 0 || elapsed >= length {
+            return self.total_bytes();
+        }
+
+        (self.total_bytes() as f64 * length as f64 / elapsed as f64) as u64
+    }
+}
+
+/// Raised once per billing period when the usage crosses a percentage of the quota.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataUsageAlert {
+    pub period_start: NaiveDate,
+    pub threshold: u8, // in percent of the quota
+    pub usage_bytes: u64,
+    pub raised_at: DateTime<Utc>,
+}
+
+/// The WAN usage over the current billing period, with its projection at the end of the period.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataUsage {
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate, // excluded
+    pub download_bytes: u64,
+    pub upload_bytes: u64,
+    pub total_bytes: u64,
+    pub projected_bytes: u64,
+    pub quota_bytes: Option<u64>,  // None if the link is not metered
+    pub used_percent: Option<f64>, // of the quota
+    pub projected_percent: Option<f64>, // of the quota
+    pub alerts: Vec<DataUsageAlert>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn period(start: NaiveDate, end: NaiveDate) -> BillingPeriod {
+        BillingPeriod { start, end }
+    }
+
+    #[test]
+    fn period_starts_on_the_last_reset_day() {
+        assert_eq!(
+            BillingPeriod::containing(date(2025, 6, 20), 15),
+            period(date(2025, 6, 15), date(2025, 7, 15))
+        );
+        assert_eq!(
+            BillingPeriod::containing(date(2025, 6, 15), 15),
+            period(date(2025, 6, 15), date(2025, 7, 15))
+        );
+        assert_eq!(
+            BillingPeriod::containing(date(2025, 6, 14), 15),
+            period(date(2025, 5, 15), date(2025, 6, 15))
+        );
+        assert_eq!(
+            BillingPeriod::containing(date(2025, 1, 5), 10),
+            period(date(2024, 12, 10), date(2025, 1, 10))
+        );
+    }
+
+    #[test]
+    fn period_reset_day_is_clamped_to_the_end_of_short_months() {
+        // February
+        assert_eq!(
+            BillingPeriod::containing(date(2025, 2, 10), 31),
+            period(date(2025, 1, 31), date(2025, 2, 28))
+        );
+        assert_eq!(
+            BillingPeriod::containing(date(2025, 2, 28), 31),
+            period(date(2025, 2, 28), date(2025, 3, 31))
+        );
+        // February of a leap year
+        assert_eq!(
+            BillingPeriod::containing(date(2024, 2, 28), 31),
+            period(date(2024, 1, 31), date(2024, 2, 29))
+        );
+        assert_eq!(
+            BillingPeriod::containing(date(2024, 2, 29), 30),
+            period(date(2024, 2, 29), date(2024, 3, 30))
+        );
+        // 30-day months
+        assert_eq!(
+            BillingPeriod::containing(date(2025, 4, 30), 31),
+            period(date(2025, 4, 30), date(2025, 5, 31))
+        );
+        assert_eq!(
+            BillingPeriod::containing(date(2025, 5, 1), 31),
+            period(date(2025, 4, 30), date(2025, 5, 31))
+        );
+    }
+
+    #[test]
+    fn reset_date_stays_in_the_month() {
+        assert_eq!(reset_date(date(2025, 3, 20), 5), date(2025, 3, 5));
+        assert_eq!(reset_date(date(2025, 2, 14), 30), date(2025, 2, 28));
+        assert_eq!(reset_date(date(2024, 2, 1), 30), date(2024, 2, 29));
+        assert_eq!(reset_date(date(2025, 4, 10), 31), date(2025, 4, 30));
+        assert_eq!(reset_date(date(2025, 4, 10), 0), date(2025, 4, 1));
+    }
+
+    #[test]
+    fn projected_bytes_extrapolates_the_average_rate() {
+        // 30-day period
+        let usage = DataUsagePeriod {
+            period: period(date(2025, 4, 1), date(2025, 5, 1)),
+            download_bytes: 80,
+            upload_bytes: 20,
+        };
+        let starts_at = usage.period.starts_at();
+
+        assert_eq!(
+            usage.projected_bytes(starts_at + chrono::Duration::days(10)),
+            300
+        );
+        assert_eq!(
+            usage.projected_bytes(starts_at + chrono::Duration::days(15)),
+            200
+        );
+        // Outside of the period, the usage is what it is
+        assert_eq!(usage.projected_bytes(starts_at), 100);
+        assert_eq!(
+            usage.projected_bytes(starts_at - chrono::Duration::days(1)),
+            100
+        );
+        assert_eq!(usage.projected_bytes(usage.period.ends_at()), 100);
+    }
+}
@@ -4,7 +4,7 @@ use mac_address::MacAddress;
 use serde::Serialize;
 use sqlx::FromRow;
 
-use crate::Service;
+use crate::{AccessState, DeviceAccess, DeviceConnection, Service};
 
 #[derive(Clone, Debug, Serialize, FromRow)]
 #[serde(rename_all = "camelCase")]
@@ -17,6 +17,11 @@ pub struct Device {
     pub is_online: bool,
     pub last_seen: chrono::DateTime<chrono::Utc>,
     pub last_scanned: chrono::DateTime<chrono::Utc>,
+    /// Addresses held by the device besides `last_known_ip` (e.g. IPv6 ones), as reported by the
+    /// router. They are only kept in the IP history.
+    #[sqlx(skip)]
+    #[serde(skip)]
+    pub other_ips: Vec<IpAddr>,
 }
 
 impl Device {
@@ -31,13 +36,26 @@ impl Device {
         self.is_online = new_device.is_online;
         self.last_seen = new_device.last_seen;
         self.last_scanned = new_device.last_scanned;
+        self.other_ips = new_device.other_ips;
 
         self
     }
 }
 
 #[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct FullDevice {
     pub device: Device,
     pub services: Option<Vec<Service>>,
+    pub access: Option<DeviceAccess>, // None if the access of the device is not restricted
+    pub access_state: AccessState,
+    pub connection: Option<DeviceConnection>, // None if the router does not report it
+}
+
+/// An IP address reserved for a device by the DHCP server of the router.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaticLease {
+    pub mac_address: MacAddress,
+    pub ip_address: IpAddr,
 }
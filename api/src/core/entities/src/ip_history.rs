@@ -0,0 +1,25 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use mac_address::MacAddress;
+use serde::Serialize;
+
+/// A period during which a device held an address, from the first to the last scan that saw it.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpAssignment {
+    pub mac_address: MacAddress,
+    pub display_name: Option<String>, // None once the device was deleted
+    pub ip_address: IpAddr,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// The devices that held an address, most recent first.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpHistory {
+    pub ip_address: IpAddr,
+    pub at: Option<DateTime<Utc>>, // only the devices that held the address at that time if set
+    pub assignments: Vec<IpAssignment>,
+}
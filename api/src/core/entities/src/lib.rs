@@ -1,20 +1,27 @@
+mod access;
+mod data_usage;
 mod device;
+mod ip_history;
 mod network;
+mod port_forward;
+mod presence;
+mod query;
+mod router;
 mod service;
+mod traffic;
 mod utils;
+mod wifi;
 
-use std::sync::Arc;
-
-use tokio::sync::Mutex;
-
+pub use access::*;
+pub use data_usage::*;
 pub use device::*;
+pub use ip_history::*;
 pub use network::*;
+pub use port_forward::*;
+pub use presence::*;
+pub use query::*;
+pub use router::*;
 pub use service::*;
+pub use traffic::*;
 pub use utils::*;
-
-/// Convert the object to an SQL expression (useful for pagination, filtering, etc.)
-pub trait ToSql {
-    fn to_sql(&self) -> String;
-}
-
-pub type SharedLockedReference<T> = Arc<Mutex<T>>;
+pub use wifi::*;
@@ -1,8 +1,11 @@
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
-use serde::Serialize;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use uuid::Uuid;
 
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WanStats {
     pub download: WanStatsItem,
@@ -10,7 +13,7 @@ pub struct WanStats {
     pub active_sessions: usize,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WanStatsItem {
     pub max_bandwidth: usize,           // in kbps
@@ -20,18 +23,18 @@ pub struct WanStatsItem {
 }
 
 #[serde_with::serde_as]
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WanConnectivity {
     pub ipv4: Ipv4Addr,
     pub ipv6: Option<Ipv6Addr>,
     pub gateway: IpAddr,
     pub status: WanStatus,
-    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
-    pub uptime: chrono::Duration,
+    #[serde_as(as = "Option<serde_with::DurationSeconds<i64>>")]
+    pub uptime: Option<chrono::Duration>, // None if the router does not report it
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, EnumString, Display)]
 pub enum WanStatus {
     Up,
     Down,
@@ -39,6 +42,135 @@ pub enum WanStatus {
 
 #[derive(Debug, Serialize)]
 pub struct NetworkStatus {
-    pub connectivity: WanConnectivity,
-    pub stats: WanStats,
+    pub connectivity: Option<WanConnectivity>, // None if the router API cannot report it
+    pub stats: Option<WanStats>,
+}
+
+/// A period during which the WAN was down.
+#[serde_with::serde_as]
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WanOutage {
+    pub outage_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>, // None while the outage is ongoing
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub duration: chrono::Duration, // up to now for an ongoing outage
+}
+
+impl WanOutage {
+    pub fn start(started_at: DateTime<Utc>) -> Self {
+        Self {
+            outage_id: Uuid::now_v7(),
+            started_at,
+            ended_at: None,
+            duration: chrono::Duration::zero(),
+        }
+    }
+
+    pub fn end(mut self, ended_at: DateTime<Utc>) -> Self {
+        let ended_at = ended_at.max(self.started_at);
+        self.ended_at = Some(ended_at);
+        self.duration = ended_at - self.started_at;
+        self
+    }
+
+    /// Returns how long the WAN was down between `from` and `to`.
+    pub fn downtime_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> chrono::Duration {
+        let start = self.started_at.max(from);
+        let end = self.ended_at.unwrap_or(now).min(to);
+        (end - start).max(chrono::Duration::zero())
+    }
+}
+
+#[serde_with::serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WanAvailability {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub availability: f64, // in percent
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub downtime: chrono::Duration,
+    pub daily: Vec<DailyWanAvailability>,
+    pub outages: Vec<WanOutage>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyWanAvailability {
+    pub date: NaiveDate,   // in UTC
+    pub availability: f64, // in percent
+}
+
+/// A sample of the WAN bandwidth, with its status if the router reports it.
+#[derive(Clone, Debug)]
+pub struct WanStatsSample {
+    pub sampled_at: DateTime<Utc>,
+    pub download_bandwidth: usize, // in kbps
+    pub upload_bandwidth: usize,   // in kbps
+    pub status: Option<WanStatus>,
+}
+
+/// Size of the buckets in which the WAN samples are rolled up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, EnumString, Display)]
+pub enum WanStatsBucket {
+    #[serde(rename = "1m")]
+    #[strum(serialize = "1m")]
+    Minute,
+
+    #[serde(rename = "1h")]
+    #[strum(serialize = "1h")]
+    Hour,
+
+    #[serde(rename = "1d")]
+    #[strum(serialize = "1d")]
+    Day,
+}
+
+impl WanStatsBucket {
+    pub const ALL: [WanStatsBucket; 3] = [
+        WanStatsBucket::Minute,
+        WanStatsBucket::Hour,
+        WanStatsBucket::Day,
+    ];
+
+    pub fn duration(&self) -> chrono::Duration {
+        match self {
+            WanStatsBucket::Minute => chrono::Duration::minutes(1),
+            WanStatsBucket::Hour => chrono::Duration::hours(1),
+            WanStatsBucket::Day => chrono::Duration::days(1),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BandwidthSummary {
+    pub min: usize, // in kbps
+    pub avg: f64,   // in kbps
+    pub max: usize, // in kbps
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WanHistoryPoint {
+    pub start: DateTime<Utc>,
+    pub download: BandwidthSummary,
+    pub upload: BandwidthSummary,
+    pub availability: Option<f64>, // in percent, None if the router does not report the WAN status
+}
+
+/// The WAN bandwidth over a range, rolled up in buckets. Buckets without samples are omitted.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WanHistory {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub bucket: WanStatsBucket,
+    pub points: Vec<WanHistoryPoint>,
 }
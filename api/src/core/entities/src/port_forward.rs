@@ -0,0 +1,69 @@
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::TransportProtocol;
+
+/// A port forwarding (NAT) rule, as configured on the router.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortForward {
+    pub port_forward_id: String, // identifier given by the router
+    #[serde(flatten)]
+    pub rule: PortForwardRule,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PortForwardRule {
+    #[validate(length(max = 100))]
+    pub description: String,
+    pub is_enabled: bool,
+    pub protocol: PortForwardProtocol,
+
+    #[validate(range(min = 1, max = 65535))]
+    pub external_port: u16,
+    pub internal_ip: IpAddr,
+
+    #[validate(range(min = 1, max = 65535))]
+    pub internal_port: u16,
+    pub remote_ip: Option<IpAddr>, // None if any remote address is allowed
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PortForwardProtocol {
+    TCP,
+    UDP,
+    Both,
+}
+
+impl PortForwardProtocol {
+    /// Whether traffic of the given transport protocol is forwarded.
+    pub fn forwards(&self, transport_protocol: TransportProtocol) -> bool {
+        matches!(
+            (self, transport_protocol),
+            (PortForwardProtocol::Both, _)
+                | (PortForwardProtocol::TCP, TransportProtocol::TCP)
+                | (PortForwardProtocol::UDP, TransportProtocol::UDP)
+        )
+    }
+}
+
+/// The service port exposed by a port forwarding rule.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortForwardLink {
+    #[serde(skip)]
+    pub port_forward_id: String,
+    pub service_id: Uuid,
+    pub port: u16,
+    pub transport_protocol: TransportProtocol,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FullPortForward {
+    pub port_forward: PortForward,
+    pub service: Option<PortForwardLink>, // None if the rule exposes no known service
+}
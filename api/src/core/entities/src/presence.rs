@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// A change of the presence of a device, as detected when syncing the devices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumString, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum PresenceEventKind {
+    New,
+    Disconnected,
+    Reconnected,
+}
+
+#[derive(Clone, Debug)]
+pub struct PresenceEvent {
+    pub mac_address: MacAddress,
+    pub kind: PresenceEventKind,
+    pub is_online: bool, // after the event, new devices may be offline
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// A period during which a device was online.
+#[serde_with::serde_as]
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnlineInterval {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>, // None while the device is online
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub duration: chrono::Duration,
+}
+
+#[serde_with::serde_as]
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevicePresence {
+    pub mac_address: MacAddress,
+    pub display_name: String,
+    pub is_online: bool, // at the end of the range
+    pub first_seen: DateTime<Utc>,
+    pub last_disconnected_at: Option<DateTime<Utc>>, // within the range
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub online_time: chrono::Duration,
+    pub intervals: Vec<OnlineInterval>,
+}
+
+impl DevicePresence {
+    /// Builds the online intervals of the device between `from` and `to` from its presence events
+    /// sorted by date. The intervals are clipped to the range, the events before it only give the
+    /// state of the device at its start.
+    pub fn from_events(
+        mac_address: MacAddress,
+        display_name: String,
+        first_seen: DateTime<Utc>,
+        events: &[PresenceEvent],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mut intervals = Vec::new();
+        let mut online_since = None;
+        let mut is_online = false;
+        let mut last_disconnected_at = None;
+
+        for event in events.iter().filter(|event| event.occurred_at < to) {
+            let occurred_at = event.occurred_at.max(from);
+            if event.kind == PresenceEventKind::Disconnected && event.occurred_at >= from {
+                last_disconnected_at = Some(event.occurred_at);
+            }
+
+            match (online_since, event.is_online) {
+                (None, true) => online_since = Some(occurred_at),
+                (Some(started_at), false) => {
+                    intervals.push(OnlineInterval {
+                        started_at,
+                        ended_at: Some(occurred_at),
+                        duration: occurred_at - started_at,
+                    });
+                    online_since = None;
+                }
+                _ => (),
+            }
+            is_online = event.is_online;
+        }
+
+        if let Some(started_at) = online_since {
+            let end = to.min(now);
+            intervals.push(OnlineInterval {
+                started_at,
+                ended_at: (to < now).then_some(to),
+                duration: (end - started_at).max(chrono::Duration::zero()),
+            });
+        }
+
+        Self {
+            mac_address,
+            display_name,
+            is_online,
+            first_seen,
+            last_disconnected_at,
+            online_time: intervals.iter().map(|interval| interval.duration).sum(),
+            intervals,
+        }
+    }
+}
+
+/// The presence of the devices over a range.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresenceHistory {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub devices: Vec<DevicePresence>,
+}
@@ -0,0 +1,156 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use mac_address::MacAddress;
+use serde::Deserialize;
+use strum::Display;
+use uuid::Uuid;
+
+use crate::{Cursor, Device, Paging, Service, ServiceKind};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Sort<F> {
+    pub field: F,
+    pub order: SortOrder,
+}
+
+/// A column a listing can be sorted by, which the cursors of the listing are bound to.
+pub trait SortField: Copy + std::fmt::Display {
+    /// Whether the cursor was built for a listing sorted by this field, so that its value and key
+    /// can be compared to the rows.
+    fn accepts(self, cursor: &Cursor) -> bool;
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Display)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum DeviceSortField {
+    #[default]
+    DisplayName,
+    MacAddress,
+    LastKnownIp,
+    IsOnline,
+    LastSeen,
+    LastScanned,
+}
+
+impl DeviceSortField {
+    /// Cursor positioned right after the device in a listing sorted by this field.
+    pub fn cursor_of(self, device: &Device) -> Cursor {
+        let value = match self {
+            DeviceSortField::DisplayName => device.display_name.clone(),
+            DeviceSortField::MacAddress => device.mac_address.to_string(),
+            DeviceSortField::LastKnownIp => device.last_known_ip.to_string(),
+            DeviceSortField::IsOnline => device.is_online.to_string(),
+            DeviceSortField::LastSeen => device.last_seen.to_rfc3339(),
+            DeviceSortField::LastScanned => device.last_scanned.to_rfc3339(),
+        };
+
+        Cursor {
+            field: self.to_string(),
+            value,
+            key: device.mac_address.to_string(),
+        }
+    }
+}
+
+impl SortField for DeviceSortField {
+    fn accepts(self, cursor: &Cursor) -> bool {
+        let is_value_valid = match self {
+            DeviceSortField::DisplayName => true,
+            DeviceSortField::MacAddress => cursor.value.parse::<MacAddress>().is_ok(),
+            DeviceSortField::LastKnownIp => cursor.value.parse::<IpAddr>().is_ok(),
+            DeviceSortField::IsOnline => cursor.value.parse::<bool>().is_ok(),
+            DeviceSortField::LastSeen | DeviceSortField::LastScanned => {
+                DateTime::parse_from_rfc3339(&cursor.value).is_ok()
+            }
+        };
+
+        cursor.field == self.to_string()
+            && is_value_valid
+            && cursor.key.parse::<MacAddress>().is_ok()
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct DeviceFilter {
+    pub is_online: Option<bool>,
+    /// Case-insensitive substring of the display name, IP address or MAC address.
+    pub search: Option<String>,
+    pub last_seen_from: Option<DateTime<Utc>>,
+    pub last_seen_to: Option<DateTime<Utc>>,
+    /// Only keeps the devices hosting a service of this kind.
+    pub service_kind: Option<ServiceKind>,
+}
+
+/// Selection of devices, the default one being all of them sorted by name.
+#[derive(Debug, Default, Clone)]
+pub struct DeviceQuery {
+    pub filter: DeviceFilter,
+    pub sort: Sort<DeviceSortField>,
+    pub paging: Option<Paging>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Display)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum ServiceSortField {
+    #[default]
+    DisplayName,
+    Kind,
+    DeviceMac,
+}
+
+impl ServiceSortField {
+    /// Cursor positioned right after the service in a listing sorted by this field.
+    pub fn cursor_of(self, service: &Service) -> Cursor {
+        let value = match self {
+            ServiceSortField::DisplayName => service.display_name.clone(),
+            ServiceSortField::Kind => service.kind.to_string(),
+            ServiceSortField::DeviceMac => service.device_mac.to_string(),
+        };
+
+        Cursor {
+            field: self.to_string(),
+            value,
+            key: service.service_id.to_string(),
+        }
+    }
+}
+
+impl SortField for ServiceSortField {
+    fn accepts(self, cursor: &Cursor) -> bool {
+        let is_value_valid = match self {
+            ServiceSortField::DisplayName | ServiceSortField::Kind => true,
+            ServiceSortField::DeviceMac => cursor.value.parse::<MacAddress>().is_ok(),
+        };
+
+        cursor.field == self.to_string() && is_value_valid && cursor.key.parse::<Uuid>().is_ok()
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ServiceFilter {
+    pub device_mac: Option<MacAddress>,
+    pub kind: Option<ServiceKind>,
+    /// A service is online when at least one of its ports is.
+    pub is_online: Option<bool>,
+    /// Case-insensitive substring of the display name.
+    pub search: Option<String>,
+}
+
+/// Selection of services, the default one being all of them sorted by name.
+#[derive(Debug, Default, Clone)]
+pub struct ServiceQuery {
+    pub filter: ServiceFilter,
+    pub sort: Sort<ServiceSortField>,
+    pub paging: Option<Paging>,
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+#[serde_with::serde_as]
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouterInfo {
+    pub model: String,
+    pub firmware_version: String,
+    pub serial_number: Option<String>, // None if the router does not report it
+    #[serde_as(as = "Option<serde_with::DurationSeconds<i64>>")]
+    pub uptime: Option<chrono::Duration>, // None if the router does not report it
+}
+
+/// A backup of the router configuration.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSnapshot {
+    pub snapshot_id: Uuid,
+    pub taken_at: DateTime<Utc>,
+    pub size: usize, // in bytes
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FullConfigSnapshot {
+    #[serde(flatten)]
+    pub snapshot: ConfigSnapshot,
+    pub content: String,
+}
+
+impl FullConfigSnapshot {
+    pub fn new(content: String) -> Self {
+        Self {
+            snapshot: ConfigSnapshot {
+                snapshot_id: Uuid::now_v7(),
+                taken_at: Utc::now(),
+                size: content.len(),
+            },
+            content,
+        }
+    }
+}
+
+/// The changes of the router configuration between two snapshots.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSnapshotDiff {
+    pub from: Option<ConfigSnapshot>, // None when diffing the first snapshot
+    pub to: ConfigSnapshot,
+    pub diff: String, // in the unified format
+}
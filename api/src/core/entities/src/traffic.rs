@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use crate::counter_delta;
+
+/// Cumulative byte counters of a device as reported by the router, from the point of view of the
+/// device. They are reset when the router reboots or forgets the device.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceTrafficCounters {
+    pub mac_address: MacAddress,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+impl DeviceTrafficCounters {
+    /// Returns the traffic since the `previous` counters. A counter lower than before was reset,
+    /// everything it counted since is new traffic.
+    pub fn delta_since(&self, previous: &DeviceTrafficCounters) -> (u64, u64) {
+        (
+            counter_delta(self.rx_bytes, previous.rx_bytes),
+            counter_delta(self.tx_bytes, previous.tx_bytes),
+        )
+    }
+}
+
+/// Traffic of a device between two samples of its counters.
+#[derive(Clone, Debug)]
+pub struct DeviceTrafficSample {
+    pub mac_address: MacAddress,
+    pub sampled_at: DateTime<Utc>,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceUsage {
+    pub mac_address: MacAddress,
+    pub display_name: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// The devices that exchanged the most traffic over a range.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopTalkers {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub devices: Vec<DeviceUsage>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum UsageInterval {
+    Hour,
+    Day,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageBucket {
+    pub start: DateTime<Utc>,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+/// The traffic of a device over a range, split in intervals. Intervals without traffic are omitted.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceUsageHistory {
+    pub mac_address: MacAddress,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub buckets: Vec<UsageBucket>,
+}
@@ -1,56 +1,201 @@
-use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
 
-use crate::ToSql;
+use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct Pagination {
-    pub page: u32,
+    pub page: u32, // starts at 1
     pub limit: u32,
 }
 
 impl Pagination {
     pub const fn page_count(limit: u32, total: u32) -> u32 {
-        (total + limit - 1) / limit
+        total.div_ceil(limit)
     }
+
+    pub const fn offset(&self) -> u32 {
+        self.page.saturating_sub(1).saturating_mul(self.limit)
+    }
+}
+
+/// Position of a row in a listing: the sort field, the value of the row for it and its unique key.
+///
+/// It is exchanged with the clients as an opaque hexadecimal string.
+#[derive(Debug, Clone, PartialEq, Eq, SerializeDisplay, DeserializeFromStr)]
+pub struct Cursor {
+    pub field: String,
+    pub value: String,
+    pub key: String,
 }
 
-impl ToSql for Pagination {
-    fn to_sql(&self) -> String {
+const CURSOR_SEPARATOR: char = '\u{1f}';
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         format!(
-            "LIMIT {} OFFSET {}",
-            self.limit,
-            (self.page - 1) * self.limit
+            "{}{CURSOR_SEPARATOR}{}{CURSOR_SEPARATOR}{}",
+            self.field, self.value, self.key
         )
+        .bytes()
+        .try_for_each(|byte| write!(f, "{byte:02x}"))
     }
 }
 
-impl ToSql for Option<Pagination> {
-    fn to_sql(&self) -> String {
+impl FromStr for Cursor {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_ascii() || !s.len().is_multiple_of(2) {
+            return Err("invalid cursor");
+        }
+
+        let bytes = (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| "invalid cursor")?;
+        let decoded = String::from_utf8(bytes).map_err(|_| "invalid cursor")?;
+
+        // The field and the key never contain the separator, the value might
+        let (field, rest) = decoded
+            .split_once(CURSOR_SEPARATOR)
+            .ok_or("invalid cursor")?;
+        let (value, key) = rest.rsplit_once(CURSOR_SEPARATOR).ok_or("invalid cursor")?;
+        Ok(Self {
+            field: field.to_string(),
+            value: value.to_string(),
+            key: key.to_string(),
+        })
+    }
+}
+
+/// How a listing is split in pages.
+#[derive(Debug, Clone)]
+pub enum Paging {
+    /// Numbered pages, rows inserted meanwhile shift the following pages.
+    Offset(Pagination),
+    /// Rows following the cursor (the first ones without it), stable under insertions.
+    Keyset { after: Option<Cursor>, limit: u32 },
+}
+
+impl Paging {
+    pub const fn limit(&self) -> u32 {
         match self {
-            Some(pagination) => pagination.to_sql(),
-            None => String::new(),
+            Paging::Offset(pagination) => pagination.limit,
+            Paging::Keyset { limit, .. } => *limit,
         }
     }
 }
 
-pub fn deserialize_option_pagination<'de, D>(
-    deserializer: D,
-) -> Result<Option<Pagination>, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    #[derive(Deserialize)]
-    struct PaginationFields {
-        page: Option<u32>,
-        limit: Option<u32>,
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageMeta {
+    /// Number of rows matching the filters, across all pages.
+    pub total: u64,
+    /// Only set for offset pagination.
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+    /// Set while more rows may follow the returned ones.
+    pub next_cursor: Option<Cursor>,
+}
+
+impl PageMeta {
+    /// Metadata of a listing returned at once.
+    pub fn whole(total: usize) -> Self {
+        Self {
+            total: total as u64,
+            page: None,
+            limit: None,
+            next_cursor: None,
+        }
     }
 
-    let fields = PaginationFields::deserialize(deserializer)?;
+    /// Metadata of a page of `items`, `cursor_of` giving the cursor following an item.
+    pub fn of<T>(
+        items: &[T],
+        total: u64,
+        paging: Option<&Paging>,
+        cursor_of: impl Fn(&T) -> Cursor,
+    ) -> Self {
+        let Some(paging) = paging else {
+            return Self::whole(items.len());
+        };
+
+        let page = match paging {
+            Paging::Offset(pagination) => Some(pagination.page),
+            Paging::Keyset { .. } => None,
+        };
+
+        // A full page may be followed by more rows
+        let next_cursor = match paging {
+            Paging::Offset(pagination)
+                if u64::from(pagination.offset()) + items.len() as u64 >= total =>
+            {
+                None
+            }
+            _ if items.len() < paging.limit() as usize => None,
+            _ => items.last().map(cursor_of),
+        };
+
+        Self {
+            total,
+            page,
+            limit: Some(paging.limit()),
+            next_cursor,
+        }
+    }
+}
+
+/// A page of a listing along with its metadata.
+#[derive(Debug, Clone)]
+pub struct Paged<T> {
+    pub items: Vec<T>,
+    pub meta: PageMeta,
+}
+
+/// Returns what a cumulative counter counted since its `previous` reading, from zero if it was
+/// reset in between.
+pub(crate) fn counter_delta(current: u64, previous: u64) -> u64 {
+    current.checked_sub(previous).unwrap_or(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips_through_its_hex_form() {
+        let cursor = Cursor {
+            field: "displayName".to_string(),
+            value: "Living room \u{1f} TV 📺".to_string(),
+            key: "aa:bb:cc:dd:ee:ff".to_string(),
+        };
+
+        let encoded = cursor.to_string();
+        assert!(encoded.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(encoded.parse::<Cursor>(), Ok(cursor));
+    }
+
+    #[test]
+    fn cursor_rejects_malformed_strings() {
+        assert!("abc".parse::<Cursor>().is_err()); // odd length
+        assert!("zz".parse::<Cursor>().is_err()); // not hexadecimal
+        assert!("ff".parse::<Cursor>().is_err()); // not UTF-8
+        assert!("616263".parse::<Cursor>().is_err()); // no separator
+        assert!("611f62".parse::<Cursor>().is_err()); // no key
+        assert!("é".parse::<Cursor>().is_err());
+    }
+
+    #[test]
+    fn counter_delta_counts_since_the_previous_reading() {
+        assert_eq!(counter_delta(1500, 1000), 500);
+        assert_eq!(counter_delta(1000, 1000), 0);
+    }
 
-    match (fields.page, fields.limit) {
-        (Some(page), Some(limit)) => Ok(Some(Pagination { page, limit })),
-        (Some(_), None) => Err(serde::de::Error::missing_field("limit")),
-        (None, Some(_)) => Err(serde::de::Error::missing_field("page")),
-        (None, None) => Ok(None),
+    #[test]
+    fn counter_delta_counts_from_zero_after_a_reset() {
+        assert_eq!(counter_delta(200, 1000), 200);
+        assert_eq!(counter_delta(0, 1000), 0);
     }
 }
@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use mac_address::MacAddress;
+use serde::Serialize;
+use strum::{Display, EnumString};
+
+#[derive(
+    Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumString, Display,
+)]
+pub enum WifiBand {
+    #[serde(rename = "2.4GHz")]
+    #[strum(serialize = "2.4GHz")]
+    Band2_4GHz,
+
+    #[serde(rename = "5GHz")]
+    #[strum(serialize = "5GHz")]
+    Band5GHz,
+
+    #[serde(rename = "6GHz")]
+    #[strum(serialize = "6GHz")]
+    Band6GHz,
+}
+
+/// A Wi-Fi radio of the router, with the networks it broadcasts.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WifiRadio {
+    pub band: WifiBand,
+    pub is_enabled: bool,
+    pub channel: Option<u16>, // None if the channel is automatic and not yet selected
+    pub networks: Vec<WifiNetwork>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WifiNetwork {
+    pub ssid: String,
+    pub bssid: Option<MacAddress>,
+    pub is_enabled: bool,
+    pub is_hidden: bool,
+    pub security: Option<String>, // None if the network is open
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+pub enum ConnectionType {
+    Wired,
+    Wifi,
+}
+
+/// How a device is connected to the network, as last reported by the router.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceConnection {
+    pub mac_address: MacAddress,
+    pub connection_type: ConnectionType,
+    pub band: Option<WifiBand>,       // None for wired devices
+    pub rssi: Option<i16>,            // in dBm, None for wired devices
+    pub link_rate: Option<u32>,       // in Mbit/s
+    pub access_point: Option<String>, // name or BSSID of the access point, None if unknown
+}
+
+impl DeviceConnection {
+    pub fn wired(mac_address: MacAddress, link_rate: Option<u32>) -> Self {
+        Self {
+            mac_address,
+            connection_type: ConnectionType::Wired,
+            band: None,
+            rssi: None,
+            link_rate,
+            access_point: None,
+        }
+    }
+
+    pub fn is_wifi(&self) -> bool {
+        self.connection_type == ConnectionType::Wifi
+    }
+}
+
+/// The guest Wi-Fi network of the router.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuestNetwork {
+    pub is_enabled: bool,
+    pub ssid: String,
+    pub passphrase: String,                // empty if the network is open
+    pub expires_at: Option<DateTime<Utc>>, // when the network is disabled, None if it does not expire
+}
+
+impl GuestNetwork {
+    /// Returns the payload of a QR code joining the network, in the `WIFI:` format understood by
+    /// phone cameras.
+    pub fn qr_payload(&self) -> String {
+        fn escape(value: &str) -> String {
+            value
+                .chars()
+                .flat_map(|c| match c {
+                    '\\' | ';' | ',' | ':' | '"' => vec!['\\', c],
+                    c => vec![c],
+                })
+                .collect()
+        }
+
+        if self.passphrase.is_empty() {
+            format!("WIFI:T:nopass;S:{};;", escape(&self.ssid))
+        } else {
+            format!(
+                "WIFI:T:WPA;S:{};P:{};;",
+                escape(&self.ssid),
+                escape(&self.passphrase)
+            )
+        }
+    }
+}
@@ -1,7 +1,14 @@
-use entities::{Device, WanConnectivity, WanStats};
+use std::net::IpAddr;
+
+use entities::{
+    AccessSlot, Device, DeviceAccess, DeviceConnection, DeviceTrafficCounters, GuestNetwork,
+    PortForward, PortForwardRule, RouterInfo, StaticLease, WanConnectivity, WanStats, WifiRadio,
+};
+use ipnetwork::IpNetwork;
+use mac_address::MacAddress;
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone)]
 pub enum RouterApiError {
     #[error("The router API is unavailable or failed to respond.")]
     Unavailable,
@@ -12,15 +19,148 @@ pub enum RouterApiError {
     #[error("Authentication with the router API failed. Please check credentials.")]
     AuthenticationFailed,
 
+    #[error("The requested resource does not exist on the router.")]
+    NotFound,
+
+    #[error("The router API does not support this operation.")]
+    Unsupported,
+
     #[error("An unknown error occurred while communicating with the router API.")]
     Unknown(String),
 }
 
 pub type RouterApiResult<T> = Result<T, RouterApiError>;
 
+pub trait RouterApiResultExt<T> {
+    /// Turns an [`RouterApiError::Unsupported`] error into `None`, for data that is optional.
+    fn supported(self) -> RouterApiResult<Option<T>>;
+}
+
+impl<T> RouterApiResultExt<T> for RouterApiResult<T> {
+    fn supported(self) -> RouterApiResult<Option<T>> {
+        match self {
+            Ok(value) => Ok(Some(value)),
+            Err(RouterApiError::Unsupported) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
 #[async_trait::async_trait]
 pub trait RouterApi: Send + Sync {
     async fn wan_connectivity(&self) -> RouterApiResult<WanConnectivity>;
     async fn list_devices(&self) -> RouterApiResult<Vec<Device>>;
     async fn wan_stats(&self) -> RouterApiResult<WanStats>;
+
+    async fn list_port_forwards(&self) -> RouterApiResult<Vec<PortForward>> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    async fn create_port_forward(&self, _rule: PortForwardRule) -> RouterApiResult<PortForward> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    /// Fails with [`RouterApiError::NotFound`] if the rule does not exist.
+    async fn update_port_forward(
+        &self,
+        _port_forward_id: &str,
+        _rule: PortForwardRule,
+    ) -> RouterApiResult<PortForward> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    /// Fails with [`RouterApiError::NotFound`] if the rule does not exist.
+    async fn delete_port_forward(&self, _port_forward_id: &str) -> RouterApiResult<()> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    /// Returns the LAN of the router, with the address of the router itself.
+    async fn lan_network(&self) -> RouterApiResult<IpNetwork> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    async fn list_static_leases(&self) -> RouterApiResult<Vec<StaticLease>> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    /// Reserves the address for the device, replacing its previous reservation if any.
+    async fn reserve_ip(
+        &self,
+        _mac_address: MacAddress,
+        _ip_address: IpAddr,
+    ) -> RouterApiResult<StaticLease> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    /// Fails with [`RouterApiError::NotFound`] if the device has no reservation.
+    async fn release_ip(&self, _mac_address: MacAddress) -> RouterApiResult<()> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    /// Lists the devices whose internet access is restricted.
+    async fn list_device_access(&self) -> RouterApiResult<Vec<DeviceAccess>> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    async fn set_device_blocked(
+        &self,
+        _mac_address: MacAddress,
+        _is_blocked: bool,
+    ) -> RouterApiResult<()> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    /// Replaces the weekly slots during which the internet access of the device is blocked.
+    async fn set_device_schedule(
+        &self,
+        _mac_address: MacAddress,
+        _schedule: Vec<AccessSlot>,
+    ) -> RouterApiResult<()> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    async fn list_wifi_radios(&self) -> RouterApiResult<Vec<WifiRadio>> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    /// Lists how the devices seen by the router are connected to it.
+    async fn list_device_connections(&self) -> RouterApiResult<Vec<DeviceConnection>> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    /// Returns the byte counters of the devices seen by the router.
+    async fn list_device_traffic(&self) -> RouterApiResult<Vec<DeviceTrafficCounters>> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    /// Returns the guest Wi-Fi network, routers do not know when it expires.
+    async fn guest_network(&self) -> RouterApiResult<GuestNetwork> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    async fn set_guest_network_enabled(&self, _is_enabled: bool) -> RouterApiResult<()> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    /// Renames the guest Wi-Fi network and changes its passphrase, an empty one makes it open.
+    async fn set_guest_network_credentials(
+        &self,
+        _ssid: &str,
+        _passphrase: &str,
+    ) -> RouterApiResult<()> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    async fn router_info(&self) -> RouterApiResult<RouterInfo> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    async fn reboot(&self) -> RouterApiResult<()> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    /// Exports the configuration of the router, as text so that backups can be compared.
+    async fn export_config(&self) -> RouterApiResult<String> {
+        Err(RouterApiError::Unsupported)
+    }
 }
@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use entities::{ConfigSnapshot, FullConfigSnapshot};
+use uuid::Uuid;
+
+use crate::repositories::{Repository, UnitOfWorkProvider};
+
+use super::RepositoryResult;
+
+#[async_trait::async_trait]
+pub trait ConfigSnapshotsRepository<UWP>: Repository<UWP> + Send + Sync + Clone
+where
+    UWP: UnitOfWorkProvider,
+{
+    /// Fetches the snapshots, from the latest to the oldest.
+    async fn fetch_all<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+    ) -> RepositoryResult<Vec<ConfigSnapshot>>;
+
+    async fn fetch_one<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        snapshot_id: Uuid,
+    ) -> RepositoryResult<Option<FullConfigSnapshot>>;
+
+    async fn fetch_latest<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+    ) -> RepositoryResult<Option<FullConfigSnapshot>>;
+
+    /// Fetches the last snapshot taken before the given one.
+    async fn fetch_previous<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        snapshot: &ConfigSnapshot,
+    ) -> RepositoryResult<Option<FullConfigSnapshot>>;
+
+    async fn create<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        snapshot: FullConfigSnapshot,
+    ) -> RepositoryResult<()>;
+
+    /// Deletes the snapshots taken before the given date, except the latest one. Returns the
+    /// number of deleted snapshots.
+    async fn delete_older_than<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        before: DateTime<Utc>,
+    ) -> RepositoryResult<u64>;
+}
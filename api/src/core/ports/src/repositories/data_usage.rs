@@ -0,0 +1,50 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use entities::{BillingPeriod, DataUsageAlert, DataUsagePeriod, WanTrafficCounters};
+
+use crate::repositories::{Repository, UnitOfWorkProvider};
+
+use super::RepositoryResult;
+
+#[async_trait::async_trait]
+pub trait DataUsageRepository<UWP>: Repository<UWP> + Send + Sync + Clone
+where
+    UWP: UnitOfWorkProvider,
+{
+    /// Fetches the WAN counters as of the last sample, `None` before the first one.
+    async fn fetch_counters<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+    ) -> RepositoryResult<Option<WanTrafficCounters>>;
+
+    async fn save_counters<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        counters: WanTrafficCounters,
+        sampled_at: DateTime<Utc>,
+    ) -> RepositoryResult<()>;
+
+    /// Adds traffic to the usage of the period, and returns the new usage.
+    async fn add_usage<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        period: BillingPeriod,
+        download_bytes: u64,
+        upload_bytes: u64,
+    ) -> RepositoryResult<DataUsagePeriod>;
+
+    /// Fetches the usage of the period starting on the given day, `None` if nothing was recorded.
+    async fn fetch_usage<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        period_start: NaiveDate,
+    ) -> RepositoryResult<Option<DataUsagePeriod>>;
+
+    /// Stores the alert, unless one was already raised for the threshold during the period.
+    /// Returns whether it was stored.
+    async fn create_alert<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        alert: DataUsageAlert,
+    ) -> RepositoryResult<bool>;
+
+    /// Fetches the alerts raised during the period starting on the given day, oldest first.
+    async fn fetch_alerts<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        period_start: NaiveDate,
+    ) -> RepositoryResult<Vec<DataUsageAlert>>;
+}
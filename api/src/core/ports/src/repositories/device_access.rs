@@ -0,0 +1,31 @@
+use entities::DeviceAccess;
+use mac_address::MacAddress;
+
+use crate::repositories::{Repository, UnitOfWorkProvider};
+
+use super::RepositoryResult;
+
+#[async_trait::async_trait]
+pub trait DeviceAccessRepository<UWP>: Repository<UWP> + Send + Sync + Clone
+where
+    UWP: UnitOfWorkProvider,
+{
+    async fn fetch_all<'a>(uow: &'a mut UWP::UnitOfWork<'_>)
+    -> RepositoryResult<Vec<DeviceAccess>>;
+
+    async fn fetch_one<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        mac_address: MacAddress,
+    ) -> RepositoryResult<Option<DeviceAccess>>;
+
+    /// Creates or replaces the access rules of the device.
+    async fn save<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        access: DeviceAccess,
+    ) -> RepositoryResult<()>;
+
+    async fn delete<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        mac_address: MacAddress,
+    ) -> RepositoryResult<()>;
+}
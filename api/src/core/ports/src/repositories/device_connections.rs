@@ -0,0 +1,32 @@
+use entities::DeviceConnection;
+use mac_address::MacAddress;
+
+use crate::repositories::{Repository, UnitOfWorkProvider};
+
+use super::RepositoryResult;
+
+#[async_trait::async_trait]
+pub trait DeviceConnectionsRepository<UWP>: Repository<UWP> + Send + Sync + Clone
+where
+    UWP: UnitOfWorkProvider,
+{
+    async fn fetch_all<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+    ) -> RepositoryResult<Vec<DeviceConnection>>;
+
+    async fn fetch_one<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        mac_address: MacAddress,
+    ) -> RepositoryResult<Option<DeviceConnection>>;
+
+    /// Creates or replaces the connection of the device.
+    async fn save<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        connection: DeviceConnection,
+    ) -> RepositoryResult<()>;
+
+    async fn delete<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        mac_address: MacAddress,
+    ) -> RepositoryResult<()>;
+}
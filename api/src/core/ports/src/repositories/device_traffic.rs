@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use entities::{
+    DeviceTrafficCounters, DeviceTrafficSample, DeviceUsage, UsageBucket, UsageInterval,
+};
+use mac_address::MacAddress;
+
+use crate::repositories::{Repository, UnitOfWorkProvider};
+
+use super::RepositoryResult;
+
+#[async_trait::async_trait]
+pub trait DeviceTrafficRepository<UWP>: Repository<UWP> + Send + Sync + Clone
+where
+    UWP: UnitOfWorkProvider,
+{
+    /// Fetches the counters of each device as of their last sample.
+    async fn fetch_counters<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+    ) -> RepositoryResult<Vec<DeviceTrafficCounters>>;
+
+    async fn save_counters<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        counters: DeviceTrafficCounters,
+        sampled_at: DateTime<Utc>,
+    ) -> RepositoryResult<()>;
+
+    async fn create_sample<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        sample: DeviceTrafficSample,
+    ) -> RepositoryResult<()>;
+
+    /// Fetches the devices with the most traffic sampled in the given range, busiest first.
+    async fn fetch_top_talkers<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: usize,
+    ) -> RepositoryResult<Vec<DeviceUsage>>;
+
+    /// Fetches the traffic of the device sampled in the given range, summed per interval.
+    async fn fetch_usage<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        mac_address: MacAddress,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        interval: UsageInterval,
+    ) -> RepositoryResult<Vec<UsageBucket>>;
+}
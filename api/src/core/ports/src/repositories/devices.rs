@@ -1,4 +1,4 @@
-use entities::{Device, Pagination};
+use entities::{Device, DeviceFilter, DeviceQuery};
 use mac_address::MacAddress;
 
 use crate::repositories::{Repository, UnitOfWorkProvider};
@@ -12,14 +12,38 @@ where
 {
     async fn fetch_all<'a>(
         uow: &'a mut UWP::UnitOfWork<'_>,
-        pagination: Option<Pagination>,
+        query: DeviceQuery,
     ) -> RepositoryResult<Vec<Device>>;
 
+    /// Counts the devices matching the filter, regardless of any pagination.
+    async fn count<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        filter: DeviceFilter,
+    ) -> RepositoryResult<u64>;
+
     async fn fetch_one<'a>(
         uow: &'a mut UWP::UnitOfWork<'_>,
         mac_address: MacAddress,
     ) -> RepositoryResult<Option<Device>>;
 
     async fn create<'a>(uow: &'a mut UWP::UnitOfWork<'_>, device: Device) -> RepositoryResult<()>;
+
+    /// Updates the device as scanned, the custom name and the notes set by the user are kept.
     async fn update<'a>(uow: &'a mut UWP::UnitOfWork<'_>, device: Device) -> RepositoryResult<()>;
+
+    /// Sets the name and notes of the device. A name that is not custom is replaced by the one
+    /// reported by the router at the next scan.
+    async fn update_details<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        mac_address: MacAddress,
+        display_name: String,
+        is_name_custom: bool,
+        notes: String,
+    ) -> RepositoryResult<()>;
+
+    /// Deletes the device along with its services and history.
+    async fn delete<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        mac_address: MacAddress,
+    ) -> RepositoryResult<()>;
 }
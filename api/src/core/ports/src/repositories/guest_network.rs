@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+
+use crate::repositories::{Repository, UnitOfWorkProvider};
+
+use super::RepositoryResult;
+
+#[async_trait::async_trait]
+pub trait GuestNetworkRepository<UWP>: Repository<UWP> + Send + Sync + Clone
+where
+    UWP: UnitOfWorkProvider,
+{
+    /// Fetches when the guest network must be disabled, None if it does not expire.
+    async fn fetch_expiry<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+    ) -> RepositoryResult<Option<DateTime<Utc>>>;
+
+    async fn save_expiry<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<()>;
+
+    async fn delete_expiry<'a>(uow: &'a mut UWP::UnitOfWork<'_>) -> RepositoryResult<()>;
+}
@@ -0,0 +1,32 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use entities::IpAssignment;
+use mac_address::MacAddress;
+
+use crate::repositories::{Repository, UnitOfWorkProvider};
+
+use super::RepositoryResult;
+
+#[async_trait::async_trait]
+pub trait IpHistoryRepository<UWP>: Repository<UWP> + Send + Sync + Clone
+where
+    UWP: UnitOfWorkProvider,
+{
+    /// Records that the device held the address when scanned. The last assignment of the address
+    /// to the device is extended, unless another device held the address since.
+    async fn record<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        mac_address: MacAddress,
+        ip_address: IpAddr,
+        seen_at: DateTime<Utc>,
+    ) -> RepositoryResult<()>;
+
+    /// Fetches the assignments of the address, most recent first. Only the ones spanning `at` are
+    /// fetched if set.
+    async fn fetch_by_ip<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        ip_address: IpAddr,
+        at: Option<DateTime<Utc>>,
+    ) -> RepositoryResult<Vec<IpAssignment>>;
+}
@@ -1,9 +1,31 @@
+mod config_snapshots;
+mod data_usage;
+mod device_access;
+mod device_connections;
+mod device_traffic;
 mod devices;
+mod guest_network;
+mod ip_history;
+mod port_forward_links;
+mod presence_events;
 mod services;
+mod wan_outages;
+mod wan_stats;
 
+pub use config_snapshots::*;
+pub use data_usage::*;
+pub use device_access::*;
+pub use device_connections::*;
+pub use device_traffic::*;
 pub use devices::*;
+pub use guest_network::*;
+pub use ip_history::*;
+pub use port_forward_links::*;
+pub use presence_events::*;
 pub use services::*;
 use thiserror::Error;
+pub use wan_outages::*;
+pub use wan_stats::*;
 
 pub trait Repository<UnitOfWorkProvider> {}
 
@@ -0,0 +1,26 @@
+use entities::PortForwardLink;
+
+use crate::repositories::{Repository, UnitOfWorkProvider};
+
+use super::RepositoryResult;
+
+#[async_trait::async_trait]
+pub trait PortForwardLinksRepository<UWP>: Repository<UWP> + Send + Sync + Clone
+where
+    UWP: UnitOfWorkProvider,
+{
+    async fn fetch_all<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+    ) -> RepositoryResult<Vec<PortForwardLink>>;
+
+    async fn create<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        link: PortForwardLink,
+    ) -> RepositoryResult<()>;
+
+    /// Removes the link of the given rule, if any.
+    async fn delete<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        port_forward_id: &str,
+    ) -> RepositoryResult<()>;
+}
@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use entities::PresenceEvent;
+use mac_address::MacAddress;
+
+use crate::repositories::{Repository, UnitOfWorkProvider};
+
+use super::RepositoryResult;
+
+#[async_trait::async_trait]
+pub trait PresenceEventsRepository<UWP>: Repository<UWP> + Send + Sync + Clone
+where
+    UWP: UnitOfWorkProvider,
+{
+    async fn create<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        event: PresenceEvent,
+    ) -> RepositoryResult<()>;
+
+    /// Fetches the events of the device, or of every device if `None`, between `from` and `to`
+    /// sorted by date. The last event of each device before `from` is included, as it gives the
+    /// state of the device at the start of the range.
+    async fn fetch_between<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        mac_address: Option<MacAddress>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> RepositoryResult<Vec<PresenceEvent>>;
+
+    /// Fetches when the device, or every device if `None`, was first seen on the network.
+    async fn fetch_first_seen<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        mac_address: Option<MacAddress>,
+    ) -> RepositoryResult<Vec<(MacAddress, DateTime<Utc>)>>;
+}
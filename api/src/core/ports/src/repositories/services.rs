@@ -1,4 +1,4 @@
-use entities::{Service, ServiceKind, ServicePortTemplate};
+use entities::{Service, ServiceFilter, ServiceKind, ServicePortTemplate, ServiceQuery};
 use mac_address::MacAddress;
 use uuid::Uuid;
 
@@ -11,11 +11,28 @@ pub trait ServicesRepository<UWP>: Repository<UWP> + Send + Sync + Clone
 where
     UWP: UnitOfWorkProvider,
 {
+    async fn fetch_all<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        query: ServiceQuery,
+    ) -> RepositoryResult<Vec<Service>>;
+
+    /// Counts the services matching the filter, regardless of any pagination.
+    async fn count<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        filter: ServiceFilter,
+    ) -> RepositoryResult<u64>;
+
     async fn fetch_all_of_device<'a>(
         uow: &'a mut UWP::UnitOfWork<'_>,
         mac_address: MacAddress,
     ) -> RepositoryResult<Vec<Service>>;
 
+    /// Fetches the services of all the given devices at once.
+    async fn fetch_all_of_devices<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        mac_addresses: &[MacAddress],
+    ) -> RepositoryResult<Vec<Service>>;
+
     async fn fetch_one<'a>(
         uow: &'a mut UWP::UnitOfWork<'_>,
         service_id: Uuid,
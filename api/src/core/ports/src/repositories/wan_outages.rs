@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use entities::WanOutage;
+
+use crate::repositories::{Repository, UnitOfWorkProvider};
+
+use super::RepositoryResult;
+
+#[async_trait::async_trait]
+pub trait WanOutagesRepository<UWP>: Repository<UWP> + Send + Sync + Clone
+where
+    UWP: UnitOfWorkProvider,
+{
+    /// Fetches the outages overlapping the given range, ordered by start.
+    async fn fetch_between<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> RepositoryResult<Vec<WanOutage>>;
+
+    async fn fetch_ongoing<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+    ) -> RepositoryResult<Option<WanOutage>>;
+
+    async fn create<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        outage: WanOutage,
+    ) -> RepositoryResult<()>;
+    async fn update<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        outage: WanOutage,
+    ) -> RepositoryResult<()>;
+}
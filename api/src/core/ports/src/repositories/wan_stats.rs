@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use entities::{WanHistoryPoint, WanStatsBucket, WanStatsSample};
+
+use crate::repositories::{Repository, UnitOfWorkProvider};
+
+use super::RepositoryResult;
+
+#[async_trait::async_trait]
+pub trait WanStatsRepository<UWP>: Repository<UWP> + Send + Sync + Clone
+where
+    UWP: UnitOfWorkProvider,
+{
+    async fn create_sample<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        sample: WanStatsSample,
+    ) -> RepositoryResult<()>;
+
+    /// Rolls up the buckets starting from the one containing `since`, from the samples for
+    /// minutes and from the finer buckets otherwise. Existing buckets are replaced.
+    async fn rollup<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        bucket: WanStatsBucket,
+        since: DateTime<Utc>,
+    ) -> RepositoryResult<()>;
+
+    /// Fetches the buckets overlapping the given range, ordered by start.
+    async fn fetch_history<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        bucket: WanStatsBucket,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> RepositoryResult<Vec<WanHistoryPoint>>;
+
+    async fn delete_samples_before<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        before: DateTime<Utc>,
+    ) -> RepositoryResult<u64>;
+
+    async fn delete_buckets_before<'a>(
+        uow: &'a mut UWP::UnitOfWork<'_>,
+        bucket: WanStatsBucket,
+        before: DateTime<Utc>,
+    ) -> RepositoryResult<u64>;
+}
@@ -0,0 +1,12 @@
+use ports::{api::RouterApiError, repositories::RepositoryError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ConfigSnapshotError {
+    #[error("Config snapshot not found")]
+    SnapshotNotFound,
+    #[error("A router API error occurred: {0}")]
+    RouterApiError(#[from] RouterApiError),
+    #[error("A database error occurred: {0}.")]
+    DatabaseError(#[from] RepositoryError),
+}
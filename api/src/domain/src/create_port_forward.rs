@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use entities::{FullPortForward, PortForwardLink};
+use ports::{
+    api::RouterApi,
+    repositories::{
+        DevicesRepository, PortForwardLinksRepository, ServicesRepository, UnitOfWorkProvider,
+    },
+};
+use tracing::{error, info, instrument};
+
+use crate::{PortForwardError, SavePortForward, check_link};
+
+#[derive(Clone)]
+pub struct CreatePortForwardUseCase<
+    LR: PortForwardLinksRepository<UWP>,
+    SR: ServicesRepository<UWP>,
+    DR: DevicesRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> {
+    uow_provider: UWP,
+    router_api: Arc<dyn RouterApi>,
+    _marker: std::marker::PhantomData<(LR, SR, DR)>,
+}
+
+impl<
+    LR: PortForwardLinksRepository<UWP>,
+    SR: ServicesRepository<UWP>,
+    DR: DevicesRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> CreatePortForwardUseCase<LR, SR, DR, UWP>
+{
+    pub fn new(uow_provider: UWP, router_api: Arc<dyn RouterApi>) -> Self {
+        Self {
+            uow_provider,
+            router_api,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    #[instrument(skip(self), name = "CreatePortForwardUseCase::execute")]
+    pub async fn execute(
+        &self,
+        port_forward: SavePortForward,
+    ) -> Result<FullPortForward, PortForwardError> {
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        if let Some(link) = &port_forward.service {
+            check_link::<SR, DR, UWP>(&mut uow, &port_forward.rule, link).await?;
+        }
+
+        let created = self
+            .router_api
+            .create_port_forward(port_forward.rule)
+            .await?;
+
+        let service = port_forward.service.map(|link| PortForwardLink {
+            port_forward_id: created.port_forward_id.clone(),
+            ..link
+        });
+        if let Some(link) = service.clone()
+            && let Err(err) = LR::create(&mut uow, link).await
+        {
+            error!(port_forward = ?created, "Port forward created but could not be linked");
+            return Err(err.into());
+        }
+        self.uow_provider.commit(uow).await?;
+
+        info!(port_forward = ?created, "Port forward created successfully");
+        Ok(FullPortForward {
+            port_forward: created,
+            service,
+        })
+    }
+}
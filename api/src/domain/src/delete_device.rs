@@ -0,0 +1,36 @@
+use mac_address::MacAddress;
+use ports::repositories::{DevicesRepository, UnitOfWorkProvider};
+use tracing::{info, instrument};
+
+use crate::DeviceError;
+
+#[derive(Clone)]
+pub struct DeleteDeviceUseCase<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    _marker: std::marker::PhantomData<DR>,
+}
+
+impl<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider> DeleteDeviceUseCase<DR, UWP> {
+    pub fn new(uow_provider: UWP) -> Self {
+        Self {
+            uow_provider,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Deletes the device with its services, but keeps its presence events and address history. A
+    /// device still seen by the router comes back at the next scan, as a new device.
+    #[instrument(skip(self), name = "DeleteDeviceUseCase::execute")]
+    pub async fn execute(&self, mac_address: MacAddress) -> Result<(), DeviceError> {
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        if DR::fetch_one(&mut uow, mac_address).await?.is_none() {
+            return Err(DeviceError::DeviceNotFound);
+        }
+
+        DR::delete(&mut uow, mac_address).await?;
+        self.uow_provider.commit(uow).await?;
+
+        info!(%mac_address, "Device deleted");
+        Ok(())
+    }
+}
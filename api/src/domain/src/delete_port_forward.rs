@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use ports::{
+    api::{RouterApi, RouterApiError},
+    repositories::{PortForwardLinksRepository, UnitOfWorkProvider},
+};
+use tracing::{info, instrument};
+
+use crate::PortForwardError;
+
+#[derive(Clone)]
+pub struct DeletePortForwardUseCase<LR: PortForwardLinksRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    router_api: Arc<dyn RouterApi>,
+    _marker: std::marker::PhantomData<LR>,
+}
+
+impl<LR: PortForwardLinksRepository<UWP>, UWP: UnitOfWorkProvider>
+    DeletePortForwardUseCase<LR, UWP>
+{
+    pub fn new(uow_provider: UWP, router_api: Arc<dyn RouterApi>) -> Self {
+        Self {
+            uow_provider,
+            router_api,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    #[instrument(skip(self), name = "DeletePortForwardUseCase::execute")]
+    pub async fn execute(&self, port_forward_id: String) -> Result<(), PortForwardError> {
+        let result = self.router_api.delete_port_forward(&port_forward_id).await;
+
+        // A rule removed from the router UI leaves its link behind, clean it up as well
+        if matches!(result, Ok(()) | Err(RouterApiError::NotFound)) {
+            let mut uow = self.uow_provider.begin_transaction().await?;
+            LR::delete(&mut uow, &port_forward_id).await?;
+            self.uow_provider.commit(uow).await?;
+        }
+        result?;
+
+        info!(port_forward_id, "Port forward deleted successfully");
+        Ok(())
+    }
+}
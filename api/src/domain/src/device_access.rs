@@ -0,0 +1,45 @@
+use entities::AccessSlot;
+use ports::{api::RouterApiError, repositories::RepositoryError};
+use serde::Deserialize;
+use thiserror::Error;
+use validator::Validate;
+
+#[derive(Error, Debug)]
+pub enum DeviceAccessError {
+    #[error("Device not found")]
+    DeviceNotFound,
+    #[error("The schedule has empty or overlapping slots")]
+    InvalidSchedule,
+    #[error("A router API error occurred: {0}")]
+    RouterApiError(#[from] RouterApiError),
+    #[error("A database error occurred: {0}.")]
+    DatabaseError(#[from] RepositoryError),
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDeviceAccess {
+    pub is_blocked: bool,
+
+    #[serde(default)]
+    #[validate(length(max = 100))]
+    pub schedule: Vec<AccessSlot>,
+}
+
+impl UpdateDeviceAccess {
+    pub(crate) fn validate_schedule(&mut self) -> Result<(), DeviceAccessError> {
+        self.schedule
+            .sort_by_key(|slot| (slot.day.num_days_from_monday(), slot.start));
+
+        if self.schedule.iter().any(|slot| !slot.is_valid())
+            || self
+                .schedule
+                .windows(2)
+                .any(|slots| slots[0].day == slots[1].day && slots[0].end > slots[1].start)
+        {
+            return Err(DeviceAccessError::InvalidSchedule);
+        }
+
+        Ok(())
+    }
+}
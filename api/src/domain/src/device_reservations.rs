@@ -0,0 +1,28 @@
+use std::net::IpAddr;
+
+use ports::{api::RouterApiError, repositories::RepositoryError};
+use serde::Deserialize;
+use thiserror::Error;
+use validator::Validate;
+
+#[derive(Error, Debug)]
+pub enum DeviceReservationError {
+    #[error("Device not found")]
+    DeviceNotFound,
+    #[error("The device has no reserved address")]
+    ReservationNotFound,
+    #[error("The address is not a host address of the LAN")]
+    AddressOutsideLan,
+    #[error("The address is already used by another device")]
+    AddressTaken,
+    #[error("A router API error occurred: {0}")]
+    RouterApiError(#[from] RouterApiError),
+    #[error("A database error occurred: {0}.")]
+    DatabaseError(#[from] RepositoryError),
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ReserveIp {
+    pub ip_address: IpAddr,
+}
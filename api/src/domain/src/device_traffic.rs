@@ -0,0 +1,12 @@
+use ports::repositories::RepositoryError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DeviceTrafficError {
+    #[error("The start of the range must be before its end.")]
+    InvalidRange,
+    #[error("Device not found")]
+    DeviceNotFound,
+    #[error("A database error occurred: {0}.")]
+    DatabaseError(#[from] RepositoryError),
+}
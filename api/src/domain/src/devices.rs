@@ -0,0 +1,36 @@
+use ports::{api::RouterApiError, repositories::RepositoryError};
+use serde::{Deserialize, Deserializer};
+use thiserror::Error;
+use validator::Validate;
+
+#[derive(Error, Debug)]
+pub enum DeviceError {
+    #[error("Device not found")]
+    DeviceNotFound,
+    #[error("A router API error occurred: {0}")]
+    RouterApiError(#[from] RouterApiError),
+    #[error("A database error occurred: {0}.")]
+    DatabaseError(#[from] RepositoryError),
+}
+
+/// Changes to the details of a device, missing fields are left unchanged.
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDevice {
+    /// A custom name, or `null` to use the hostname reported by the router again.
+    #[serde(default, deserialize_with = "deserialize_some")]
+    #[validate(length(min = 1, max = 255))]
+    pub display_name: Option<Option<String>>,
+
+    #[validate(length(max = 4096))]
+    pub notes: Option<String>,
+}
+
+/// Tells a `null` field apart from a missing one, which is `None` by default.
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
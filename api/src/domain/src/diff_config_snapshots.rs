@@ -0,0 +1,61 @@
+use entities::ConfigSnapshotDiff;
+use ports::repositories::{ConfigSnapshotsRepository, UnitOfWorkProvider};
+use similar::TextDiff;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::ConfigSnapshotError;
+
+#[derive(Clone)]
+pub struct DiffConfigSnapshotsUseCase<CR: ConfigSnapshotsRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    _marker: std::marker::PhantomData<CR>,
+}
+
+impl<CR: ConfigSnapshotsRepository<UWP>, UWP: UnitOfWorkProvider>
+    DiffConfigSnapshotsUseCase<CR, UWP>
+{
+    pub fn new(uow_provider: UWP) -> Self {
+        Self {
+            uow_provider,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Diffs the snapshot against another one, by default the one taken before it.
+    #[instrument(skip(self), name = "DiffConfigSnapshotsUseCase::execute")]
+    pub async fn execute(
+        &self,
+        snapshot_id: Uuid,
+        against: Option<Uuid>,
+    ) -> Result<ConfigSnapshotDiff, ConfigSnapshotError> {
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        let to = CR::fetch_one(&mut uow, snapshot_id)
+            .await?
+            .ok_or(ConfigSnapshotError::SnapshotNotFound)?;
+
+        let from = match against {
+            Some(against) => Some(
+                CR::fetch_one(&mut uow, against)
+                    .await?
+                    .ok_or(ConfigSnapshotError::SnapshotNotFound)?,
+            ),
+            None => CR::fetch_previous(&mut uow, &to.snapshot).await?,
+        };
+
+        let from_content = from.as_ref().map_or("", |from| from.content.as_str());
+        let from_label = from.as_ref().map_or("/dev/null".to_string(), |from| {
+            from.snapshot.taken_at.to_rfc3339()
+        });
+        let diff = TextDiff::from_lines(from_content, to.content.as_str())
+            .unified_diff()
+            .header(&from_label, &to.snapshot.taken_at.to_rfc3339())
+            .to_string();
+
+        Ok(ConfigSnapshotDiff {
+            from: from.map(|from| from.snapshot),
+            to: to.snapshot,
+            diff,
+        })
+    }
+}
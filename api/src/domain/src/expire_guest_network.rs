@@ -0,0 +1,71 @@
+use std::{sync::Arc, time::Instant};
+
+use ports::{
+    api::RouterApi,
+    repositories::{GuestNetworkRepository, UnitOfWorkProvider},
+};
+use tracing::{error, info, instrument};
+
+use crate::PeriodicUseCase;
+
+/// Disables the guest network once it expired, as routers cannot do it on their own.
+pub struct ExpireGuestNetworkUseCase<GR: GuestNetworkRepository<UWP>, UWP: UnitOfWorkProvider> {
+    _marker: std::marker::PhantomData<GR>,
+    router_api: Arc<dyn RouterApi>,
+    uow_provider: UWP,
+}
+
+impl<GR: GuestNetworkRepository<UWP>, UWP: UnitOfWorkProvider> ExpireGuestNetworkUseCase<GR, UWP> {
+    pub fn new(uow_provider: UWP, router_api: Arc<dyn RouterApi>) -> Self {
+        Self {
+            _marker: std::marker::PhantomData,
+            router_api,
+            uow_provider,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<GR: GuestNetworkRepository<UWP>, UWP: UnitOfWorkProvider + 'static> PeriodicUseCase
+    for ExpireGuestNetworkUseCase<GR, UWP>
+{
+    fn next_execution(&self) -> Option<Instant> {
+        Some(Instant::now() + std::time::Duration::from_secs(60))
+    }
+
+    #[instrument(skip(self), name = "ExpireGuestNetworkUseCase::execute")]
+    async fn execute(&self) {
+        let mut uow = match self.uow_provider.begin_transaction().await {
+            Ok(uow) => uow,
+            Err(err) => {
+                error!("Failed to begin transaction: {}", err);
+                return;
+            }
+        };
+
+        let expires_at = match GR::fetch_expiry(&mut uow).await {
+            Ok(Some(expires_at)) if expires_at <= chrono::Utc::now() => expires_at,
+            Ok(_) => return,
+            Err(err) => {
+                error!("Failed to fetch guest network expiry: {}", err);
+                return;
+            }
+        };
+
+        if let Err(err) = self.router_api.set_guest_network_enabled(false).await {
+            // Retried on the next execution
+            error!("Failed to disable guest network: {}", err);
+            return;
+        }
+
+        if let Err(err) = GR::delete_expiry(&mut uow).await {
+            error!("Failed to delete guest network expiry: {}", err);
+            return;
+        }
+
+        match self.uow_provider.commit(uow).await {
+            Ok(_) => info!(expires_at = ?expires_at, "Guest network expired and was disabled"),
+            Err(err) => error!("Failed to commit transaction: {}", err),
+        };
+    }
+}
@@ -0,0 +1,34 @@
+use entities::FullConfigSnapshot;
+use ports::repositories::{ConfigSnapshotsRepository, UnitOfWorkProvider};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::ConfigSnapshotError;
+
+#[derive(Clone)]
+pub struct FetchConfigSnapshotUseCase<CR: ConfigSnapshotsRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    _marker: std::marker::PhantomData<CR>,
+}
+
+impl<CR: ConfigSnapshotsRepository<UWP>, UWP: UnitOfWorkProvider>
+    FetchConfigSnapshotUseCase<CR, UWP>
+{
+    pub fn new(uow_provider: UWP) -> Self {
+        Self {
+            uow_provider,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    #[instrument(skip(self), name = "FetchConfigSnapshotUseCase::execute")]
+    pub async fn execute(
+        &self,
+        snapshot_id: Uuid,
+    ) -> Result<FullConfigSnapshot, ConfigSnapshotError> {
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        CR::fetch_one(&mut uow, snapshot_id)
+            .await?
+            .ok_or(ConfigSnapshotError::SnapshotNotFound)
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::Utc;
+use entities::{BillingPeriod, DataUsage, DataUsagePeriod};
+use ports::repositories::{DataUsageRepository, RepositoryResult, UnitOfWorkProvider};
+use tracing::instrument;
+
+#[derive(Clone)]
+pub struct FetchDataUsageUseCase<UR: DataUsageRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    reset_day: u32,
+    quota: Option<u64>,
+    _marker: std::marker::PhantomData<UR>,
+}
+
+impl<UR: DataUsageRepository<UWP>, UWP: UnitOfWorkProvider> FetchDataUsageUseCase<UR, UWP> {
+    pub fn new(uow_provider: UWP, reset_day: u32, quota: Option<u64>) -> Self {
+        Self {
+            uow_provider,
+            reset_day,
+            quota: quota.filter(|quota| *quota > 0),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Fetches the usage of the current billing period, projected to its end.
+    #[instrument(skip(self), name = "FetchDataUsageUseCase::execute")]
+    pub async fn execute(&self) -> RepositoryResult<DataUsage> {
+        let now = Utc::now();
+        let period = BillingPeriod::containing(now.date_naive(), self.reset_day);
+
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        let usage = UR::fetch_usage(&mut uow, period.start)
+            .await?
+            .unwrap_or(DataUsagePeriod {
+                period,
+                download_bytes: 0,
+                upload_bytes: 0,
+            });
+        let alerts = UR::fetch_alerts(&mut uow, period.start).await?;
+
+        let total_bytes = usage.total_bytes();
+        let projected_bytes = usage.projected_bytes(now);
+        let percent_of_quota =
+            |bytes: u64| self.quota.map(|quota| bytes as f64 * 100.0 / quota as f64);
+
+        Ok(DataUsage {
+            period_start: usage.period.start,
+            period_end: usage.period.end,
+            download_bytes: usage.download_bytes,
+            upload_bytes: usage.upload_bytes,
+            total_bytes,
+            projected_bytes,
+            quota_bytes: self.quota,
+            used_percent: percent_of_quota(total_bytes),
+            projected_percent: percent_of_quota(projected_bytes),
+            alerts,
+        })
+    }
+}
@@ -0,0 +1,62 @@
+use entities::{AccessState, FullDevice};
+use mac_address::MacAddress;
+use ports::repositories::{
+    DeviceAccessRepository, DeviceConnectionsRepository, DevicesRepository, ServicesRepository,
+    UnitOfWorkProvider,
+};
+use tracing::instrument;
+
+use crate::DeviceError;
+
+#[derive(Clone)]
+pub struct FetchDeviceUseCase<
+    DR: DevicesRepository<UWP>,
+    SR: ServicesRepository<UWP>,
+    AR: DeviceAccessRepository<UWP>,
+    CR: DeviceConnectionsRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> {
+    uow_provider: UWP,
+    _marker: std::marker::PhantomData<(DR, SR, AR, CR)>,
+}
+
+impl<
+    DR: DevicesRepository<UWP>,
+    SR: ServicesRepository<UWP>,
+    AR: DeviceAccessRepository<UWP>,
+    CR: DeviceConnectionsRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> FetchDeviceUseCase<DR, SR, AR, CR, UWP>
+{
+    pub fn new(uow_provider: UWP) -> Self {
+        Self {
+            uow_provider,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the device with its services.
+    #[instrument(skip(self), name = "FetchDeviceUseCase::execute")]
+    pub async fn execute(&self, mac_address: MacAddress) -> Result<FullDevice, DeviceError> {
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        let device = DR::fetch_one(&mut uow, mac_address)
+            .await?
+            .ok_or(DeviceError::DeviceNotFound)?;
+
+        let access = AR::fetch_one(&mut uow, mac_address).await?;
+        let connection = CR::fetch_one(&mut uow, mac_address).await?;
+        let services = SR::fetch_all_of_device(&mut uow, mac_address).await?;
+
+        // Schedules are set in the local time of the router, assumed to be the one of the host
+        let now = chrono::Local::now().naive_local();
+        Ok(FullDevice {
+            access_state: access
+                .as_ref()
+                .map_or(AccessState::Allowed, |access| access.state_at(now)),
+            access,
+            connection,
+            device,
+            services: Some(services),
+        })
+    }
+}
@@ -0,0 +1,43 @@
+use entities::DeviceAccess;
+use mac_address::MacAddress;
+use ports::repositories::{DeviceAccessRepository, DevicesRepository, UnitOfWorkProvider};
+use tracing::instrument;
+
+use crate::DeviceAccessError;
+
+#[derive(Clone)]
+pub struct FetchDeviceAccessUseCase<
+    DR: DevicesRepository<UWP>,
+    AR: DeviceAccessRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> {
+    uow_provider: UWP,
+    _marker: std::marker::PhantomData<(DR, AR)>,
+}
+
+impl<DR: DevicesRepository<UWP>, AR: DeviceAccessRepository<UWP>, UWP: UnitOfWorkProvider>
+    FetchDeviceAccessUseCase<DR, AR, UWP>
+{
+    pub fn new(uow_provider: UWP) -> Self {
+        Self {
+            uow_provider,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the access rules of the device, as of the last sync with the router.
+    #[instrument(skip(self), name = "FetchDeviceAccessUseCase::execute")]
+    pub async fn execute(
+        &self,
+        mac_address: MacAddress,
+    ) -> Result<DeviceAccess, DeviceAccessError> {
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        if DR::fetch_one(&mut uow, mac_address).await?.is_none() {
+            return Err(DeviceAccessError::DeviceNotFound);
+        }
+
+        Ok(AR::fetch_one(&mut uow, mac_address)
+            .await?
+            .unwrap_or_else(|| DeviceAccess::allowed(mac_address)))
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use entities::StaticLease;
+use mac_address::MacAddress;
+use ports::api::RouterApi;
+use tracing::instrument;
+
+use crate::DeviceReservationError;
+
+#[derive(Clone)]
+pub struct FetchDeviceReservationUseCase {
+    router_api: Arc<dyn RouterApi>,
+}
+
+impl FetchDeviceReservationUseCase {
+    pub fn new(router_api: Arc<dyn RouterApi>) -> Self {
+        Self { router_api }
+    }
+
+    #[instrument(skip(self), name = "FetchDeviceReservationUseCase::execute")]
+    pub async fn execute(
+        &self,
+        mac_address: MacAddress,
+    ) -> Result<StaticLease, DeviceReservationError> {
+        self.router_api
+            .list_static_leases()
+            .await?
+            .into_iter()
+            .find(|lease| lease.mac_address == mac_address)
+            .ok_or(DeviceReservationError::ReservationNotFound)
+    }
+}
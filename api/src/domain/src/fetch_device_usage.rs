@@ -0,0 +1,75 @@
+use chrono::{DateTime, Days, Utc};
+use entities::{DeviceUsageHistory, UsageInterval};
+use mac_address::MacAddress;
+use ports::repositories::{DeviceTrafficRepository, DevicesRepository, UnitOfWorkProvider};
+use tracing::instrument;
+
+use crate::DeviceTrafficError;
+
+/// Range used when the request does not specify one.
+const DEFAULT_RANGE_DAYS: u64 = 1;
+
+/// Longest range split in hours by default, longer ones are split in days.
+const MAX_HOURLY_RANGE_DAYS: i64 = 2;
+
+#[derive(Clone)]
+pub struct FetchDeviceUsageUseCase<
+    DR: DevicesRepository<UWP>,
+    TR: DeviceTrafficRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> {
+    uow_provider: UWP,
+    _marker: std::marker::PhantomData<(DR, TR)>,
+}
+
+impl<DR: DevicesRepository<UWP>, TR: DeviceTrafficRepository<UWP>, UWP: UnitOfWorkProvider>
+    FetchDeviceUsageUseCase<DR, TR, UWP>
+{
+    pub fn new(uow_provider: UWP) -> Self {
+        Self {
+            uow_provider,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the traffic of the device between `from` (a day ago by default) and `to` (now by
+    /// default), split in hours or days depending on the length of the range.
+    #[instrument(skip(self), name = "FetchDeviceUsageUseCase::execute")]
+    pub async fn execute(
+        &self,
+        mac_address: MacAddress,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        interval: Option<UsageInterval>,
+    ) -> Result<DeviceUsageHistory, DeviceTrafficError> {
+        let to = to.unwrap_or_else(Utc::now);
+        let from = from.unwrap_or(to - Days::new(DEFAULT_RANGE_DAYS));
+        if from >= to {
+            return Err(DeviceTrafficError::InvalidRange);
+        }
+
+        let interval = interval.unwrap_or(
+            if to - from <= chrono::Duration::days(MAX_HOURLY_RANGE_DAYS) {
+                UsageInterval::Hour
+            } else {
+                UsageInterval::Day
+            },
+        );
+
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        if DR::fetch_one(&mut uow, mac_address).await?.is_none() {
+            return Err(DeviceTrafficError::DeviceNotFound);
+        }
+
+        let buckets = TR::fetch_usage(&mut uow, mac_address, from, to, interval).await?;
+
+        Ok(DeviceUsageHistory {
+            mac_address,
+            from,
+            to,
+            rx_bytes: buckets.iter().map(|bucket| bucket.rx_bytes).sum(),
+            tx_bytes: buckets.iter().map(|bucket| bucket.tx_bytes).sum(),
+            buckets,
+        })
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use entities::GuestNetwork;
+use ports::{
+    api::RouterApi,
+    repositories::{GuestNetworkRepository, UnitOfWorkProvider},
+};
+use tracing::instrument;
+
+use crate::GuestNetworkError;
+
+#[derive(Clone)]
+pub struct FetchGuestNetworkUseCase<GR: GuestNetworkRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    router_api: Arc<dyn RouterApi>,
+    _marker: std::marker::PhantomData<GR>,
+}
+
+impl<GR: GuestNetworkRepository<UWP>, UWP: UnitOfWorkProvider> FetchGuestNetworkUseCase<GR, UWP> {
+    pub fn new(uow_provider: UWP, router_api: Arc<dyn RouterApi>) -> Self {
+        Self {
+            uow_provider,
+            router_api,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    #[instrument(skip(self), name = "FetchGuestNetworkUseCase::execute")]
+    pub async fn execute(&self) -> Result<GuestNetwork, GuestNetworkError> {
+        let mut guest_network = self.router_api.guest_network().await?;
+
+        if guest_network.is_enabled {
+            let mut uow = self.uow_provider.begin_transaction().await?;
+            guest_network.expires_at = GR::fetch_expiry(&mut uow).await?;
+        }
+
+        Ok(guest_network)
+    }
+}
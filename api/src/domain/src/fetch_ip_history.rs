@@ -0,0 +1,38 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use entities::IpHistory;
+use ports::repositories::{IpHistoryRepository, RepositoryResult, UnitOfWorkProvider};
+use tracing::instrument;
+
+#[derive(Clone)]
+pub struct FetchIpHistoryUseCase<IR: IpHistoryRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    _marker: std::marker::PhantomData<IR>,
+}
+
+impl<IR: IpHistoryRepository<UWP>, UWP: UnitOfWorkProvider> FetchIpHistoryUseCase<IR, UWP> {
+    pub fn new(uow_provider: UWP) -> Self {
+        Self {
+            uow_provider,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the devices that held the address, at the given time if set.
+    #[instrument(skip(self), name = "FetchIpHistoryUseCase::execute")]
+    pub async fn execute(
+        &self,
+        ip_address: IpAddr,
+        at: Option<DateTime<Utc>>,
+    ) -> RepositoryResult<IpHistory> {
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        let assignments = IR::fetch_by_ip(&mut uow, ip_address, at).await?;
+
+        Ok(IpHistory {
+            ip_address,
+            at,
+            assignments,
+        })
+    }
+}
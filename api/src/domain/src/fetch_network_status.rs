@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use entities::NetworkStatus;
-use ports::api::{RouterApi, RouterApiResult};
+use ports::api::{RouterApi, RouterApiResult, RouterApiResultExt};
 use tracing::instrument;
 
 #[derive(Clone)]
@@ -16,8 +16,8 @@ impl FetchNetworkStatusUseCase {
 
     #[instrument(skip(self), name = "FetchNetworkStatusUseCase::execute")]
     pub async fn execute(&self) -> RouterApiResult<NetworkStatus> {
-        let stats = self.router_api.wan_stats().await?;
-        let connectivity = self.router_api.wan_connectivity().await?;
+        let stats = self.router_api.wan_stats().await.supported()?;
+        let connectivity = self.router_api.wan_connectivity().await.supported()?;
 
         Ok(NetworkStatus {
             stats,
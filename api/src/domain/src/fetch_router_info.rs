@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use entities::RouterInfo;
+use ports::api::{RouterApi, RouterApiResult};
+use tracing::instrument;
+
+#[derive(Clone)]
+pub struct FetchRouterInfoUseCase {
+    router_api: Arc<dyn RouterApi>,
+}
+
+impl FetchRouterInfoUseCase {
+    pub fn new(router_api: Arc<dyn RouterApi>) -> Self {
+        Self { router_api }
+    }
+
+    #[instrument(skip(self), name = "FetchRouterInfoUseCase::execute")]
+    pub async fn execute(&self) -> RouterApiResult<RouterInfo> {
+        self.router_api.router_info().await
+    }
+}
@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use ports::api::RouterApi;
+use tracing::instrument;
+
+use crate::GuestNetworkError;
+
+#[derive(Clone)]
+pub struct GenerateGuestNetworkQrUseCase {
+    router_api: Arc<dyn RouterApi>,
+}
+
+impl GenerateGuestNetworkQrUseCase {
+    pub fn new(router_api: Arc<dyn RouterApi>) -> Self {
+        Self { router_api }
+    }
+
+    /// Returns the payload of a QR code joining the guest network.
+    #[instrument(skip(self), name = "GenerateGuestNetworkQrUseCase::execute")]
+    pub async fn execute(&self) -> Result<String, GuestNetworkError> {
+        let guest_network = self.router_api.guest_network().await?;
+        if !guest_network.is_enabled {
+            return Err(GuestNetworkError::Disabled);
+        }
+
+        Ok(guest_network.qr_payload())
+    }
+}
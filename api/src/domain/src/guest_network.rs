@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use ports::{api::RouterApiError, repositories::RepositoryError};
+use serde::Deserialize;
+use thiserror::Error;
+use validator::Validate;
+
+#[derive(Error, Debug)]
+pub enum GuestNetworkError {
+    #[error("The guest network is disabled")]
+    Disabled,
+    #[error("The expiry date is in the past")]
+    ExpiryInPast,
+    #[error("A router API error occurred: {0}")]
+    RouterApiError(#[from] RouterApiError),
+    #[error("A database error occurred: {0}.")]
+    DatabaseError(#[from] RepositoryError),
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateGuestNetwork {
+    pub is_enabled: bool,
+
+    #[validate(length(min = 1, max = 32))]
+    pub ssid: Option<String>, // None to keep the current one
+
+    #[validate(length(min = 8, max = 63))]
+    pub passphrase: Option<String>, // None to keep the current one
+
+    pub expires_at: Option<DateTime<Utc>>, // None if the network does not expire
+}
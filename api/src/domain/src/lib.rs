@@ -1,20 +1,102 @@
+mod config_snapshots;
+mod create_port_forward;
 mod create_service;
+mod delete_device;
+mod delete_port_forward;
+mod device_access;
+mod device_reservations;
+mod device_traffic;
+mod devices;
+mod diff_config_snapshots;
+mod expire_guest_network;
+mod fetch_config_snapshot;
+mod fetch_data_usage;
+mod fetch_device;
+mod fetch_device_access;
+mod fetch_device_reservation;
+mod fetch_device_usage;
+mod fetch_guest_network;
+mod fetch_ip_history;
 mod fetch_network_status;
+mod fetch_router_info;
+mod generate_guest_network_qr;
 mod generate_install_script;
+mod guest_network;
+mod list_config_snapshots;
+mod list_device_presence;
 mod list_devices;
+mod list_port_forwards;
 mod list_service_templates;
 mod list_services;
+mod list_top_talkers;
+mod list_wan_history;
+mod list_wan_outages;
+mod list_wifi_radios;
+mod port_forwards;
+mod reboot_router;
+mod record_data_usage;
+mod record_device_traffic;
+mod record_wan_outages;
+mod record_wan_stats;
+mod release_device_ip;
+mod reserve_device_ip;
 mod sync_devices;
+mod take_config_snapshots;
+mod update_device;
+mod update_device_access;
+mod update_guest_network;
+mod update_port_forward;
 
 use std::time::Instant;
 
+pub use config_snapshots::*;
+pub use create_port_forward::*;
 pub use create_service::*;
+pub use delete_device::*;
+pub use delete_port_forward::*;
+pub use device_access::*;
+pub use device_reservations::*;
+pub use device_traffic::*;
+pub use devices::*;
+pub use diff_config_snapshots::*;
+pub use expire_guest_network::*;
+pub use fetch_config_snapshot::*;
+pub use fetch_data_usage::*;
+pub use fetch_device::*;
+pub use fetch_device_access::*;
+pub use fetch_device_reservation::*;
+pub use fetch_device_usage::*;
+pub use fetch_guest_network::*;
+pub use fetch_ip_history::*;
 pub use fetch_network_status::*;
+pub use fetch_router_info::*;
+pub use generate_guest_network_qr::*;
 pub use generate_install_script::*;
+pub use guest_network::*;
+pub use list_config_snapshots::*;
+pub use list_device_presence::*;
 pub use list_devices::*;
+pub use list_port_forwards::*;
 pub use list_service_templates::*;
 pub use list_services::*;
+pub use list_top_talkers::*;
+pub use list_wan_history::*;
+pub use list_wan_outages::*;
+pub use list_wifi_radios::*;
+pub use port_forwards::*;
+pub use reboot_router::*;
+pub use record_data_usage::*;
+pub use record_device_traffic::*;
+pub use record_wan_outages::*;
+pub use record_wan_stats::*;
+pub use release_device_ip::*;
+pub use reserve_device_ip::*;
 pub use sync_devices::*;
+pub use take_config_snapshots::*;
+pub use update_device::*;
+pub use update_device_access::*;
+pub use update_guest_network::*;
+pub use update_port_forward::*;
 
 #[async_trait::async_trait]
 pub trait PeriodicUseCase {
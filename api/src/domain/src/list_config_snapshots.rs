@@ -0,0 +1,26 @@
+use entities::ConfigSnapshot;
+use ports::repositories::{ConfigSnapshotsRepository, RepositoryResult, UnitOfWorkProvider};
+use tracing::instrument;
+
+#[derive(Clone)]
+pub struct ListConfigSnapshotsUseCase<CR: ConfigSnapshotsRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    _marker: std::marker::PhantomData<CR>,
+}
+
+impl<CR: ConfigSnapshotsRepository<UWP>, UWP: UnitOfWorkProvider>
+    ListConfigSnapshotsUseCase<CR, UWP>
+{
+    pub fn new(uow_provider: UWP) -> Self {
+        Self {
+            uow_provider,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    #[instrument(skip(self), name = "ListConfigSnapshotsUseCase::execute")]
+    pub async fn execute(&self) -> RepositoryResult<Vec<ConfigSnapshot>> {
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        CR::fetch_all(&mut uow).await
+    }
+}
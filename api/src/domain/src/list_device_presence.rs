@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Days, Utc};
+use entities::{DevicePresence, DeviceQuery, PresenceHistory};
+use mac_address::MacAddress;
+use ports::repositories::{
+    DevicesRepository, PresenceEventsRepository, RepositoryError, UnitOfWorkProvider,
+};
+use thiserror::Error;
+use tracing::instrument;
+
+/// Range used when the request does not specify one.
+const DEFAULT_RANGE_DAYS: u64 = 7;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ListDevicePresenceError {
+    #[error("The start of the range must be before its end.")]
+    InvalidRange,
+
+    #[error("The device does not exist.")]
+    DeviceNotFound,
+
+    #[error("A database error occurred: {0}.")]
+    DatabaseError(#[from] RepositoryError),
+}
+
+#[derive(Clone)]
+pub struct ListDevicePresenceUseCase<
+    DR: DevicesRepository<UWP>,
+    ER: PresenceEventsRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> {
+    uow_provider: UWP,
+    _marker: std::marker::PhantomData<(DR, ER)>,
+}
+
+impl<DR: DevicesRepository<UWP>, ER: PresenceEventsRepository<UWP>, UWP: UnitOfWorkProvider>
+    ListDevicePresenceUseCase<DR, ER, UWP>
+{
+    pub fn new(uow_provider: UWP) -> Self {
+        Self {
+            uow_provider,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the online intervals of the device, or of every device if `None`, between `from`
+    /// (a week ago by default) and `to` (now by default).
+    #[instrument(skip(self), name = "ListDevicePresenceUseCase::execute")]
+    pub async fn execute(
+        &self,
+        mac_address: Option<MacAddress>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<PresenceHistory, ListDevicePresenceError> {
+        let now = Utc::now();
+        let to = to.unwrap_or(now);
+        let from = from.unwrap_or(to - Days::new(DEFAULT_RANGE_DAYS));
+        if from >= to {
+            return Err(ListDevicePresenceError::InvalidRange);
+        }
+
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        let devices = match mac_address {
+            Some(mac_address) => vec![
+                DR::fetch_one(&mut uow, mac_address)
+                    .await?
+                    .ok_or(ListDevicePresenceError::DeviceNotFound)?,
+            ],
+            None => DR::fetch_all(&mut uow, DeviceQuery::default()).await?,
+        };
+
+        let first_seen = ER::fetch_first_seen(&mut uow, mac_address)
+            .await?
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        let mut events = HashMap::<_, Vec<_>>::new();
+        for event in ER::fetch_between(&mut uow, mac_address, from, to).await? {
+            events.entry(event.mac_address).or_default().push(event);
+        }
+
+        let devices = devices
+            .into_iter()
+            .map(|device| {
+                DevicePresence::from_events(
+                    device.mac_address,
+                    device.display_name,
+                    first_seen
+                        .get(&device.mac_address)
+                        .copied()
+                        .unwrap_or(device.last_seen),
+                    events
+                        .get(&device.mac_address)
+                        .map(Vec::as_slice)
+                        .unwrap_or_default(),
+                    from,
+                    to,
+                    now,
+                )
+            })
+            .collect();
+
+        Ok(PresenceHistory { from, to, devices })
+    }
+}
@@ -1,6 +1,9 @@
-use entities::{FullDevice, Pagination};
+use std::collections::HashMap;
+
+use entities::{AccessState, DeviceQuery, FullDevice, PageMeta, Paged};
 use ports::repositories::{
-    DevicesRepository, RepositoryResult, ServicesRepository, UnitOfWorkProvider,
+    DeviceAccessRepository, DeviceConnectionsRepository, DevicesRepository, RepositoryResult,
+    ServicesRepository, UnitOfWorkProvider,
 };
 use tracing::instrument;
 
@@ -8,14 +11,21 @@ use tracing::instrument;
 pub struct ListDevicesUseCase<
     DR: DevicesRepository<UWP>,
     SR: ServicesRepository<UWP>,
+    AR: DeviceAccessRepository<UWP>,
+    CR: DeviceConnectionsRepository<UWP>,
     UWP: UnitOfWorkProvider,
 > {
     uow_provider: UWP,
-    _marker: std::marker::PhantomData<(DR, SR)>,
+    _marker: std::marker::PhantomData<(DR, SR, AR, CR)>,
 }
 
-impl<DR: DevicesRepository<UWP>, SR: ServicesRepository<UWP>, UWP: UnitOfWorkProvider>
-    ListDevicesUseCase<DR, SR, UWP>
+impl<
+    DR: DevicesRepository<UWP>,
+    SR: ServicesRepository<UWP>,
+    AR: DeviceAccessRepository<UWP>,
+    CR: DeviceConnectionsRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> ListDevicesUseCase<DR, SR, AR, CR, UWP>
 {
     pub fn new(uow_provider: UWP) -> Self {
         Self {
@@ -27,31 +37,78 @@ impl<DR: DevicesRepository<UWP>, SR: ServicesRepository<UWP>, UWP: UnitOfWorkPro
     #[instrument(skip(self), name = "ListDevicesUseCase::execute")]
     pub async fn execute(
         &self,
-        pagination: Option<Pagination>,
+        query: DeviceQuery,
         full: bool,
-    ) -> RepositoryResult<Vec<FullDevice>> {
+    ) -> RepositoryResult<Paged<FullDevice>> {
         let mut uow = self.uow_provider.begin_transaction().await?;
-        let devices = DR::fetch_all(&mut uow, pagination).await?;
+        let total = DR::count(&mut uow, query.filter.clone()).await?;
+        let (sort, paging) = (query.sort, query.paging.clone());
+        let devices = DR::fetch_all(&mut uow, query).await?;
+        let meta = PageMeta::of(&devices, total, paging.as_ref(), |device| {
+            sort.field.cursor_of(device)
+        });
 
         if devices.is_empty() {
-            return Ok(vec![]);
+            return Ok(Paged {
+                items: vec![],
+                meta,
+            });
         }
 
+        let mut accesses = AR::fetch_all(&mut uow)
+            .await?
+            .into_iter()
+            .map(|access| (access.mac_address, access))
+            .collect::<HashMap<_, _>>();
+        let mut connections = CR::fetch_all(&mut uow)
+            .await?
+            .into_iter()
+            .map(|connection| (connection.mac_address, connection))
+            .collect::<HashMap<_, _>>();
+
+        // Schedules are set in the local time of the router, assumed to be the one of the host
+        let now = chrono::Local::now().naive_local();
         let mut devices = devices
             .into_iter()
-            .map(|device| FullDevice {
-                device,
-                services: None,
+            .map(|device| {
+                let access = accesses.remove(&device.mac_address);
+                FullDevice {
+                    access_state: access
+                        .as_ref()
+                        .map_or(AccessState::Allowed, |access| access.state_at(now)),
+                    access,
+                    connection: connections.remove(&device.mac_address),
+                    device,
+                    services: None,
+                }
             })
             .collect::<Vec<_>>();
 
         if full {
+            let mac_addresses = devices
+                .iter()
+                .map(|device| device.device.mac_address)
+                .collect::<Vec<_>>();
+            let mut services = HashMap::<_, Vec<_>>::new();
+            for service in SR::fetch_all_of_devices(&mut uow, &mac_addresses).await? {
+                services
+                    .entry(service.device_mac)
+                    .or_default()
+                    .push(service);
+            }
+
             for device in &mut devices {
-                device.services =
-                    Some(SR::fetch_all_of_device(&mut uow, device.device.mac_address).await?);
+                device.services = Some(
+                    services
+                        .remove(&device.device.mac_address)
+                        .unwrap_or_default(),
+                );
             }
         }
 
-        Ok(devices)
+        Ok(Paged {
+            items: devices,
+            meta,
+        })
     }
 }
@@ -0,0 +1,49 @@
+use std::{collections::HashMap, sync::Arc};
+
+use entities::FullPortForward;
+use ports::{
+    api::RouterApi,
+    repositories::{PortForwardLinksRepository, UnitOfWorkProvider},
+};
+use tracing::instrument;
+
+use crate::PortForwardError;
+
+#[derive(Clone)]
+pub struct ListPortForwardsUseCase<LR: PortForwardLinksRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    router_api: Arc<dyn RouterApi>,
+    _marker: std::marker::PhantomData<LR>,
+}
+
+impl<LR: PortForwardLinksRepository<UWP>, UWP: UnitOfWorkProvider>
+    ListPortForwardsUseCase<LR, UWP>
+{
+    pub fn new(uow_provider: UWP, router_api: Arc<dyn RouterApi>) -> Self {
+        Self {
+            uow_provider,
+            router_api,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    #[instrument(skip(self), name = "ListPortForwardsUseCase::execute")]
+    pub async fn execute(&self) -> Result<Vec<FullPortForward>, PortForwardError> {
+        let port_forwards = self.router_api.list_port_forwards().await?;
+
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        let mut links = LR::fetch_all(&mut uow)
+            .await?
+            .into_iter()
+            .map(|link| (link.port_forward_id.clone(), link))
+            .collect::<HashMap<_, _>>();
+
+        Ok(port_forwards
+            .into_iter()
+            .map(|port_forward| FullPortForward {
+                service: links.remove(&port_forward.port_forward_id),
+                port_forward,
+            })
+            .collect())
+    }
+}
@@ -8,7 +8,7 @@ impl ListServiceTemplatesUseCase {
     #[instrument(skip(self), name = "ListServiceTemplatesUseCase::execute")]
     pub async fn execute(&self) -> Vec<ServiceTemplate> {
         ServiceKind::variants()
-            .into_iter()
+            .iter()
             .cloned()
             .map(Into::into)
             .collect()
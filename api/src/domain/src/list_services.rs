@@ -1,5 +1,4 @@
-use entities::Service;
-use mac_address::MacAddress;
+use entities::{PageMeta, Paged, Service, ServiceQuery};
 use ports::repositories::{RepositoryResult, ServicesRepository, UnitOfWorkProvider};
 use tracing::instrument;
 
@@ -18,9 +17,18 @@ impl<SR: ServicesRepository<UWP>, UWP: UnitOfWorkProvider> ListServicesUseCase<S
     }
 
     #[instrument(skip(self), name = "ListServicesUseCase::execute")]
-    pub async fn execute(&self, mac_address: MacAddress) -> RepositoryResult<Vec<Service>> {
+    pub async fn execute(&self, query: ServiceQuery) -> RepositoryResult<Paged<Service>> {
         let mut uwo = self.uow_provider.begin_transaction().await?;
-        let devices = SR::fetch_all_of_device(&mut uwo, mac_address).await?;
-        Ok(devices)
+        let total = SR::count(&mut uwo, query.filter.clone()).await?;
+        let (sort, paging) = (query.sort, query.paging.clone());
+        let services = SR::fetch_all(&mut uwo, query).await?;
+        let meta = PageMeta::of(&services, total, paging.as_ref(), |service| {
+            sort.field.cursor_of(service)
+        });
+
+        Ok(Paged {
+            items: services,
+            meta,
+        })
     }
 }
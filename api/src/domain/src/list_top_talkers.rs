@@ -0,0 +1,47 @@
+use chrono::{DateTime, Days, Utc};
+use entities::TopTalkers;
+use ports::repositories::{DeviceTrafficRepository, UnitOfWorkProvider};
+use tracing::instrument;
+
+use crate::DeviceTrafficError;
+
+/// Range used when the request does not specify one.
+const DEFAULT_RANGE_DAYS: u64 = 1;
+const DEFAULT_LIMIT: usize = 10;
+
+#[derive(Clone)]
+pub struct ListTopTalkersUseCase<TR: DeviceTrafficRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    _marker: std::marker::PhantomData<TR>,
+}
+
+impl<TR: DeviceTrafficRepository<UWP>, UWP: UnitOfWorkProvider> ListTopTalkersUseCase<TR, UWP> {
+    pub fn new(uow_provider: UWP) -> Self {
+        Self {
+            uow_provider,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Lists the devices with the most traffic between `from` (a day ago by default) and `to` (now
+    /// by default).
+    #[instrument(skip(self), name = "ListTopTalkersUseCase::execute")]
+    pub async fn execute(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: Option<usize>,
+    ) -> Result<TopTalkers, DeviceTrafficError> {
+        let to = to.unwrap_or_else(Utc::now);
+        let from = from.unwrap_or(to - Days::new(DEFAULT_RANGE_DAYS));
+        if from >= to {
+            return Err(DeviceTrafficError::InvalidRange);
+        }
+
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        let devices =
+            TR::fetch_top_talkers(&mut uow, from, to, limit.unwrap_or(DEFAULT_LIMIT)).await?;
+
+        Ok(TopTalkers { from, to, devices })
+    }
+}
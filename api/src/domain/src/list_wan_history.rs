@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use entities::{WanHistory, WanStatsBucket};
+use ports::repositories::{RepositoryError, UnitOfWorkProvider, WanStatsRepository};
+use thiserror::Error;
+use tracing::instrument;
+
+/// Range used when the request does not specify one.
+const DEFAULT_RANGE_HOURS: i64 = 24;
+
+/// Longest ranges split in minutes and hours by default, longer ones are split in days.
+const MAX_MINUTE_RANGE_HOURS: i64 = 6;
+const MAX_HOUR_RANGE_DAYS: i64 = 7;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ListWanHistoryError {
+    #[error("The start of the range must be before its end.")]
+    InvalidRange,
+
+    #[error("A database error occurred: {0}.")]
+    DatabaseError(#[from] RepositoryError),
+}
+
+#[derive(Clone)]
+pub struct ListWanHistoryUseCase<WR: WanStatsRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    _marker: std::marker::PhantomData<WR>,
+}
+
+impl<WR: WanStatsRepository<UWP>, UWP: UnitOfWorkProvider> ListWanHistoryUseCase<WR, UWP> {
+    pub fn new(uow_provider: UWP) -> Self {
+        Self {
+            uow_provider,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Lists the WAN bandwidth between `from` (a day ago by default) and `to` (now by default), in
+    /// buckets sized after the length of the range unless specified.
+    #[instrument(skip(self), name = "ListWanHistoryUseCase::execute")]
+    pub async fn execute(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        bucket: Option<WanStatsBucket>,
+    ) -> Result<WanHistory, ListWanHistoryError> {
+        let to = to.unwrap_or_else(Utc::now);
+        let from = from.unwrap_or(to - chrono::Duration::hours(DEFAULT_RANGE_HOURS));
+        if from >= to {
+            return Err(ListWanHistoryError::InvalidRange);
+        }
+
+        let bucket = bucket.unwrap_or(match to - from {
+            range if range <= chrono::Duration::hours(MAX_MINUTE_RANGE_HOURS) => {
+                WanStatsBucket::Minute
+            }
+            range if range <= chrono::Duration::days(MAX_HOUR_RANGE_DAYS) => WanStatsBucket::Hour,
+            _ => WanStatsBucket::Day,
+        });
+
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        let points = WR::fetch_history(&mut uow, bucket, from, to).await?;
+
+        Ok(WanHistory {
+            from,
+            to,
+            bucket,
+            points,
+        })
+    }
+}
@@ -0,0 +1,105 @@
+use chrono::{DateTime, Days, Utc};
+use entities::{DailyWanAvailability, WanAvailability, WanOutage};
+use ports::repositories::{RepositoryError, UnitOfWorkProvider, WanOutagesRepository};
+use thiserror::Error;
+use tracing::instrument;
+
+/// Range used when the request does not specify one.
+const DEFAULT_RANGE_DAYS: u64 = 7;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ListWanOutagesError {
+    #[error("The start of the range must be before its end.")]
+    InvalidRange,
+
+    #[error("A database error occurred: {0}.")]
+    DatabaseError(#[from] RepositoryError),
+}
+
+#[derive(Clone)]
+pub struct ListWanOutagesUseCase<OR: WanOutagesRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    _marker: std::marker::PhantomData<OR>,
+}
+
+/// Returns the share of `period` during which the WAN was up, in percent.
+fn availability(downtime: chrono::Duration, period: chrono::Duration) -> f64 {
+    if period <= chrono::Duration::zero() {
+        return 100.0;
+    }
+
+    100.0 * (1.0 - downtime.as_seconds_f64() / period.as_seconds_f64()).clamp(0.0, 1.0)
+}
+
+fn downtime_between(
+    outages: &[WanOutage],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> chrono::Duration {
+    outages
+        .iter()
+        .map(|outage| outage.downtime_between(from, to, now))
+        .sum()
+}
+
+impl<OR: WanOutagesRepository<UWP>, UWP: UnitOfWorkProvider> ListWanOutagesUseCase<OR, UWP> {
+    pub fn new(uow_provider: UWP) -> Self {
+        Self {
+            uow_provider,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Lists the outages between `from` (a week ago by default) and `to` (now by default), along
+    /// with the availability of the WAN over the range and each of its days.
+    #[instrument(skip(self), name = "ListWanOutagesUseCase::execute")]
+    pub async fn execute(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<WanAvailability, ListWanOutagesError> {
+        let now = Utc::now();
+        let to = to.unwrap_or(now);
+        let from = from.unwrap_or(to - Days::new(DEFAULT_RANGE_DAYS));
+        if from >= to {
+            return Err(ListWanOutagesError::InvalidRange);
+        }
+
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        let outages = OR::fetch_between(&mut uow, from, to).await?;
+
+        // The future cannot be accounted for
+        let end = to.min(now);
+        let downtime = downtime_between(&outages, from, end, now);
+
+        let mut daily = Vec::new();
+        let mut day = from.date_naive();
+        loop {
+            let day_start = day.and_hms_opt(0, 0, 0).unwrap().and_utc().max(from);
+            if day_start >= end {
+                break;
+            }
+
+            let next_day = day + Days::new(1);
+            let day_end = next_day.and_hms_opt(0, 0, 0).unwrap().and_utc().min(end);
+            daily.push(DailyWanAvailability {
+                date: day,
+                availability: availability(
+                    downtime_between(&outages, day_start, day_end, now),
+                    day_end - day_start,
+                ),
+            });
+            day = next_day;
+        }
+
+        Ok(WanAvailability {
+            from,
+            to,
+            availability: availability(downtime, end - from),
+            downtime,
+            daily,
+            outages,
+        })
+    }
+}
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use entities::WifiRadio;
+use ports::api::{RouterApi, RouterApiResult};
+use tracing::instrument;
+
+#[derive(Clone)]
+pub struct ListWifiRadiosUseCase {
+    router_api: Arc<dyn RouterApi>,
+}
+
+impl ListWifiRadiosUseCase {
+    pub fn new(router_api: Arc<dyn RouterApi>) -> Self {
+        Self { router_api }
+    }
+
+    #[instrument(skip(self), name = "ListWifiRadiosUseCase::execute")]
+    pub async fn execute(&self) -> RouterApiResult<Vec<WifiRadio>> {
+        self.router_api.list_wifi_radios().await
+    }
+}
@@ -0,0 +1,70 @@
+use entities::{PortForwardLink, PortForwardRule};
+use ports::{
+    api::RouterApiError,
+    repositories::{DevicesRepository, RepositoryError, ServicesRepository, UnitOfWorkProvider},
+};
+use serde::Deserialize;
+use thiserror::Error;
+use validator::Validate;
+
+#[derive(Error, Debug)]
+pub enum PortForwardError {
+    #[error("The linked service does not exist")]
+    ServiceNotFound,
+    #[error("The linked service has no such port")]
+    ServicePortNotFound,
+    #[error("The rule does not forward traffic to the linked service port")]
+    ServicePortMismatch,
+    #[error("A router API error occurred: {0}")]
+    RouterApiError(#[from] RouterApiError),
+    #[error("A database error occurred: {0}.")]
+    DatabaseError(#[from] RepositoryError),
+}
+
+/// A port forwarding rule to create or update, optionally linked to the service port it exposes.
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SavePortForward {
+    #[serde(flatten)]
+    #[validate(nested)]
+    pub rule: PortForwardRule,
+    pub service: Option<PortForwardLink>,
+}
+
+/// Checks that the rule forwards traffic to the linked service port, on the device hosting it.
+pub(crate) async fn check_link<
+    SR: ServicesRepository<UWP>,
+    DR: DevicesRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+>(
+    uow: &mut UWP::UnitOfWork<'_>,
+    rule: &PortForwardRule,
+    link: &PortForwardLink,
+) -> Result<(), PortForwardError> {
+    let service = match SR::fetch_one(uow, link.service_id).await {
+        Ok(service) => service,
+        Err(RepositoryError::NotFound) => return Err(PortForwardError::ServiceNotFound),
+        Err(err) => return Err(err.into()),
+    };
+
+    if !service
+        .ports
+        .iter()
+        .any(|port| port.port == link.port && port.transport_protocol == link.transport_protocol)
+    {
+        return Err(PortForwardError::ServicePortNotFound);
+    }
+
+    let device = DR::fetch_one(uow, service.device_mac)
+        .await?
+        .ok_or(PortForwardError::ServiceNotFound)?;
+
+    if rule.internal_ip != device.last_known_ip
+        || rule.internal_port != link.port
+        || !rule.protocol.forwards(link.transport_protocol)
+    {
+        return Err(PortForwardError::ServicePortMismatch);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use ports::api::{RouterApi, RouterApiResult};
+use tracing::{info, instrument};
+
+#[derive(Clone)]
+pub struct RebootRouterUseCase {
+    router_api: Arc<dyn RouterApi>,
+}
+
+impl RebootRouterUseCase {
+    pub fn new(router_api: Arc<dyn RouterApi>) -> Self {
+        Self { router_api }
+    }
+
+    #[instrument(skip(self), name = "RebootRouterUseCase::execute")]
+    pub async fn execute(&self) -> RouterApiResult<()> {
+        self.router_api.reboot().await?;
+
+        info!("Router reboot requested");
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use entities::{BillingPeriod, DataUsageAlert, WanTrafficCounters};
+use ports::{
+    api::{RouterApi, RouterApiError},
+    repositories::{DataUsageRepository, UnitOfWorkProvider},
+};
+use tracing::{error, info, instrument, warn};
+
+use crate::PeriodicUseCase;
+
+/// Accumulates the WAN traffic of the current billing period, and raises an alert when the usage
+/// crosses one of the thresholds of the quota.
+pub struct RecordDataUsageUseCase<UR: DataUsageRepository<UWP>, UWP: UnitOfWorkProvider> {
+    _marker: std::marker::PhantomData<UR>,
+    router_api: Arc<dyn RouterApi>,
+    uow_provider: UWP,
+    interval: Duration,
+    reset_day: u32,
+    quota: Option<u64>,
+    alert_thresholds: Vec<u8>,
+    is_supported: AtomicBool,
+}
+
+impl<UR: DataUsageRepository<UWP>, UWP: UnitOfWorkProvider> RecordDataUsageUseCase<UR, UWP> {
+    pub fn new(
+        uow_provider: UWP,
+        router_api: Arc<dyn RouterApi>,
+        interval: Duration,
+        reset_day: u32,
+        quota: Option<u64>,
+        alert_thresholds: Vec<u8>,
+    ) -> Self {
+        Self {
+            _marker: std::marker::PhantomData,
+            router_api,
+            uow_provider,
+            interval,
+            reset_day,
+            quota: quota.filter(|quota| *quota > 0),
+            alert_thresholds,
+            is_supported: AtomicBool::new(true),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<UR: DataUsageRepository<UWP>, UWP: UnitOfWorkProvider + 'static> PeriodicUseCase
+    for RecordDataUsageUseCase<UR, UWP>
+{
+    fn next_execution(&self) -> Option<Instant> {
+        if self.is_supported.load(Ordering::Relaxed) {
+            Some(Instant::now() + self.interval)
+        } else {
+            None
+        }
+    }
+
+    #[instrument(skip(self), name = "RecordDataUsageUseCase::execute")]
+    async fn execute(&self) {
+        let stats = match self.router_api.wan_stats().await {
+            Ok(stats) => stats,
+            Err(RouterApiError::Unsupported) => {
+                warn!(
+                    "The router API cannot report WAN statistics, data usage will not be recorded"
+                );
+                self.is_supported.store(false, Ordering::Relaxed);
+                return;
+            }
+            Err(err) => {
+                error!("Failed to fetch WAN statistics: {}", err);
+                return;
+            }
+        };
+
+        let counters = WanTrafficCounters {
+            download_bytes: stats.download.total_since_last_reboot as u64,
+            upload_bytes: stats.upload.total_since_last_reboot as u64,
+        };
+
+        let now = Utc::now();
+        let mut uow = match self.uow_provider.begin_transaction().await {
+            Ok(uow) => uow,
+            Err(err) => {
+                error!("Failed to begin transaction: {}", err);
+                return;
+            }
+        };
+
+        // The traffic is counted from the first sample, what the router counted before is unknown
+        let previous_counters = match UR::fetch_counters(&mut uow).await {
+            Ok(counters) => counters,
+            Err(err) => {
+                error!("Failed to fetch previous WAN counters: {}", err);
+                return;
+            }
+        };
+
+        let mut raised_alerts = Vec::new();
+        if let Some(previous) = previous_counters {
+            let (download_bytes, upload_bytes) = counters.delta_since(&previous);
+            let period = BillingPeriod::containing(now.date_naive(), self.reset_day);
+
+            let usage = match UR::add_usage(&mut uow, period, download_bytes, upload_bytes).await {
+                Ok(usage) => usage,
+                Err(err) => {
+                    error!("Failed to save data usage: {}", err);
+                    return;
+                }
+            };
+
+            if let Some(quota) = self.quota {
+                for &threshold in &self.alert_thresholds {
+                    if (usage.total_bytes() as f64) < quota as f64 * threshold as f64 / 100.0 {
+                        continue;
+                    }
+
+                    let alert = DataUsageAlert {
+                        period_start: period.start,
+                        threshold,
+                        usage_bytes: usage.total_bytes(),
+                        raised_at: now,
+                    };
+
+                    match UR::create_alert(&mut uow, alert.clone()).await {
+                        Ok(true) => raised_alerts.push(alert),
+                        Ok(false) => (),
+                        Err(err) => {
+                            error!("Failed to save data usage alert: {}", err);
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Err(err) = UR::save_counters(&mut uow, counters, now).await {
+            error!("Failed to save WAN counters: {}", err);
+            return;
+        }
+
+        match self.uow_provider.commit(uow).await {
+            Ok(_) => {
+                for alert in raised_alerts {
+                    warn!(
+                        threshold = alert.threshold,
+                        usage_bytes = alert.usage_bytes,
+                        quota_bytes = self.quota,
+                        "Data usage crossed {}% of the quota",
+                        alert.threshold
+                    );
+                }
+                info!("Data usage recorded");
+            }
+            Err(err) => error!("Failed to commit transaction: {}", err),
+        };
+    }
+}
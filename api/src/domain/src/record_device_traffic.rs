@@ -0,0 +1,147 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use entities::{DeviceQuery, DeviceTrafficSample};
+use ports::{
+    api::{RouterApi, RouterApiError},
+    repositories::{DeviceTrafficRepository, DevicesRepository, UnitOfWorkProvider},
+};
+use tracing::{error, info, instrument, warn};
+
+use crate::PeriodicUseCase;
+
+/// Samples the byte counters of the devices, and stores the traffic since the previous sample.
+pub struct RecordDeviceTrafficUseCase<
+    DR: DevicesRepository<UWP>,
+    TR: DeviceTrafficRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> {
+    _marker: std::marker::PhantomData<(DR, TR)>,
+    router_api: Arc<dyn RouterApi>,
+    uow_provider: UWP,
+    interval: Duration,
+    is_supported: AtomicBool,
+}
+
+impl<DR: DevicesRepository<UWP>, TR: DeviceTrafficRepository<UWP>, UWP: UnitOfWorkProvider>
+    RecordDeviceTrafficUseCase<DR, TR, UWP>
+{
+    pub fn new(uow_provider: UWP, router_api: Arc<dyn RouterApi>, interval: Duration) -> Self {
+        Self {
+            _marker: std::marker::PhantomData,
+            router_api,
+            uow_provider,
+            interval,
+            is_supported: AtomicBool::new(true),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<
+    DR: DevicesRepository<UWP>,
+    TR: DeviceTrafficRepository<UWP>,
+    UWP: UnitOfWorkProvider + 'static,
+> PeriodicUseCase for RecordDeviceTrafficUseCase<DR, TR, UWP>
+{
+    fn next_execution(&self) -> Option<Instant> {
+        if self.is_supported.load(Ordering::Relaxed) {
+            Some(Instant::now() + self.interval)
+        } else {
+            None
+        }
+    }
+
+    #[instrument(skip(self), name = "RecordDeviceTrafficUseCase::execute")]
+    async fn execute(&self) {
+        let counters = match self.router_api.list_device_traffic().await {
+            Ok(counters) => counters,
+            Err(RouterApiError::Unsupported) => {
+                warn!(
+                    "The router API cannot report the traffic of devices, it will not be recorded"
+                );
+                self.is_supported.store(false, Ordering::Relaxed);
+                return;
+            }
+            Err(err) => {
+                error!("Failed to fetch device traffic: {}", err);
+                return;
+            }
+        };
+
+        let now = Utc::now();
+        let mut uow = match self.uow_provider.begin_transaction().await {
+            Ok(uow) => uow,
+            Err(err) => {
+                error!("Failed to begin transaction: {}", err);
+                return;
+            }
+        };
+
+        // Devices are only known once synced, their traffic is recorded from the next sample
+        let known_devices = match DR::fetch_all(&mut uow, DeviceQuery::default()).await {
+            Ok(devices) => devices
+                .into_iter()
+                .map(|device| device.mac_address)
+                .collect::<HashSet<_>>(),
+            Err(err) => {
+                error!("Failed to fetch devices: {}", err);
+                return;
+            }
+        };
+
+        let previous_counters = match TR::fetch_counters(&mut uow).await {
+            Ok(counters) => counters
+                .into_iter()
+                .map(|counters| (counters.mac_address, counters))
+                .collect::<HashMap<_, _>>(),
+            Err(err) => {
+                error!("Failed to fetch previous traffic counters: {}", err);
+                return;
+            }
+        };
+
+        let (mut rx_bytes, mut tx_bytes) = (0, 0);
+        for counters in counters
+            .into_iter()
+            .filter(|counters| known_devices.contains(&counters.mac_address))
+        {
+            if let Some(previous) = previous_counters.get(&counters.mac_address) {
+                let (rx_delta, tx_delta) = counters.delta_since(previous);
+                if rx_delta > 0 || tx_delta > 0 {
+                    let sample = DeviceTrafficSample {
+                        mac_address: counters.mac_address,
+                        sampled_at: now,
+                        rx_bytes: rx_delta,
+                        tx_bytes: tx_delta,
+                    };
+
+                    if let Err(err) = TR::create_sample(&mut uow, sample).await {
+                        error!("Failed to save traffic sample: {}", err);
+                        return;
+                    }
+
+                    rx_bytes += rx_delta;
+                    tx_bytes += tx_delta;
+                }
+            }
+
+            if let Err(err) = TR::save_counters(&mut uow, counters, now).await {
+                error!("Failed to save traffic counters: {}", err);
+                return;
+            }
+        }
+
+        match self.uow_provider.commit(uow).await {
+            Ok(_) => info!(rx_bytes, tx_bytes, "Device traffic recorded"),
+            Err(err) => error!("Failed to commit transaction: {}", err),
+        };
+    }
+}
@@ -0,0 +1,132 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use entities::{WanOutage, WanStatus};
+use ports::{
+    api::{RouterApi, RouterApiError},
+    repositories::{UnitOfWorkProvider, WanOutagesRepository},
+};
+use tracing::{error, info, instrument, warn};
+
+use crate::PeriodicUseCase;
+
+pub struct RecordWanOutagesUseCase<OR: WanOutagesRepository<UWP>, UWP: UnitOfWorkProvider> {
+    _marker: std::marker::PhantomData<OR>,
+    router_api: Arc<dyn RouterApi>,
+    uow_provider: UWP,
+    interval: Duration,
+    last_check: Mutex<Option<DateTime<Utc>>>,
+    is_supported: AtomicBool,
+}
+
+impl<OR: WanOutagesRepository<UWP>, UWP: UnitOfWorkProvider> RecordWanOutagesUseCase<OR, UWP> {
+    pub fn new(uow_provider: UWP, router_api: Arc<dyn RouterApi>, interval: Duration) -> Self {
+        Self {
+            _marker: std::marker::PhantomData,
+            router_api,
+            uow_provider,
+            interval,
+            last_check: Mutex::new(None),
+            is_supported: AtomicBool::new(true),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<OR: WanOutagesRepository<UWP>, UWP: UnitOfWorkProvider + 'static> PeriodicUseCase
+    for RecordWanOutagesUseCase<OR, UWP>
+{
+    fn next_execution(&self) -> Option<Instant> {
+        if self.is_supported.load(Ordering::Relaxed) {
+            Some(Instant::now() + self.interval)
+        } else {
+            None
+        }
+    }
+
+    #[instrument(skip(self), name = "RecordWanOutagesUseCase::execute")]
+    async fn execute(&self) {
+        let connectivity = match self.router_api.wan_connectivity().await {
+            Ok(connectivity) => connectivity,
+            Err(RouterApiError::Unsupported) => {
+                warn!("The router API cannot report the WAN status, outages will not be recorded");
+                self.is_supported.store(false, Ordering::Relaxed);
+                return;
+            }
+            Err(err) => {
+                error!("Failed to fetch WAN connectivity: {}", err);
+                return;
+            }
+        };
+
+        let now = Utc::now();
+        // Only moved once the check is committed, so that a failed one is covered by the next
+        let last_check = *self.last_check.lock().unwrap();
+
+        let mut uow = match self.uow_provider.begin_transaction().await {
+            Ok(uow) => uow,
+            Err(err) => {
+                error!("Failed to begin transaction: {}", err);
+                return;
+            }
+        };
+
+        let ongoing_outage = match OR::fetch_ongoing(&mut uow).await {
+            Ok(outage) => outage,
+            Err(err) => {
+                error!("Failed to fetch ongoing outage: {}", err);
+                return;
+            }
+        };
+
+        // Unknown when the router does not report the uptime
+        let up_since = connectivity.uptime.map(|uptime| now - uptime);
+        let result = match (connectivity.status, ongoing_outage) {
+            (WanStatus::Down, None) => {
+                info!("WAN went down");
+                OR::create(&mut uow, WanOutage::start(now)).await
+            }
+            (WanStatus::Up, Some(outage)) => {
+                // The uptime tells when the WAN came back up, if it was reset by the outage
+                let ended_at = match up_since {
+                    Some(up_since) if up_since > outage.started_at => up_since,
+                    _ => now,
+                };
+
+                let outage = outage.end(ended_at);
+                info!(duration = outage.duration.num_seconds(), "WAN came back up");
+                OR::update(&mut uow, outage).await
+            }
+            // Without the uptime, an outage between two checks cannot be detected
+            (WanStatus::Up, None) => match (last_check, up_since) {
+                (Some(last_check), Some(up_since)) if up_since > last_check => {
+                    // The WAN went down and came back up (or the router rebooted) in between
+                    let outage = WanOutage::start(last_check).end(up_since);
+                    info!(
+                        duration = outage.duration.num_seconds(),
+                        "WAN went down and came back up since the last check"
+                    );
+                    OR::create(&mut uow, outage).await
+                }
+                _ => Ok(()),
+            },
+            _ => Ok(()),
+        };
+
+        if let Err(err) = result {
+            error!("Failed to record WAN outage: {}", err);
+            return;
+        }
+
+        match self.uow_provider.commit(uow).await {
+            Ok(_) => *self.last_check.lock().unwrap() = Some(now),
+            Err(err) => error!("Failed to commit transaction: {}", err),
+        };
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use entities::{WanStatsBucket, WanStatsSample};
+use ports::{
+    api::{RouterApi, RouterApiError, RouterApiResultExt},
+    repositories::{UnitOfWorkProvider, WanStatsRepository},
+};
+use tracing::{debug, error, instrument, warn};
+
+use crate::PeriodicUseCase;
+
+/// Samples the WAN bandwidth and status, rolls the samples up and deletes the expired ones.
+pub struct RecordWanStatsUseCase<WR: WanStatsRepository<UWP>, UWP: UnitOfWorkProvider> {
+    _marker: std::marker::PhantomData<WR>,
+    router_api: Arc<dyn RouterApi>,
+    uow_provider: UWP,
+    interval: Duration,
+    samples_retention: chrono::Duration,
+    buckets_retention: Vec<(WanStatsBucket, chrono::Duration)>,
+    is_supported: AtomicBool,
+}
+
+impl<WR: WanStatsRepository<UWP>, UWP: UnitOfWorkProvider> RecordWanStatsUseCase<WR, UWP> {
+    pub fn new(
+        uow_provider: UWP,
+        router_api: Arc<dyn RouterApi>,
+        interval: Duration,
+        samples_retention: chrono::Duration,
+        buckets_retention: Vec<(WanStatsBucket, chrono::Duration)>,
+    ) -> Self {
+        Self {
+            _marker: std::marker::PhantomData,
+            router_api,
+            uow_provider,
+            interval,
+            samples_retention,
+            buckets_retention,
+            is_supported: AtomicBool::new(true),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<WR: WanStatsRepository<UWP>, UWP: UnitOfWorkProvider + 'static> PeriodicUseCase
+    for RecordWanStatsUseCase<WR, UWP>
+{
+    fn next_execution(&self) -> Option<Instant> {
+        if self.is_supported.load(Ordering::Relaxed) {
+            Some(Instant::now() + self.interval)
+        } else {
+            None
+        }
+    }
+
+    #[instrument(skip(self), name = "RecordWanStatsUseCase::execute")]
+    async fn execute(&self) {
+        let stats = match self.router_api.wan_stats().await {
+            Ok(stats) => stats,
+            Err(RouterApiError::Unsupported) => {
+                warn!("The router API cannot report WAN statistics, they will not be recorded");
+                self.is_supported.store(false, Ordering::Relaxed);
+                return;
+            }
+            Err(err) => {
+                error!("Failed to fetch WAN statistics: {}", err);
+                return;
+            }
+        };
+
+        // The bandwidth is worth recording even when the status cannot be fetched
+        let status = match self.router_api.wan_connectivity().await.supported() {
+            Ok(connectivity) => connectivity.map(|connectivity| connectivity.status),
+            Err(err) => {
+                warn!("Failed to fetch WAN connectivity: {}", err);
+                None
+            }
+        };
+
+        let now = Utc::now();
+        let mut uow = match self.uow_provider.begin_transaction().await {
+            Ok(uow) => uow,
+            Err(err) => {
+                error!("Failed to begin transaction: {}", err);
+                return;
+            }
+        };
+
+        let sample = WanStatsSample {
+            sampled_at: now,
+            download_bandwidth: stats.download.current_bandwidth,
+            upload_bandwidth: stats.upload.current_bandwidth,
+            status,
+        };
+        if let Err(err) = WR::create_sample(&mut uow, sample).await {
+            error!("Failed to save WAN statistics sample: {}", err);
+            return;
+        }
+
+        // The previous bucket is rolled up again, in case it ended since the last execution
+        for bucket in WanStatsBucket::ALL {
+            if let Err(err) = WR::rollup(&mut uow, bucket, now - bucket.duration()).await {
+                error!(%bucket, "Failed to roll up WAN statistics: {}", err);
+                return;
+            }
+        }
+
+        match WR::delete_samples_before(&mut uow, now - self.samples_retention).await {
+            Ok(0) => (),
+            Ok(deleted) => debug!(deleted, "Deleted expired WAN statistics samples"),
+            Err(err) => error!("Failed to delete expired WAN statistics samples: {}", err),
+        }
+
+        for (bucket, retention) in &self.buckets_retention {
+            match WR::delete_buckets_before(&mut uow, *bucket, now - *retention).await {
+                Ok(0) => (),
+                Ok(deleted) => debug!(%bucket, deleted, "Deleted expired WAN statistics buckets"),
+                Err(err) => error!(%bucket, "Failed to delete expired WAN statistics: {}", err),
+            }
+        }
+
+        match self.uow_provider.commit(uow).await {
+            Ok(_) => (),
+            Err(err) => error!("Failed to commit transaction: {}", err),
+        };
+    }
+}
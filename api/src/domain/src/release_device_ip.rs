@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use mac_address::MacAddress;
+use ports::api::{RouterApi, RouterApiError};
+use tracing::{info, instrument};
+
+use crate::DeviceReservationError;
+
+#[derive(Clone)]
+pub struct ReleaseDeviceIpUseCase {
+    router_api: Arc<dyn RouterApi>,
+}
+
+impl ReleaseDeviceIpUseCase {
+    pub fn new(router_api: Arc<dyn RouterApi>) -> Self {
+        Self { router_api }
+    }
+
+    #[instrument(skip(self), name = "ReleaseDeviceIpUseCase::execute")]
+    pub async fn execute(&self, mac_address: MacAddress) -> Result<(), DeviceReservationError> {
+        match self.router_api.release_ip(mac_address).await {
+            Ok(()) => {
+                info!(device = %mac_address, "Reserved address released");
+                Ok(())
+            }
+            Err(RouterApiError::NotFound) => Err(DeviceReservationError::ReservationNotFound),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
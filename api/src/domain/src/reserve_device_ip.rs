@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use entities::{DeviceQuery, StaticLease};
+use mac_address::MacAddress;
+use ports::{
+    api::RouterApi,
+    repositories::{DevicesRepository, UnitOfWorkProvider},
+};
+use tracing::{info, instrument, warn};
+
+use crate::{DeviceReservationError, ReserveIp};
+
+#[derive(Clone)]
+pub struct ReserveDeviceIpUseCase<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    router_api: Arc<dyn RouterApi>,
+    _marker: std::marker::PhantomData<DR>,
+}
+
+impl<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider> ReserveDeviceIpUseCase<DR, UWP> {
+    pub fn new(uow_provider: UWP, router_api: Arc<dyn RouterApi>) -> Self {
+        Self {
+            uow_provider,
+            router_api,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    #[instrument(skip(self), name = "ReserveDeviceIpUseCase::execute")]
+    pub async fn execute(
+        &self,
+        mac_address: MacAddress,
+        reservation: ReserveIp,
+    ) -> Result<StaticLease, DeviceReservationError> {
+        let ip_address = reservation.ip_address;
+
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        if DR::fetch_one(&mut uow, mac_address).await?.is_none() {
+            return Err(DeviceReservationError::DeviceNotFound);
+        }
+
+        let lan = self.router_api.lan_network().await?;
+        if !lan.contains(ip_address)
+            || ip_address == lan.network()
+            || (lan.is_ipv4() && ip_address == lan.broadcast())
+        {
+            warn!(lan = %lan, "Address is outside the LAN");
+            return Err(DeviceReservationError::AddressOutsideLan);
+        }
+        if ip_address == lan.ip() {
+            warn!("Address is the one of the router");
+            return Err(DeviceReservationError::AddressTaken);
+        }
+
+        let leases = self.router_api.list_static_leases().await?;
+        if let Some(lease) = leases.iter().find(|lease| lease.ip_address == ip_address) {
+            if lease.mac_address == mac_address {
+                return Ok(lease.clone());
+            }
+            warn!(device = %lease.mac_address, "Address is reserved for another device");
+            return Err(DeviceReservationError::AddressTaken);
+        }
+
+        // Devices without a reservation can still hold the address
+        if let Some(device) = DR::fetch_all(&mut uow, DeviceQuery::default())
+            .await?
+            .iter()
+            .find(|device| {
+                device.is_online
+                    && device.last_known_ip == ip_address
+                    && device.mac_address != mac_address
+            })
+        {
+            warn!(device = %device.mac_address, "Address is used by another device");
+            return Err(DeviceReservationError::AddressTaken);
+        }
+
+        let lease = self.router_api.reserve_ip(mac_address, ip_address).await?;
+
+        info!(lease = ?lease, "Address reserved successfully");
+        Ok(lease)
+    }
+}
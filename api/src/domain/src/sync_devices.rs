@@ -1,20 +1,47 @@
-use std::{collections::HashMap, sync::Arc, time::Instant};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
 
+use chrono::Utc;
+use entities::{
+    AccessState, DeviceAccess, DeviceConnection, DeviceQuery, PresenceEvent, PresenceEventKind,
+};
+use mac_address::MacAddress;
 use ports::{
-    api::RouterApi,
-    repositories::{DevicesRepository, UnitOfWorkProvider},
+    api::{RouterApi, RouterApiResultExt},
+    repositories::{
+        DeviceAccessRepository, DeviceConnectionsRepository, DevicesRepository,
+        IpHistoryRepository, PresenceEventsRepository, UnitOfWorkProvider,
+    },
 };
 use tracing::{error, info, instrument};
 
 use crate::PeriodicUseCase;
 
-pub struct SyncDevicesUseCase<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider> {
-    _marker: std::marker::PhantomData<DR>,
+pub struct SyncDevicesUseCase<
+    DR: DevicesRepository<UWP>,
+    AR: DeviceAccessRepository<UWP>,
+    CR: DeviceConnectionsRepository<UWP>,
+    ER: PresenceEventsRepository<UWP>,
+    IR: IpHistoryRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> {
+    _marker: std::marker::PhantomData<(DR, AR, CR, ER, IR)>,
     router_api: Arc<dyn RouterApi>,
     uow_provider: UWP,
 }
 
-impl<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider> SyncDevicesUseCase<DR, UWP> {
+impl<
+    DR: DevicesRepository<UWP>,
+    AR: DeviceAccessRepository<UWP>,
+    CR: DeviceConnectionsRepository<UWP>,
+    ER: PresenceEventsRepository<UWP>,
+    IR: IpHistoryRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> SyncDevicesUseCase<DR, AR, CR, ER, IR, UWP>
+{
     pub fn new(uow_provider: UWP, router_api: Arc<dyn RouterApi>) -> Self {
         Self {
             _marker: std::marker::PhantomData,
@@ -22,11 +49,112 @@ impl<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider> SyncDevicesUseCase<DR,
             uow_provider,
         }
     }
+
+    /// Syncs the access rules of the known devices, returns the ones that are restricted.
+    async fn sync_access(
+        &self,
+        uow: &mut UWP::UnitOfWork<'_>,
+        device_macs: &HashSet<MacAddress>,
+    ) -> Vec<DeviceAccess> {
+        // Parental controls are optional, the devices are synced anyway
+        let accesses = match self.router_api.list_device_access().await.supported() {
+            Ok(Some(accesses)) => accesses
+                .into_iter()
+                .filter(|access| device_macs.contains(&access.mac_address))
+                .map(|access| (access.mac_address, access))
+                .collect::<HashMap<_, _>>(),
+            Ok(None) => return Vec::new(),
+            Err(err) => {
+                error!("Failed to fetch device access: {}", err);
+                return Vec::new();
+            }
+        };
+
+        let known_accesses = match AR::fetch_all(uow).await {
+            Ok(accesses) => accesses,
+            Err(err) => {
+                error!("Failed to fetch device access: {}", err);
+                return Vec::new();
+            }
+        };
+
+        for known in known_accesses {
+            if !accesses.contains_key(&known.mac_address)
+                && let Err(err) = AR::delete(uow, known.mac_address).await
+            {
+                error!("Failed to delete device access: {}", err);
+            }
+        }
+
+        let mut restricted = Vec::new();
+        for access in accesses.into_values() {
+            match AR::save(uow, access.clone()).await {
+                Ok(_) => restricted.push(access),
+                Err(err) => error!("Failed to save device access: {}", err),
+            }
+        }
+
+        restricted
+    }
+
+    /// Syncs the connections of the known devices, returns the ones connected through Wi-Fi.
+    async fn sync_connections(
+        &self,
+        uow: &mut UWP::UnitOfWork<'_>,
+        device_macs: &HashSet<MacAddress>,
+    ) -> Vec<DeviceConnection> {
+        // Wi-Fi details are optional, the devices are synced anyway
+        let connections = match self.router_api.list_device_connections().await.supported() {
+            Ok(Some(connections)) => connections
+                .into_iter()
+                .filter(|connection| device_macs.contains(&connection.mac_address))
+                .map(|connection| (connection.mac_address, connection))
+                .collect::<HashMap<_, _>>(),
+            Ok(None) => return Vec::new(),
+            Err(err) => {
+                error!("Failed to fetch device connections: {}", err);
+                return Vec::new();
+            }
+        };
+
+        let known_connections = match CR::fetch_all(uow).await {
+            Ok(connections) => connections,
+            Err(err) => {
+                error!("Failed to fetch device connections: {}", err);
+                return Vec::new();
+            }
+        };
+
+        for known in known_connections {
+            if !connections.contains_key(&known.mac_address)
+                && let Err(err) = CR::delete(uow, known.mac_address).await
+            {
+                error!("Failed to delete device connection: {}", err);
+            }
+        }
+
+        let mut wifi = Vec::new();
+        for connection in connections.into_values() {
+            match CR::save(uow, connection.clone()).await {
+                Ok(_) if connection.is_wifi() => wifi.push(connection),
+                Ok(_) => (),
+                Err(err) => error!("Failed to save device connection: {}", err),
+            }
+        }
+
+        wifi
+    }
 }
 
 #[async_trait::async_trait]
-impl<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider + 'static> PeriodicUseCase
-    for SyncDevicesUseCase<DR, UWP>
+impl<
+    DR: DevicesRepository<UWP>,
+    AR: DeviceAccessRepository<UWP>,
+    CR: DeviceConnectionsRepository<UWP>,
+    ER: PresenceEventsRepository<UWP>,
+    IR: IpHistoryRepository<UWP>,
+    UWP: UnitOfWorkProvider + 'static,
+> PeriodicUseCase for SyncDevicesUseCase<DR, AR, CR, ER, IR, UWP>
 {
     fn next_execution(&self) -> Option<Instant> {
         Some(Instant::now() + std::time::Duration::from_secs(60))
@@ -50,7 +178,7 @@ impl<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider + 'static> PeriodicUseC
             }
         };
 
-        let known_devices = match DR::fetch_all(&mut uow, None).await {
+        let known_devices = match DR::fetch_all(&mut uow, DeviceQuery::default()).await {
             Ok(devices) => devices,
             Err(err) => {
                 error!("Failed to fetch devices: {}", err);
@@ -64,11 +192,28 @@ impl<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider + 'static> PeriodicUseC
             "Fetched devices from router and database"
         );
 
+        let device_macs = scanned_devices
+            .iter()
+            .chain(&known_devices)
+            .map(|device| device.mac_address)
+            .collect::<HashSet<_>>();
+
         let mut known_map = known_devices
             .into_iter()
-            .map(|d| (d.mac_address.clone(), d))
+            .map(|d| (d.mac_address, d))
             .collect::<HashMap<_, _>>();
 
+        // Only the addresses of online devices are current, offline ones may have been reassigned
+        let seen_ips = scanned_devices
+            .iter()
+            .filter(|device| device.is_online)
+            .flat_map(|device| {
+                std::iter::once(device.last_known_ip)
+                    .chain(device.other_ips.iter().copied())
+                    .map(|ip_address| (device.mac_address, ip_address))
+            })
+            .collect::<Vec<_>>();
+
         let mut new_devices = Vec::new();
         let mut disconnected_devices = Vec::new();
         let mut reconnected_devices = Vec::new();
@@ -98,16 +243,62 @@ impl<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider + 'static> PeriodicUseC
             }
         }
 
-        for device in known_map.into_values().map(|mut d| {
-            d.is_online = false;
-            d
-        }) {
+        // Devices missing from the scan went offline, unless they already were
+        for device in known_map
+            .into_values()
+            .filter(|d| d.is_online)
+            .map(|mut d| {
+                d.is_online = false;
+                d
+            })
+        {
             match DR::update(&mut uow, device.clone()).await {
                 Ok(_) => disconnected_devices.push(device),
                 Err(err) => error!("Failed to update device: {}", err),
             };
         }
 
+        let detected_at = Utc::now();
+        let events = new_devices
+            .iter()
+            .map(|device| (device, PresenceEventKind::New))
+            .chain(
+                disconnected_devices
+                    .iter()
+                    .map(|device| (device, PresenceEventKind::Disconnected)),
+            )
+            .chain(
+                reconnected_devices
+                    .iter()
+                    .map(|device| (device, PresenceEventKind::Reconnected)),
+            );
+        for (device, kind) in events {
+            let event = PresenceEvent {
+                mac_address: device.mac_address,
+                kind,
+                is_online: device.is_online,
+                occurred_at: detected_at,
+            };
+
+            if let Err(err) = ER::create(&mut uow, event).await {
+                error!("Failed to save presence event: {}", err);
+            }
+        }
+
+        for (mac_address, ip_address) in seen_ips {
+            if let Err(err) = IR::record(&mut uow, mac_address, ip_address, detected_at).await {
+                error!("Failed to record IP address: {}", err);
+            }
+        }
+
+        let restricted_devices = self.sync_access(&mut uow, &device_macs).await;
+        let now = chrono::Local::now().naive_local();
+        let blocked_devices = restricted_devices
+            .iter()
+            .filter(|access| access.state_at(now) != AccessState::Allowed)
+            .collect::<Vec<_>>();
+        let wifi_devices = self.sync_connections(&mut uow, &device_macs).await;
+
         match self.uow_provider.commit(uow).await {
             Ok(_) => (),
             Err(err) => error!("Failed to commit transaction: {}", err),
@@ -117,6 +308,9 @@ impl<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider + 'static> PeriodicUseC
             new_devices = ?new_devices.iter().map(|d| d.mac_address.to_string()).collect::<Vec<_>>(),
             disconnected_devices = ?disconnected_devices.iter().map(|d| d.mac_address.to_string()).collect::<Vec<_>>(),
             reconnected_devices = ?reconnected_devices.iter().map(|d| d.mac_address.to_string()).collect::<Vec<_>>(),
+            restricted_devices = ?restricted_devices.iter().map(|a| a.mac_address.to_string()).collect::<Vec<_>>(),
+            blocked_devices = ?blocked_devices.iter().map(|a| a.mac_address.to_string()).collect::<Vec<_>>(),
+            wifi_devices = wifi_devices.len(),
             "Finished syncing devices"
         );
     }
@@ -0,0 +1,116 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use entities::FullConfigSnapshot;
+use ports::{
+    api::{RouterApi, RouterApiError},
+    repositories::{ConfigSnapshotsRepository, UnitOfWorkProvider},
+};
+use tracing::{error, info, instrument, warn};
+
+use crate::PeriodicUseCase;
+
+/// Backs up the router configuration when it changed, and deletes the expired backups.
+pub struct TakeConfigSnapshotsUseCase<CR: ConfigSnapshotsRepository<UWP>, UWP: UnitOfWorkProvider> {
+    _marker: std::marker::PhantomData<CR>,
+    router_api: Arc<dyn RouterApi>,
+    uow_provider: UWP,
+    interval: Duration,
+    retention: chrono::Duration,
+    is_supported: AtomicBool,
+}
+
+impl<CR: ConfigSnapshotsRepository<UWP>, UWP: UnitOfWorkProvider>
+    TakeConfigSnapshotsUseCase<CR, UWP>
+{
+    pub fn new(
+        uow_provider: UWP,
+        router_api: Arc<dyn RouterApi>,
+        interval: Duration,
+        retention: chrono::Duration,
+    ) -> Self {
+        Self {
+            _marker: std::marker::PhantomData,
+            router_api,
+            uow_provider,
+            interval,
+            retention,
+            is_supported: AtomicBool::new(true),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<CR: ConfigSnapshotsRepository<UWP>, UWP: UnitOfWorkProvider + 'static> PeriodicUseCase
+    for TakeConfigSnapshotsUseCase<CR, UWP>
+{
+    fn next_execution(&self) -> Option<Instant> {
+        if self.is_supported.load(Ordering::Relaxed) {
+            Some(Instant::now() + self.interval)
+        } else {
+            None
+        }
+    }
+
+    #[instrument(skip(self), name = "TakeConfigSnapshotsUseCase::execute")]
+    async fn execute(&self) {
+        let content = match self.router_api.export_config().await {
+            Ok(content) => content,
+            Err(RouterApiError::Unsupported) => {
+                warn!("The router API cannot export its configuration, it will not be backed up");
+                self.is_supported.store(false, Ordering::Relaxed);
+                return;
+            }
+            Err(err) => {
+                error!("Failed to export router configuration: {}", err);
+                return;
+            }
+        };
+
+        let mut uow = match self.uow_provider.begin_transaction().await {
+            Ok(uow) => uow,
+            Err(err) => {
+                error!("Failed to begin transaction: {}", err);
+                return;
+            }
+        };
+
+        let latest = match CR::fetch_latest(&mut uow).await {
+            Ok(latest) => latest,
+            Err(err) => {
+                error!("Failed to fetch latest config snapshot: {}", err);
+                return;
+            }
+        };
+
+        if latest.is_some_and(|latest| latest.content == content) {
+            info!("Router configuration did not change, no snapshot taken");
+        } else {
+            let snapshot = FullConfigSnapshot::new(content);
+            let snapshot_id = snapshot.snapshot.snapshot_id;
+            match CR::create(&mut uow, snapshot).await {
+                Ok(_) => info!(snapshot_id = %snapshot_id, "Router configuration snapshot taken"),
+                Err(err) => {
+                    error!("Failed to save config snapshot: {}", err);
+                    return;
+                }
+            }
+        }
+
+        match CR::delete_older_than(&mut uow, chrono::Utc::now() - self.retention).await {
+            Ok(0) => (),
+            Ok(deleted) => info!(deleted, "Deleted expired config snapshots"),
+            Err(err) => error!("Failed to delete expired config snapshots: {}", err),
+        }
+
+        match self.uow_provider.commit(uow).await {
+            Ok(_) => (),
+            Err(err) => error!("Failed to commit transaction: {}", err),
+        };
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use entities::Device;
+use mac_address::MacAddress;
+use ports::{
+    api::RouterApi,
+    repositories::{DevicesRepository, UnitOfWorkProvider},
+};
+use tracing::{info, instrument, warn};
+
+use crate::{DeviceError, UpdateDevice};
+
+#[derive(Clone)]
+pub struct UpdateDeviceUseCase<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    router_api: Arc<dyn RouterApi>,
+    _marker: std::marker::PhantomData<DR>,
+}
+
+impl<DR: DevicesRepository<UWP>, UWP: UnitOfWorkProvider> UpdateDeviceUseCase<DR, UWP> {
+    pub fn new(uow_provider: UWP, router_api: Arc<dyn RouterApi>) -> Self {
+        Self {
+            uow_provider,
+            router_api,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the name reported by the router for the device, if it still knows it.
+    async fn router_name(&self, mac_address: MacAddress) -> Option<String> {
+        match self.router_api.list_devices().await {
+            Ok(devices) => devices
+                .into_iter()
+                .find(|device| device.mac_address == mac_address)
+                .map(|device| device.display_name),
+            Err(err) => {
+                warn!(
+                    "Failed to fetch the name of the device from the router: {}",
+                    err
+                );
+                None
+            }
+        }
+    }
+
+    #[instrument(skip(self, update), name = "UpdateDeviceUseCase::execute")]
+    pub async fn execute(
+        &self,
+        mac_address: MacAddress,
+        update: UpdateDevice,
+    ) -> Result<Device, DeviceError> {
+        // The router is asked before the transaction starts, so as not to hold it during the call
+        let router_name = match update.display_name {
+            Some(None) => self.router_name(mac_address).await,
+            _ => None,
+        };
+
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        let mut device = DR::fetch_one(&mut uow, mac_address)
+            .await?
+            .ok_or(DeviceError::DeviceNotFound)?;
+
+        match update.display_name {
+            Some(Some(display_name)) => {
+                device.display_name = display_name;
+                device.is_name_custom = true;
+            }
+            // The current name is kept until the next scan if the router does not know the device
+            Some(None) => {
+                if let Some(display_name) = router_name {
+                    device.display_name = display_name;
+                }
+                device.is_name_custom = false;
+            }
+            None => (),
+        }
+        if let Some(notes) = update.notes {
+            device.notes = notes;
+        }
+
+        DR::update_details(
+            &mut uow,
+            mac_address,
+            device.display_name.clone(),
+            device.is_name_custom,
+            device.notes.clone(),
+        )
+        .await?;
+        self.uow_provider.commit(uow).await?;
+
+        info!(%mac_address, display_name = %device.display_name, "Device updated");
+        Ok(device)
+    }
+}
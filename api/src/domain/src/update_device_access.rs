@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use entities::DeviceAccess;
+use mac_address::MacAddress;
+use ports::{
+    api::RouterApi,
+    repositories::{DeviceAccessRepository, DevicesRepository, UnitOfWorkProvider},
+};
+use tracing::{info, instrument};
+
+use crate::{DeviceAccessError, UpdateDeviceAccess};
+
+#[derive(Clone)]
+pub struct UpdateDeviceAccessUseCase<
+    DR: DevicesRepository<UWP>,
+    AR: DeviceAccessRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> {
+    uow_provider: UWP,
+    router_api: Arc<dyn RouterApi>,
+    _marker: std::marker::PhantomData<(DR, AR)>,
+}
+
+impl<DR: DevicesRepository<UWP>, AR: DeviceAccessRepository<UWP>, UWP: UnitOfWorkProvider>
+    UpdateDeviceAccessUseCase<DR, AR, UWP>
+{
+    pub fn new(uow_provider: UWP, router_api: Arc<dyn RouterApi>) -> Self {
+        Self {
+            uow_provider,
+            router_api,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    #[instrument(skip(self), name = "UpdateDeviceAccessUseCase::execute")]
+    pub async fn execute(
+        &self,
+        mac_address: MacAddress,
+        mut update: UpdateDeviceAccess,
+    ) -> Result<DeviceAccess, DeviceAccessError> {
+        update.validate_schedule()?;
+
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        if DR::fetch_one(&mut uow, mac_address).await?.is_none() {
+            return Err(DeviceAccessError::DeviceNotFound);
+        }
+
+        let current = AR::fetch_one(&mut uow, mac_address)
+            .await?
+            .unwrap_or_else(|| DeviceAccess::allowed(mac_address));
+
+        if current.is_blocked != update.is_blocked {
+            self.router_api
+                .set_device_blocked(mac_address, update.is_blocked)
+                .await?;
+        }
+        if current.schedule != update.schedule {
+            self.router_api
+                .set_device_schedule(mac_address, update.schedule.clone())
+                .await?;
+        }
+
+        let access = DeviceAccess {
+            mac_address,
+            is_blocked: update.is_blocked,
+            schedule: update.schedule,
+        };
+        if access.is_restricted() {
+            AR::save(&mut uow, access.clone()).await?;
+        } else {
+            AR::delete(&mut uow, mac_address).await?;
+        }
+        self.uow_provider.commit(uow).await?;
+
+        info!(access = ?access, "Device access updated successfully");
+        Ok(access)
+    }
+}
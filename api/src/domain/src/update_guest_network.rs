@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use entities::GuestNetwork;
+use ports::{
+    api::RouterApi,
+    repositories::{GuestNetworkRepository, UnitOfWorkProvider},
+};
+use tracing::{info, instrument};
+
+use crate::{GuestNetworkError, UpdateGuestNetwork};
+
+#[derive(Clone)]
+pub struct UpdateGuestNetworkUseCase<GR: GuestNetworkRepository<UWP>, UWP: UnitOfWorkProvider> {
+    uow_provider: UWP,
+    router_api: Arc<dyn RouterApi>,
+    _marker: std::marker::PhantomData<GR>,
+}
+
+impl<GR: GuestNetworkRepository<UWP>, UWP: UnitOfWorkProvider> UpdateGuestNetworkUseCase<GR, UWP> {
+    pub fn new(uow_provider: UWP, router_api: Arc<dyn RouterApi>) -> Self {
+        Self {
+            uow_provider,
+            router_api,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    #[instrument(skip(self, update), name = "UpdateGuestNetworkUseCase::execute")]
+    pub async fn execute(
+        &self,
+        update: UpdateGuestNetwork,
+    ) -> Result<GuestNetwork, GuestNetworkError> {
+        // The expiry only matters while the network is enabled
+        let expires_at = update.expires_at.filter(|_| update.is_enabled);
+        if expires_at.is_some_and(|expires_at| expires_at <= chrono::Utc::now()) {
+            return Err(GuestNetworkError::ExpiryInPast);
+        }
+
+        let current = self.router_api.guest_network().await?;
+        let ssid = update.ssid.unwrap_or(current.ssid.clone());
+        let passphrase = update.passphrase.unwrap_or(current.passphrase.clone());
+
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        match expires_at {
+            Some(expires_at) => GR::save_expiry(&mut uow, expires_at).await?,
+            None => GR::delete_expiry(&mut uow).await?,
+        }
+
+        if ssid != current.ssid || passphrase != current.passphrase {
+            self.router_api
+                .set_guest_network_credentials(&ssid, &passphrase)
+                .await?;
+        }
+        if update.is_enabled != current.is_enabled {
+            self.router_api
+                .set_guest_network_enabled(update.is_enabled)
+                .await?;
+        }
+        self.uow_provider.commit(uow).await?;
+
+        let guest_network = GuestNetwork {
+            is_enabled: update.is_enabled,
+            ssid,
+            passphrase,
+            expires_at,
+        };
+        info!(
+            is_enabled = guest_network.is_enabled,
+            ssid = guest_network.ssid,
+            expires_at = ?guest_network.expires_at,
+            "Guest network updated successfully"
+        );
+        Ok(guest_network)
+    }
+}
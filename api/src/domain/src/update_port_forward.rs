@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use entities::{FullPortForward, PortForwardLink};
+use ports::{
+    api::RouterApi,
+    repositories::{
+        DevicesRepository, PortForwardLinksRepository, ServicesRepository, UnitOfWorkProvider,
+    },
+};
+use tracing::{info, instrument};
+
+use crate::{PortForwardError, SavePortForward, check_link};
+
+#[derive(Clone)]
+pub struct UpdatePortForwardUseCase<
+    LR: PortForwardLinksRepository<UWP>,
+    SR: ServicesRepository<UWP>,
+    DR: DevicesRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> {
+    uow_provider: UWP,
+    router_api: Arc<dyn RouterApi>,
+    _marker: std::marker::PhantomData<(LR, SR, DR)>,
+}
+
+impl<
+    LR: PortForwardLinksRepository<UWP>,
+    SR: ServicesRepository<UWP>,
+    DR: DevicesRepository<UWP>,
+    UWP: UnitOfWorkProvider,
+> UpdatePortForwardUseCase<LR, SR, DR, UWP>
+{
+    pub fn new(uow_provider: UWP, router_api: Arc<dyn RouterApi>) -> Self {
+        Self {
+            uow_provider,
+            router_api,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    #[instrument(skip(self), name = "UpdatePortForwardUseCase::execute")]
+    pub async fn execute(
+        &self,
+        port_forward_id: String,
+        port_forward: SavePortForward,
+    ) -> Result<FullPortForward, PortForwardError> {
+        let mut uow = self.uow_provider.begin_transaction().await?;
+        if let Some(link) = &port_forward.service {
+            check_link::<SR, DR, UWP>(&mut uow, &port_forward.rule, link).await?;
+        }
+
+        let updated = self
+            .router_api
+            .update_port_forward(&port_forward_id, port_forward.rule)
+            .await?;
+
+        // The link follows the rule, it is replaced (or removed) along with it
+        LR::delete(&mut uow, &port_forward_id).await?;
+        let service = port_forward.service.map(|link| PortForwardLink {
+            port_forward_id: updated.port_forward_id.clone(),
+            ..link
+        });
+        if let Some(link) = service.clone() {
+            LR::create(&mut uow, link).await?;
+        }
+        self.uow_provider.commit(uow).await?;
+
+        info!(port_forward = ?updated, "Port forward updated successfully");
+        Ok(FullPortForward {
+            port_forward: updated,
+            service,
+        })
+    }
+}
@@ -0,0 +1,174 @@
+use chrono::{DateTime, Utc};
+use entities::{ConfigSnapshot, FullConfigSnapshot};
+use ports::repositories::{ConfigSnapshotsRepository, Repository, RepositoryResult};
+use sqlx::{PgConnection, Postgres, prelude::FromRow};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{PostgresUWP, PostgresUoW, map_sqlx_error};
+
+#[derive(Clone)]
+pub struct PostgresConfigSnapshotsRepository;
+
+#[derive(FromRow)]
+struct ConfigSnapshotRow {
+    snapshot_id: Uuid,
+    taken_at: DateTime<Utc>,
+    size: i32,
+}
+
+impl From<ConfigSnapshotRow> for ConfigSnapshot {
+    fn from(row: ConfigSnapshotRow) -> Self {
+        ConfigSnapshot {
+            snapshot_id: row.snapshot_id,
+            taken_at: row.taken_at,
+            size: row.size as usize,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct FullConfigSnapshotRow {
+    snapshot_id: Uuid,
+    taken_at: DateTime<Utc>,
+    content: String,
+}
+
+impl From<FullConfigSnapshotRow> for FullConfigSnapshot {
+    fn from(row: FullConfigSnapshotRow) -> Self {
+        FullConfigSnapshot {
+            snapshot: ConfigSnapshot {
+                snapshot_id: row.snapshot_id,
+                taken_at: row.taken_at,
+                size: row.content.len(),
+            },
+            content: row.content,
+        }
+    }
+}
+
+impl Repository<PostgresUWP> for PostgresConfigSnapshotsRepository {}
+
+#[async_trait::async_trait]
+impl ConfigSnapshotsRepository<PostgresUWP> for PostgresConfigSnapshotsRepository {
+    #[instrument(skip(connection))]
+    async fn fetch_all<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+    ) -> RepositoryResult<Vec<ConfigSnapshot>> {
+        Ok(sqlx::query_as::<Postgres, ConfigSnapshotRow>(
+            r#"
+            SELECT snapshot_id, taken_at, octet_length(content) as size
+            FROM core.config_snapshots
+            ORDER BY taken_at DESC
+            "#,
+        )
+        .fetch_all(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .into_iter()
+        .map(ConfigSnapshot::from)
+        .collect())
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_one<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        snapshot_id: Uuid,
+    ) -> RepositoryResult<Option<FullConfigSnapshot>> {
+        Ok(sqlx::query_as::<Postgres, FullConfigSnapshotRow>(
+            r#"
+            SELECT snapshot_id, taken_at, content
+            FROM core.config_snapshots
+            WHERE snapshot_id = $1
+            "#,
+        )
+        .bind(snapshot_id)
+        .fetch_optional(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .map(FullConfigSnapshot::from))
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_latest<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+    ) -> RepositoryResult<Option<FullConfigSnapshot>> {
+        Ok(sqlx::query_as::<Postgres, FullConfigSnapshotRow>(
+            r#"
+            SELECT snapshot_id, taken_at, content
+            FROM core.config_snapshots
+            ORDER BY taken_at DESC
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .map(FullConfigSnapshot::from))
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_previous<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        snapshot: &ConfigSnapshot,
+    ) -> RepositoryResult<Option<FullConfigSnapshot>> {
+        Ok(sqlx::query_as::<Postgres, FullConfigSnapshotRow>(
+            r#"
+            SELECT snapshot_id, taken_at, content
+            FROM core.config_snapshots
+            WHERE taken_at < $1
+            ORDER BY taken_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(snapshot.taken_at)
+        .fetch_optional(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .map(FullConfigSnapshot::from))
+    }
+
+    #[instrument(skip(connection, snapshot), fields(snapshot_id = %snapshot.snapshot.snapshot_id))]
+    async fn create<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        snapshot: FullConfigSnapshot,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO core.config_snapshots (snapshot_id, taken_at, content)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(snapshot.snapshot.snapshot_id)
+        .bind(snapshot.snapshot.taken_at)
+        .bind(snapshot.content)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+
+    #[instrument(skip(connection))]
+    async fn delete_older_than<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        before: DateTime<Utc>,
+    ) -> RepositoryResult<u64> {
+        sqlx::query(
+            r#"
+            DELETE FROM core.config_snapshots
+            WHERE taken_at < $1
+              AND snapshot_id <> (
+                  SELECT snapshot_id
+                  FROM core.config_snapshots
+                  ORDER BY taken_at DESC
+                  LIMIT 1
+              )
+            "#,
+        )
+        .bind(before)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|result| result.rows_affected())
+    }
+}
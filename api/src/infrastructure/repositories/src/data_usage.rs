@@ -0,0 +1,203 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use entities::{BillingPeriod, DataUsageAlert, DataUsagePeriod, WanTrafficCounters};
+use ports::repositories::{DataUsageRepository, Repository, RepositoryResult};
+use sqlx::{PgConnection, Postgres, prelude::FromRow};
+use tracing::instrument;
+
+use crate::{PostgresUWP, PostgresUoW, map_sqlx_error, to_bigint};
+
+#[derive(Clone)]
+pub struct PostgresDataUsageRepository;
+
+#[derive(FromRow)]
+struct WanTrafficCountersRow {
+    download_bytes: i64,
+    upload_bytes: i64,
+}
+
+impl From<WanTrafficCountersRow> for WanTrafficCounters {
+    fn from(row: WanTrafficCountersRow) -> Self {
+        WanTrafficCounters {
+            download_bytes: row.download_bytes.max(0) as u64,
+            upload_bytes: row.upload_bytes.max(0) as u64,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct DataUsagePeriodRow {
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+    download_bytes: i64,
+    upload_bytes: i64,
+}
+
+impl From<DataUsagePeriodRow> for DataUsagePeriod {
+    fn from(row: DataUsagePeriodRow) -> Self {
+        DataUsagePeriod {
+            period: BillingPeriod {
+                start: row.period_start,
+                end: row.period_end,
+            },
+            download_bytes: row.download_bytes.max(0) as u64,
+            upload_bytes: row.upload_bytes.max(0) as u64,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct DataUsageAlertRow {
+    period_start: NaiveDate,
+    threshold: i16,
+    usage_bytes: i64,
+    raised_at: DateTime<Utc>,
+}
+
+impl From<DataUsageAlertRow> for DataUsageAlert {
+    fn from(row: DataUsageAlertRow) -> Self {
+        DataUsageAlert {
+            period_start: row.period_start,
+            threshold: row.threshold.clamp(0, u8::MAX as i16) as u8,
+            usage_bytes: row.usage_bytes.max(0) as u64,
+            raised_at: row.raised_at,
+        }
+    }
+}
+
+impl Repository<PostgresUWP> for PostgresDataUsageRepository {}
+
+#[async_trait::async_trait]
+impl DataUsageRepository<PostgresUWP> for PostgresDataUsageRepository {
+    #[instrument(skip(connection))]
+    async fn fetch_counters<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+    ) -> RepositoryResult<Option<WanTrafficCounters>> {
+        Ok(sqlx::query_as::<Postgres, WanTrafficCountersRow>(
+            r#"
+            SELECT download_bytes, upload_bytes
+            FROM core.data_usage_counters
+            "#,
+        )
+        .fetch_optional(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .map(WanTrafficCounters::from))
+    }
+
+    #[instrument(skip(connection))]
+    async fn save_counters<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        counters: WanTrafficCounters,
+        sampled_at: DateTime<Utc>,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO core.data_usage_counters (download_bytes, upload_bytes, sampled_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (id) DO UPDATE
+            SET download_bytes = EXCLUDED.download_bytes,
+                upload_bytes = EXCLUDED.upload_bytes,
+                sampled_at = EXCLUDED.sampled_at,
+                updated_at = now()
+            "#,
+        )
+        .bind(to_bigint(counters.download_bytes))
+        .bind(to_bigint(counters.upload_bytes))
+        .bind(sampled_at)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+
+    #[instrument(skip(connection))]
+    async fn add_usage<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        period: BillingPeriod,
+        download_bytes: u64,
+        upload_bytes: u64,
+    ) -> RepositoryResult<DataUsagePeriod> {
+        Ok(sqlx::query_as::<Postgres, DataUsagePeriodRow>(
+            r#"
+            INSERT INTO core.data_usage_periods (period_start, period_end, download_bytes, upload_bytes)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (period_start) DO UPDATE
+            SET download_bytes = data_usage_periods.download_bytes + EXCLUDED.download_bytes,
+                upload_bytes = data_usage_periods.upload_bytes + EXCLUDED.upload_bytes,
+                updated_at = now()
+            RETURNING period_start, period_end, download_bytes, upload_bytes
+            "#,
+        )
+        .bind(period.start)
+        .bind(period.end)
+        .bind(to_bigint(download_bytes))
+        .bind(to_bigint(upload_bytes))
+        .fetch_one(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .into())
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_usage<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        period_start: NaiveDate,
+    ) -> RepositoryResult<Option<DataUsagePeriod>> {
+        Ok(sqlx::query_as::<Postgres, DataUsagePeriodRow>(
+            r#"
+            SELECT period_start, period_end, download_bytes, upload_bytes
+            FROM core.data_usage_periods
+            WHERE period_start = $1
+            "#,
+        )
+        .bind(period_start)
+        .fetch_optional(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .map(DataUsagePeriod::from))
+    }
+
+    #[instrument(skip(connection))]
+    async fn create_alert<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        alert: DataUsageAlert,
+    ) -> RepositoryResult<bool> {
+        sqlx::query(
+            r#"
+            INSERT INTO core.data_usage_alerts (period_start, threshold, usage_bytes, raised_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (period_start, threshold) DO NOTHING
+            "#,
+        )
+        .bind(alert.period_start)
+        .bind(alert.threshold as i16)
+        .bind(to_bigint(alert.usage_bytes))
+        .bind(alert.raised_at)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|result| result.rows_affected() > 0)
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_alerts<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        period_start: NaiveDate,
+    ) -> RepositoryResult<Vec<DataUsageAlert>> {
+        Ok(sqlx::query_as::<Postgres, DataUsageAlertRow>(
+            r#"
+            SELECT period_start, threshold, usage_bytes, raised_at
+            FROM core.data_usage_alerts
+            WHERE period_start = $1
+            ORDER BY raised_at, threshold
+            "#,
+        )
+        .bind(period_start)
+        .fetch_all(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .into_iter()
+        .map(DataUsageAlert::from)
+        .collect())
+    }
+}
@@ -0,0 +1,188 @@
+use chrono::{NaiveTime, Weekday};
+use entities::{AccessSlot, DeviceAccess};
+use ports::repositories::{DeviceAccessRepository, Repository, RepositoryError, RepositoryResult};
+use sqlx::{PgConnection, Postgres, prelude::FromRow, types::mac_address::MacAddress};
+use tracing::{error, instrument};
+
+use crate::{PostgresUWP, PostgresUoW, map_sqlx_error};
+
+#[derive(Clone)]
+pub struct PostgresDeviceAccessRepository;
+
+#[derive(FromRow)]
+struct DeviceAccessWithSlot {
+    mac_address: MacAddress,
+    is_blocked: bool,
+    slot_day: Option<i16>,
+    slot_start_time: Option<NaiveTime>,
+    slot_end_time: Option<NaiveTime>,
+}
+
+/// Groups the rows, ordered by device, into the access rules of each device.
+fn rows_to_device_access(rows: Vec<DeviceAccessWithSlot>) -> RepositoryResult<Vec<DeviceAccess>> {
+    let mut accesses: Vec<DeviceAccess> = Vec::new();
+
+    for row in rows {
+        if accesses
+            .last()
+            .is_none_or(|access| access.mac_address != row.mac_address)
+        {
+            accesses.push(DeviceAccess {
+                mac_address: row.mac_address,
+                is_blocked: row.is_blocked,
+                schedule: Vec::new(),
+            });
+        }
+
+        let (Some(day), Some(start), Some(end)) =
+            (row.slot_day, row.slot_start_time, row.slot_end_time)
+        else {
+            continue;
+        };
+
+        let day = u8::try_from(day)
+            .ok()
+            .and_then(|day| Weekday::try_from(day).ok())
+            .ok_or_else(|| {
+                error!("Failed to parse day from {}", day);
+                RepositoryError::Unknown
+            })?;
+
+        accesses
+            .last_mut()
+            .unwrap()
+            .schedule
+            .push(AccessSlot { day, start, end });
+    }
+
+    Ok(accesses)
+}
+
+impl Repository<PostgresUWP> for PostgresDeviceAccessRepository {}
+
+#[async_trait::async_trait]
+impl DeviceAccessRepository<PostgresUWP> for PostgresDeviceAccessRepository {
+    #[instrument(skip(connection))]
+    async fn fetch_all<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+    ) -> RepositoryResult<Vec<DeviceAccess>> {
+        rows_to_device_access(
+            sqlx::query_as::<Postgres, DeviceAccessWithSlot>(
+                r#"
+                SELECT
+                    a.mac_address,
+                    a.is_blocked,
+                    s.day as slot_day,
+                    s.start_time as slot_start_time,
+                    s.end_time as slot_end_time
+                FROM core.device_access a
+                LEFT JOIN core.device_access_slots s ON a.mac_address = s.mac_address
+                ORDER BY a.mac_address, s.day, s.start_time
+                "#,
+            )
+            .fetch_all(connection as &'a mut PgConnection)
+            .await
+            .map_err(map_sqlx_error)?,
+        )
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_one<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        mac_address: MacAddress,
+    ) -> RepositoryResult<Option<DeviceAccess>> {
+        Ok(rows_to_device_access(
+            sqlx::query_as::<Postgres, DeviceAccessWithSlot>(
+                r#"
+                SELECT
+                    a.mac_address,
+                    a.is_blocked,
+                    s.day as slot_day,
+                    s.start_time as slot_start_time,
+                    s.end_time as slot_end_time
+                FROM core.device_access a
+                LEFT JOIN core.device_access_slots s ON a.mac_address = s.mac_address
+                WHERE a.mac_address = $1
+                ORDER BY s.day, s.start_time
+                "#,
+            )
+            .bind(mac_address)
+            .fetch_all(connection as &'a mut PgConnection)
+            .await
+            .map_err(map_sqlx_error)?,
+        )?
+        .pop())
+    }
+
+    #[instrument(skip(connection))]
+    async fn save<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        access: DeviceAccess,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO core.device_access (mac_address, is_blocked)
+            VALUES ($1, $2)
+            ON CONFLICT (mac_address) DO UPDATE
+            SET is_blocked = EXCLUDED.is_blocked,
+                updated_at = now()
+            "#,
+        )
+        .bind(access.mac_address)
+        .bind(access.is_blocked)
+        .execute((&mut *connection) as &mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM core.device_access_slots
+            WHERE mac_address = $1
+            "#,
+        )
+        .bind(access.mac_address)
+        .execute((&mut *connection) as &mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        for slot in access.schedule {
+            sqlx::query(
+                r#"
+                INSERT INTO core.device_access_slots (
+                    mac_address,
+                    day,
+                    start_time,
+                    end_time
+                ) VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(access.mac_address)
+            .bind(slot.day.num_days_from_monday() as i16)
+            .bind(slot.start)
+            .bind(slot.end)
+            .execute((&mut *connection) as &mut PgConnection)
+            .await
+            .map_err(map_sqlx_error)?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(connection))]
+    async fn delete<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        mac_address: MacAddress,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM core.device_access
+            WHERE mac_address = $1
+            "#,
+        )
+        .bind(mac_address)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+}
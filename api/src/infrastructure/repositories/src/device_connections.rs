@@ -0,0 +1,155 @@
+use std::str::FromStr;
+
+use entities::{ConnectionType, DeviceConnection, WifiBand};
+use ports::repositories::{
+    DeviceConnectionsRepository, Repository, RepositoryError, RepositoryResult,
+};
+use sqlx::{PgConnection, Postgres, prelude::FromRow, types::mac_address::MacAddress};
+use tracing::{error, instrument};
+
+use crate::{PostgresUWP, PostgresUoW, map_sqlx_error};
+
+#[derive(Clone)]
+pub struct PostgresDeviceConnectionsRepository;
+
+#[derive(FromRow)]
+struct DeviceConnectionRow {
+    mac_address: MacAddress,
+    connection_type: String,
+    band: Option<String>,
+    rssi: Option<i16>,
+    link_rate: Option<i32>,
+    access_point: Option<String>,
+}
+
+impl TryFrom<DeviceConnectionRow> for DeviceConnection {
+    type Error = RepositoryError;
+
+    fn try_from(row: DeviceConnectionRow) -> RepositoryResult<Self> {
+        Ok(DeviceConnection {
+            connection_type: ConnectionType::from_str(&row.connection_type).map_err(|_| {
+                error!(
+                    "Failed to parse connection_type from {}",
+                    row.connection_type
+                );
+                RepositoryError::Unknown
+            })?,
+            band: row
+                .band
+                .map(|band| {
+                    WifiBand::from_str(&band).map_err(|_| {
+                        error!("Failed to parse band from {}", band);
+                        RepositoryError::Unknown
+                    })
+                })
+                .transpose()?,
+            mac_address: row.mac_address,
+            rssi: row.rssi,
+            link_rate: row
+                .link_rate
+                .and_then(|link_rate| link_rate.try_into().ok()),
+            access_point: row.access_point,
+        })
+    }
+}
+
+impl Repository<PostgresUWP> for PostgresDeviceConnectionsRepository {}
+
+#[async_trait::async_trait]
+impl DeviceConnectionsRepository<PostgresUWP> for PostgresDeviceConnectionsRepository {
+    #[instrument(skip(connection))]
+    async fn fetch_all<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+    ) -> RepositoryResult<Vec<DeviceConnection>> {
+        sqlx::query_as::<Postgres, DeviceConnectionRow>(
+            r#"
+            SELECT mac_address, connection_type, band, rssi, link_rate, access_point
+            FROM core.device_connections
+            "#,
+        )
+        .fetch_all(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .into_iter()
+        .map(DeviceConnection::try_from)
+        .collect()
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_one<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        mac_address: MacAddress,
+    ) -> RepositoryResult<Option<DeviceConnection>> {
+        sqlx::query_as::<Postgres, DeviceConnectionRow>(
+            r#"
+            SELECT mac_address, connection_type, band, rssi, link_rate, access_point
+            FROM core.device_connections
+            WHERE mac_address = $1
+            "#,
+        )
+        .bind(mac_address)
+        .fetch_optional(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .map(DeviceConnection::try_from)
+        .transpose()
+    }
+
+    #[instrument(skip(connection))]
+    async fn save<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        device_connection: DeviceConnection,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO core.device_connections (
+                mac_address,
+                connection_type,
+                band,
+                rssi,
+                link_rate,
+                access_point
+            ) VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (mac_address) DO UPDATE
+            SET connection_type = EXCLUDED.connection_type,
+                band = EXCLUDED.band,
+                rssi = EXCLUDED.rssi,
+                link_rate = EXCLUDED.link_rate,
+                access_point = EXCLUDED.access_point,
+                updated_at = now()
+            "#,
+        )
+        .bind(device_connection.mac_address)
+        .bind(device_connection.connection_type.to_string())
+        .bind(device_connection.band.map(|band| band.to_string()))
+        .bind(device_connection.rssi)
+        .bind(
+            device_connection
+                .link_rate
+                .map(|link_rate| link_rate.min(i32::MAX as u32) as i32),
+        )
+        .bind(device_connection.access_point)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+
+    #[instrument(skip(connection))]
+    async fn delete<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        mac_address: MacAddress,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM core.device_connections
+            WHERE mac_address = $1
+            "#,
+        )
+        .bind(mac_address)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,203 @@
+use chrono::{DateTime, Utc};
+use entities::{
+    DeviceTrafficCounters, DeviceTrafficSample, DeviceUsage, UsageBucket, UsageInterval,
+};
+use ports::repositories::{DeviceTrafficRepository, Repository, RepositoryResult};
+use sqlx::{PgConnection, Postgres, prelude::FromRow, types::mac_address::MacAddress};
+use tracing::instrument;
+
+use crate::{PostgresUWP, PostgresUoW, map_sqlx_error, to_bigint};
+
+#[derive(Clone)]
+pub struct PostgresDeviceTrafficRepository;
+
+#[derive(FromRow)]
+struct DeviceTrafficCountersRow {
+    mac_address: MacAddress,
+    rx_bytes: i64,
+    tx_bytes: i64,
+}
+
+impl From<DeviceTrafficCountersRow> for DeviceTrafficCounters {
+    fn from(row: DeviceTrafficCountersRow) -> Self {
+        DeviceTrafficCounters {
+            mac_address: row.mac_address,
+            rx_bytes: row.rx_bytes.max(0) as u64,
+            tx_bytes: row.tx_bytes.max(0) as u64,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct DeviceUsageRow {
+    mac_address: MacAddress,
+    display_name: String,
+    rx_bytes: i64,
+    tx_bytes: i64,
+}
+
+impl From<DeviceUsageRow> for DeviceUsage {
+    fn from(row: DeviceUsageRow) -> Self {
+        let rx_bytes = row.rx_bytes.max(0) as u64;
+        let tx_bytes = row.tx_bytes.max(0) as u64;
+
+        DeviceUsage {
+            mac_address: row.mac_address,
+            display_name: row.display_name,
+            rx_bytes,
+            tx_bytes,
+            total_bytes: rx_bytes + tx_bytes,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct UsageBucketRow {
+    start: DateTime<Utc>,
+    rx_bytes: i64,
+    tx_bytes: i64,
+}
+
+impl From<UsageBucketRow> for UsageBucket {
+    fn from(row: UsageBucketRow) -> Self {
+        UsageBucket {
+            start: row.start,
+            rx_bytes: row.rx_bytes.max(0) as u64,
+            tx_bytes: row.tx_bytes.max(0) as u64,
+        }
+    }
+}
+
+impl Repository<PostgresUWP> for PostgresDeviceTrafficRepository {}
+
+#[async_trait::async_trait]
+impl DeviceTrafficRepository<PostgresUWP> for PostgresDeviceTrafficRepository {
+    #[instrument(skip(connection))]
+    async fn fetch_counters<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+    ) -> RepositoryResult<Vec<DeviceTrafficCounters>> {
+        Ok(sqlx::query_as::<Postgres, DeviceTrafficCountersRow>(
+            r#"
+            SELECT mac_address, rx_bytes, tx_bytes
+            FROM core.device_traffic_counters
+            "#,
+        )
+        .fetch_all(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .into_iter()
+        .map(DeviceTrafficCounters::from)
+        .collect())
+    }
+
+    #[instrument(skip(connection))]
+    async fn save_counters<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        counters: DeviceTrafficCounters,
+        sampled_at: DateTime<Utc>,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO core.device_traffic_counters (mac_address, rx_bytes, tx_bytes, sampled_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (mac_address) DO UPDATE
+            SET rx_bytes = EXCLUDED.rx_bytes,
+                tx_bytes = EXCLUDED.tx_bytes,
+                sampled_at = EXCLUDED.sampled_at,
+                updated_at = now()
+            "#,
+        )
+        .bind(counters.mac_address)
+        .bind(to_bigint(counters.rx_bytes))
+        .bind(to_bigint(counters.tx_bytes))
+        .bind(sampled_at)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+
+    #[instrument(skip(connection))]
+    async fn create_sample<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        sample: DeviceTrafficSample,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO core.device_traffic (mac_address, sampled_at, rx_bytes, tx_bytes)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(sample.mac_address)
+        .bind(sample.sampled_at)
+        .bind(to_bigint(sample.rx_bytes))
+        .bind(to_bigint(sample.tx_bytes))
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_top_talkers<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: usize,
+    ) -> RepositoryResult<Vec<DeviceUsage>> {
+        Ok(sqlx::query_as::<Postgres, DeviceUsageRow>(
+            r#"
+            SELECT t.mac_address,
+                   d.display_name,
+                   sum(t.rx_bytes)::bigint as rx_bytes,
+                   sum(t.tx_bytes)::bigint as tx_bytes
+            FROM core.device_traffic t
+            JOIN core.devices d ON d.mac_address = t.mac_address
+            WHERE t.sampled_at > $1 AND t.sampled_at <= $2
+            GROUP BY t.mac_address, d.display_name
+            ORDER BY sum(t.rx_bytes + t.tx_bytes) DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(limit as i64)
+        .fetch_all(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .into_iter()
+        .map(DeviceUsage::from)
+        .collect())
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_usage<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        mac_address: MacAddress,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        interval: UsageInterval,
+    ) -> RepositoryResult<Vec<UsageBucket>> {
+        Ok(sqlx::query_as::<Postgres, UsageBucketRow>(
+            r#"
+            SELECT date_trunc($4, sampled_at, 'UTC') as start,
+                   sum(rx_bytes)::bigint as rx_bytes,
+                   sum(tx_bytes)::bigint as tx_bytes
+            FROM core.device_traffic
+            WHERE mac_address = $1 AND sampled_at > $2 AND sampled_at <= $3
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+        )
+        .bind(mac_address)
+        .bind(from)
+        .bind(to)
+        .bind(interval.to_string())
+        .fetch_all(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .into_iter()
+        .map(UsageBucket::from)
+        .collect())
+    }
+}
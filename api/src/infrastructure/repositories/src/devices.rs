@@ -1,9 +1,12 @@
-use entities::{Device, Pagination, ToSql};
+use entities::{Device, DeviceFilter, DeviceQuery};
 use ports::repositories::{DevicesRepository, Repository, RepositoryResult};
-use sqlx::{PgConnection, types::mac_address::MacAddress};
+use sqlx::{PgConnection, Postgres, QueryBuilder, types::mac_address::MacAddress};
 use tracing::instrument;
 
-use crate::{PostgresUWP, PostgresUoW, map_sqlx_error};
+use crate::{
+    PostgresUWP, PostgresUoW, map_sqlx_error,
+    query::{contains_pattern, push_order, push_paging_condition, push_paging_limit},
+};
 
 #[derive(Clone)]
 pub struct PostgresDevicesRepository;
@@ -15,15 +18,39 @@ impl DevicesRepository<PostgresUWP> for PostgresDevicesRepository {
     #[instrument(skip(connection))]
     async fn fetch_all<'a>(
         connection: &'a mut PostgresUoW<'_>,
-        pagination: Option<Pagination>,
+        query: DeviceQuery,
     ) -> RepositoryResult<Vec<Device>> {
-        sqlx::query_as(&format!(
-            "SELECT * FROM core.devices {}",
-            pagination.to_sql()
-        ))
-        .fetch_all(connection as &'a mut PgConnection)
-        .await
-        .map_err(map_sqlx_error)
+        let mut builder =
+            QueryBuilder::<Postgres>::new("SELECT d.* FROM core.devices d WHERE true");
+
+        push_filter(&mut builder, query.filter);
+        push_paging_condition(&mut builder, query.sort, query.paging.as_ref());
+        push_order(&mut builder, query.sort);
+        push_paging_limit(&mut builder, query.paging.as_ref());
+
+        builder
+            .build_query_as()
+            .fetch_all(connection as &'a mut PgConnection)
+            .await
+            .map_err(map_sqlx_error)
+    }
+
+    #[instrument(skip(connection))]
+    async fn count<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        filter: DeviceFilter,
+    ) -> RepositoryResult<u64> {
+        let mut builder =
+            QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM core.devices d WHERE true");
+
+        push_filter(&mut builder, filter);
+
+        builder
+            .build_query_scalar::<i64>()
+            .fetch_one(connection as &'a mut PgConnection)
+            .await
+            .map(|count| count as u64)
+            .map_err(map_sqlx_error)
     }
 
     #[instrument(skip(connection))]
@@ -80,20 +107,17 @@ impl DevicesRepository<PostgresUWP> for PostgresDevicesRepository {
             r#"
             UPDATE core.devices
             SET last_known_ip = $2,
-                display_name = $3,
-                is_name_custom = $4,
-                notes = $5,
-                is_online = $6,
-                last_seen = $7,
-                last_scanned = $8
+                display_name = CASE WHEN is_name_custom THEN display_name ELSE $3 END,
+                is_online = $4,
+                last_seen = $5,
+                last_scanned = $6,
+                updated_at = now()
             WHERE mac_address = $1
             "#,
         )
         .bind(device.mac_address)
         .bind(device.last_known_ip)
         .bind(device.display_name)
-        .bind(device.is_name_custom)
-        .bind(device.notes)
         .bind(device.is_online)
         .bind(device.last_seen)
         .bind(device.last_scanned)
@@ -102,4 +126,79 @@ impl DevicesRepository<PostgresUWP> for PostgresDevicesRepository {
         .map_err(map_sqlx_error)
         .map(|_| ())
     }
+
+    #[instrument(skip(connection))]
+    async fn update_details<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        mac_address: MacAddress,
+        display_name: String,
+        is_name_custom: bool,
+        notes: String,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE core.devices
+            SET display_name = $2,
+                is_name_custom = $3,
+                notes = $4,
+                updated_at = now()
+            WHERE mac_address = $1
+            "#,
+        )
+        .bind(mac_address)
+        .bind(display_name)
+        .bind(is_name_custom)
+        .bind(notes)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+
+    #[instrument(skip(connection))]
+    async fn delete<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        mac_address: MacAddress,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM core.devices
+            WHERE mac_address = $1
+            "#,
+        )
+        .bind(mac_address)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+}
+
+fn push_filter(builder: &mut QueryBuilder<'_, Postgres>, filter: DeviceFilter) {
+    if let Some(is_online) = filter.is_online {
+        builder.push(" AND d.is_online = ").push_bind(is_online);
+    }
+    if let Some(search) = filter.search {
+        let pattern = contains_pattern(&search);
+        builder
+            .push(" AND (d.display_name ILIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR host(d.last_known_ip) ILIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR d.mac_address::text ILIKE ")
+            .push_bind(pattern)
+            .push(")");
+    }
+    if let Some(from) = filter.last_seen_from {
+        builder.push(" AND d.last_seen >= ").push_bind(from);
+    }
+    if let Some(to) = filter.last_seen_to {
+        builder.push(" AND d.last_seen < ").push_bind(to);
+    }
+    if let Some(kind) = filter.service_kind {
+        builder
+            .push(" AND EXISTS (SELECT 1 FROM core.services s WHERE s.device_mac = d.mac_address AND s.kind = ")
+            .push_bind(kind.to_string())
+            .push(")");
+    }
 }
@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use ports::repositories::{GuestNetworkRepository, Repository, RepositoryResult};
+use sqlx::PgConnection;
+use tracing::instrument;
+
+use crate::{PostgresUWP, PostgresUoW, map_sqlx_error};
+
+#[derive(Clone)]
+pub struct PostgresGuestNetworkRepository;
+
+impl Repository<PostgresUWP> for PostgresGuestNetworkRepository {}
+
+#[async_trait::async_trait]
+impl GuestNetworkRepository<PostgresUWP> for PostgresGuestNetworkRepository {
+    #[instrument(skip(connection))]
+    async fn fetch_expiry<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+    ) -> RepositoryResult<Option<DateTime<Utc>>> {
+        sqlx::query_scalar(
+            r#"
+            SELECT expires_at
+            FROM core.guest_network_expiry
+            "#,
+        )
+        .fetch_optional(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+    }
+
+    #[instrument(skip(connection))]
+    async fn save_expiry<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO core.guest_network_expiry (expires_at)
+            VALUES ($1)
+            ON CONFLICT (id) DO UPDATE
+            SET expires_at = EXCLUDED.expires_at,
+                updated_at = now()
+            "#,
+        )
+        .bind(expires_at)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+
+    #[instrument(skip(connection))]
+    async fn delete_expiry<'a>(connection: &'a mut PostgresUoW<'_>) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM core.guest_network_expiry
+            "#,
+        )
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,105 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use entities::IpAssignment;
+use ports::repositories::{IpHistoryRepository, Repository, RepositoryResult};
+use sqlx::{PgConnection, Postgres, prelude::FromRow, types::mac_address::MacAddress};
+use tracing::instrument;
+
+use crate::{PostgresUWP, PostgresUoW, map_sqlx_error};
+
+#[derive(Clone)]
+pub struct PostgresIpHistoryRepository;
+
+#[derive(FromRow)]
+struct IpAssignmentRow {
+    mac_address: MacAddress,
+    display_name: Option<String>,
+    ip_address: IpAddr,
+    first_seen: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+}
+
+impl From<IpAssignmentRow> for IpAssignment {
+    fn from(row: IpAssignmentRow) -> Self {
+        IpAssignment {
+            mac_address: row.mac_address,
+            display_name: row.display_name,
+            ip_address: row.ip_address,
+            first_seen: row.first_seen,
+            last_seen: row.last_seen,
+        }
+    }
+}
+
+impl Repository<PostgresUWP> for PostgresIpHistoryRepository {}
+
+#[async_trait::async_trait]
+impl IpHistoryRepository<PostgresUWP> for PostgresIpHistoryRepository {
+    #[instrument(skip(connection))]
+    async fn record<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        mac_address: MacAddress,
+        ip_address: IpAddr,
+        seen_at: DateTime<Utc>,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            WITH extended AS (
+                UPDATE core.ip_history h
+                SET last_seen = greatest(h.last_seen, $3), updated_at = now()
+                WHERE h.mac_address = $1 AND h.ip_address = $2
+                  AND h.first_seen = (
+                      SELECT max(first_seen)
+                      FROM core.ip_history
+                      WHERE mac_address = $1 AND ip_address = $2
+                  )
+                  AND NOT EXISTS (
+                      SELECT 1
+                      FROM core.ip_history other
+                      WHERE other.ip_address = $2
+                        AND other.mac_address <> $1
+                        AND other.last_seen > h.last_seen
+                  )
+                RETURNING 1
+            )
+            INSERT INTO core.ip_history (mac_address, ip_address, first_seen, last_seen)
+            SELECT $1, $2, $3, $3
+            WHERE NOT EXISTS (SELECT 1 FROM extended)
+            "#,
+        )
+        .bind(mac_address)
+        .bind(ip_address)
+        .bind(seen_at)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_by_ip<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        ip_address: IpAddr,
+        at: Option<DateTime<Utc>>,
+    ) -> RepositoryResult<Vec<IpAssignment>> {
+        Ok(sqlx::query_as::<Postgres, IpAssignmentRow>(
+            r#"
+            SELECT h.mac_address, d.display_name, h.ip_address, h.first_seen, h.last_seen
+            FROM core.ip_history h
+            LEFT JOIN core.devices d ON d.mac_address = h.mac_address
+            WHERE h.ip_address = $1
+              AND ($2::timestamptz IS NULL OR $2 BETWEEN h.first_seen AND h.last_seen)
+            ORDER BY h.first_seen DESC
+            "#,
+        )
+        .bind(ip_address)
+        .bind(at)
+        .fetch_all(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .into_iter()
+        .map(IpAssignment::from)
+        .collect())
+    }
+}
@@ -1,24 +1,72 @@
+mod config_snapshots;
+mod data_usage;
+mod device_access;
+mod device_connections;
+mod device_traffic;
 mod devices;
+mod guest_network;
+mod ip_history;
+mod port_forward_links;
+mod presence_events;
+mod query;
 mod services;
+mod wan_outages;
+mod wan_stats;
 
+use common::DatabaseConfig;
+pub use config_snapshots::*;
+pub use data_usage::*;
+pub use device_access::*;
+pub use device_connections::*;
+pub use device_traffic::*;
 pub use devices::*;
-use entities::SharedLockedReference;
+pub use guest_network::*;
+pub use ip_history::*;
+pub use port_forward_links::*;
 use ports::repositories::{RepositoryError, RepositoryResult, UnitOfWorkProvider};
+pub use presence_events::*;
 pub use services::*;
-use sqlx::PgTransaction;
-use tracing::error;
+use sqlx::{
+    PgPool, PgTransaction,
+    postgres::{PgConnectOptions, PgPoolOptions},
+};
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+use tracing::{error, warn};
+pub use wan_outages::*;
+pub use wan_stats::*;
 
 type PostgresUoW<'a> = PgTransaction<'a>;
 
+/// Transactions are started concurrently, each one holding a connection of the pool until it ends.
 #[derive(Clone)]
 pub struct PostgresUWP {
-    pool: SharedLockedReference<sqlx::PgPool>,
+    pool: PgPool,
 }
 
 impl PostgresUWP {
-    pub fn new(pool: SharedLockedReference<sqlx::PgPool>) -> Self {
+    pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
+
+    pub async fn connect(config: &DatabaseConfig) -> Result<Self, sqlx::Error> {
+        let mut connect_options = PgConnectOptions::from_str(config.url.as_str())?;
+        if let Some(statement_timeout) = config.statement_timeout {
+            connect_options =
+                connect_options.options([("statement_timeout", statement_timeout.to_string())]);
+        }
+
+        let pool = PgPoolOptions::new()
+            .max_connections(config.max_connections)
+            .min_connections(config.min_connections)
+            .acquire_timeout(Duration::from_secs(config.acquire_timeout))
+            .connect_with(connect_options)
+            .await?;
+
+        Ok(Self::new(pool))
+    }
 }
 
 #[async_trait::async_trait]
@@ -29,8 +77,21 @@ impl UnitOfWorkProvider for PostgresUWP {
         Self: 'a;
 
     async fn begin_transaction<'a>(&'a self) -> RepositoryResult<PostgresUoW<'a>> {
-        let pool = self.pool.lock().await;
-        pool.begin().await.map_err(map_sqlx_error)
+        let saturated = self.pool.num_idle() == 0
+            && self.pool.size() >= self.pool.options().get_max_connections();
+        let start = Instant::now();
+
+        let uow = self.pool.begin().await.map_err(map_sqlx_error)?;
+
+        if saturated {
+            warn!(
+                size = self.pool.size(),
+                waited = ?start.elapsed(),
+                "The database pool is saturated, the transaction waited for a connection"
+            );
+        }
+
+        Ok(uow)
     }
 
     async fn commit<'a>(&'a self, uow: PostgresUoW<'a>) -> RepositoryResult<()> {
@@ -42,6 +103,11 @@ impl UnitOfWorkProvider for PostgresUWP {
     }
 }
 
+/// Byte counts are stored as `bigint`, which cannot hold the full range of `u64`.
+pub(crate) fn to_bigint(bytes: u64) -> i64 {
+    bytes.min(i64::MAX as u64) as i64
+}
+
 pub(crate) fn map_sqlx_error(err: sqlx::Error) -> RepositoryError {
     match err {
         sqlx::Error::RowNotFound => RepositoryError::NotFound,
@@ -0,0 +1,110 @@
+use std::str::FromStr;
+
+use entities::{PortForwardLink, TransportProtocol};
+use ports::repositories::{
+    PortForwardLinksRepository, Repository, RepositoryError, RepositoryResult,
+};
+use sqlx::{PgConnection, Postgres, prelude::FromRow};
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::{PostgresUWP, PostgresUoW, map_sqlx_error};
+
+#[derive(Clone)]
+pub struct PostgresPortForwardLinksRepository;
+
+#[derive(FromRow)]
+struct PortForwardLinkRow {
+    port_forward_id: String,
+    service_id: Uuid,
+    #[sqlx(try_from = "i32")]
+    port: u16,
+    transport_protocol: String,
+}
+
+impl TryFrom<PortForwardLinkRow> for PortForwardLink {
+    type Error = RepositoryError;
+
+    fn try_from(row: PortForwardLinkRow) -> RepositoryResult<Self> {
+        Ok(PortForwardLink {
+            transport_protocol: TransportProtocol::from_str(&row.transport_protocol).map_err(
+                |_| {
+                    error!(
+                        "Failed to parse transport_protocol from {}",
+                        row.transport_protocol
+                    );
+                    RepositoryError::Unknown
+                },
+            )?,
+            port_forward_id: row.port_forward_id,
+            service_id: row.service_id,
+            port: row.port,
+        })
+    }
+}
+
+impl Repository<PostgresUWP> for PostgresPortForwardLinksRepository {}
+
+#[async_trait::async_trait]
+impl PortForwardLinksRepository<PostgresUWP> for PostgresPortForwardLinksRepository {
+    #[instrument(skip(connection))]
+    async fn fetch_all<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+    ) -> RepositoryResult<Vec<PortForwardLink>> {
+        sqlx::query_as::<Postgres, PortForwardLinkRow>(
+            r#"
+            SELECT port_forward_id, service_id, port, transport_protocol
+            FROM core.port_forward_links
+            "#,
+        )
+        .fetch_all(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .into_iter()
+        .map(PortForwardLink::try_from)
+        .collect()
+    }
+
+    #[instrument(skip(connection))]
+    async fn create<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        link: PortForwardLink,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO core.port_forward_links (
+                port_forward_id,
+                service_id,
+                port,
+                transport_protocol
+            ) VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(link.port_forward_id)
+        .bind(link.service_id)
+        .bind(link.port as i32)
+        .bind(link.transport_protocol.to_string())
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+
+    #[instrument(skip(connection))]
+    async fn delete<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        port_forward_id: &str,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM core.port_forward_links
+            WHERE port_forward_id = $1
+            "#,
+        )
+        .bind(port_forward_id)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,127 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use entities::{PresenceEvent, PresenceEventKind};
+use ports::repositories::{
+    PresenceEventsRepository, Repository, RepositoryError, RepositoryResult,
+};
+use sqlx::{PgConnection, Postgres, prelude::FromRow, types::mac_address::MacAddress};
+use tracing::{error, instrument};
+
+use crate::{PostgresUWP, PostgresUoW, map_sqlx_error};
+
+#[derive(Clone)]
+pub struct PostgresPresenceEventsRepository;
+
+#[derive(FromRow)]
+struct PresenceEventRow {
+    mac_address: MacAddress,
+    kind: String,
+    is_online: bool,
+    occurred_at: DateTime<Utc>,
+}
+
+impl TryFrom<PresenceEventRow> for PresenceEvent {
+    type Error = RepositoryError;
+
+    fn try_from(row: PresenceEventRow) -> RepositoryResult<Self> {
+        Ok(PresenceEvent {
+            mac_address: row.mac_address,
+            kind: PresenceEventKind::from_str(&row.kind).map_err(|_| {
+                error!("Failed to parse kind from {}", row.kind);
+                RepositoryError::Unknown
+            })?,
+            is_online: row.is_online,
+            occurred_at: row.occurred_at,
+        })
+    }
+}
+
+#[derive(FromRow)]
+struct FirstSeenRow {
+    mac_address: MacAddress,
+    created_at: DateTime<Utc>,
+}
+
+impl Repository<PostgresUWP> for PostgresPresenceEventsRepository {}
+
+#[async_trait::async_trait]
+impl PresenceEventsRepository<PostgresUWP> for PostgresPresenceEventsRepository {
+    #[instrument(skip(connection))]
+    async fn create<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        event: PresenceEvent,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO core.presence_events (mac_address, occurred_at, kind, is_online)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(event.mac_address)
+        .bind(event.occurred_at)
+        .bind(event.kind.to_string())
+        .bind(event.is_online)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_between<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        mac_address: Option<MacAddress>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> RepositoryResult<Vec<PresenceEvent>> {
+        sqlx::query_as::<Postgres, PresenceEventRow>(
+            r#"
+            (
+                SELECT DISTINCT ON (mac_address) mac_address, kind, is_online, occurred_at
+                FROM core.presence_events
+                WHERE occurred_at < $2 AND ($1::macaddr IS NULL OR mac_address = $1)
+                ORDER BY mac_address, occurred_at DESC
+            )
+            UNION ALL
+            (
+                SELECT mac_address, kind, is_online, occurred_at
+                FROM core.presence_events
+                WHERE occurred_at >= $2 AND occurred_at < $3
+                  AND ($1::macaddr IS NULL OR mac_address = $1)
+            )
+            ORDER BY mac_address, occurred_at
+            "#,
+        )
+        .bind(mac_address)
+        .bind(from)
+        .bind(to)
+        .fetch_all(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .into_iter()
+        .map(PresenceEvent::try_from)
+        .collect()
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_first_seen<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        mac_address: Option<MacAddress>,
+    ) -> RepositoryResult<Vec<(MacAddress, DateTime<Utc>)>> {
+        Ok(sqlx::query_as::<Postgres, FirstSeenRow>(
+            r#"
+            SELECT mac_address, coalesce(created_at, last_seen, now()) as created_at
+            FROM core.devices
+            WHERE $1::macaddr IS NULL OR mac_address = $1
+            "#,
+        )
+        .bind(mac_address)
+        .fetch_all(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .into_iter()
+        .map(|row| (row.mac_address, row.created_at))
+        .collect())
+    }
+}
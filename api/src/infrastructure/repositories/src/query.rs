@@ -0,0 +1,221 @@
+use entities::{DeviceSortField, Paging, ServiceSortField, Sort, SortOrder};
+use sqlx::{Postgres, QueryBuilder};
+
+/// A column a listing is sorted by, cursor values being cast to its type.
+#[derive(Clone, Copy)]
+pub(crate) struct Column {
+    name: &'static str,
+    sql_type: &'static str,
+    nullable: bool,
+}
+
+const fn column(name: &'static str, sql_type: &'static str, nullable: bool) -> Column {
+    Column {
+        name,
+        sql_type,
+        nullable,
+    }
+}
+
+/// Sort fields are translated to a fixed set of columns, user input never reaches the SQL itself.
+pub(crate) trait SortColumn: Copy {
+    /// Unique column keeping the order stable.
+    const KEY: Column;
+
+    fn column(self) -> Column;
+}
+
+impl SortColumn for DeviceSortField {
+    const KEY: Column = column("d.mac_address", "macaddr", false);
+
+    fn column(self) -> Column {
+        match self {
+            DeviceSortField::DisplayName => column("d.display_name", "text", false),
+            DeviceSortField::MacAddress => column("d.mac_address", "macaddr", false),
+            DeviceSortField::LastKnownIp => column("d.last_known_ip", "inet", true),
+            DeviceSortField::IsOnline => column("d.is_online", "boolean", false),
+            DeviceSortField::LastSeen => column("d.last_seen", "timestamptz", true),
+            DeviceSortField::LastScanned => column("d.last_scanned", "timestamptz", false),
+        }
+    }
+}
+
+impl SortColumn for ServiceSortField {
+    const KEY: Column = column("s.service_id", "uuid", false);
+
+    fn column(self) -> Column {
+        match self {
+            ServiceSortField::DisplayName => column("s.display_name", "text", false),
+            ServiceSortField::Kind => column("s.kind", "text", false),
+            ServiceSortField::DeviceMac => column("s.device_mac", "macaddr", false),
+        }
+    }
+}
+
+/// Pushes the ORDER BY clause, ties being broken by the key of the rows in the same direction.
+/// Rows without a value come last in both directions.
+pub(crate) fn push_order<F: SortColumn>(builder: &mut QueryBuilder<'_, Postgres>, sort: Sort<F>) {
+    let direction = match sort.order {
+        SortOrder::Asc => " ASC NULLS LAST",
+        SortOrder::Desc => " DESC NULLS LAST",
+    };
+
+    builder
+        .push(" ORDER BY ")
+        .push(sort.field.column().name)
+        .push(direction)
+        .push(", ")
+        .push(F::KEY.name)
+        .push(direction);
+}
+
+/// Pushes the condition keeping the rows following the cursor of a keyset pagination. Cursors are
+/// built from rows with a value, the rows without one follow them.
+pub(crate) fn push_paging_condition<F: SortColumn>(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    sort: Sort<F>,
+    paging: Option<&Paging>,
+) {
+    let Some(Paging::Keyset {
+        after: Some(cursor),
+        ..
+    }) = paging
+    else {
+        return;
+    };
+
+    let (column, key) = (sort.field.column(), F::KEY);
+    let comparison = match sort.order {
+        SortOrder::Asc => ") > (CAST(",
+        SortOrder::Desc => ") < (CAST(",
+    };
+
+    builder
+        .push(" AND ((")
+        .push(column.name)
+        .push(", ")
+        .push(key.name)
+        .push(comparison)
+        .push_bind(cursor.value.clone())
+        .push(" AS ")
+        .push(column.sql_type)
+        .push("), CAST(")
+        .push_bind(cursor.key.clone())
+        .push(" AS ")
+        .push(key.sql_type)
+        .push("))");
+    if column.nullable {
+        builder.push(" OR ").push(column.name).push(" IS NULL");
+    }
+    builder.push(")");
+}
+
+pub(crate) fn push_paging_limit(builder: &mut QueryBuilder<'_, Postgres>, paging: Option<&Paging>) {
+    match paging {
+        Some(Paging::Offset(pagination)) => {
+            builder
+                .push(" LIMIT ")
+                .push_bind(i64::from(pagination.limit))
+                .push(" OFFSET ")
+                .push_bind(i64::from(pagination.offset()));
+        }
+        Some(Paging::Keyset { limit, .. }) => {
+            builder.push(" LIMIT ").push_bind(i64::from(*limit));
+        }
+        None => {}
+    }
+}
+
+/// Turns a search into an ILIKE pattern matching it literally anywhere in the value.
+pub(crate) fn contains_pattern(search: &str) -> String {
+    let escaped = search
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{escaped}%")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use entities::{Cursor, DeviceSortField};
+    use sqlx::{PgPool, types::mac_address::MacAddress};
+
+    use super::*;
+
+    /// Lists the devices of `devices_paging` a page at a time, returning them in order.
+    async fn page_through(pool: &PgPool, order: SortOrder, limit: u32) -> Vec<MacAddress> {
+        let sort = Sort {
+            field: DeviceSortField::LastKnownIp,
+            order,
+        };
+        let mut paging = Paging::Keyset { after: None, limit };
+        let mut listed = Vec::new();
+
+        loop {
+            let mut builder = QueryBuilder::<Postgres>::new(
+                "SELECT d.mac_address, d.last_known_ip FROM devices_paging d WHERE true",
+            );
+            push_paging_condition(&mut builder, sort, Some(&paging));
+            push_order(&mut builder, sort);
+            push_paging_limit(&mut builder, Some(&paging));
+
+            let page = builder
+                .build_query_as::<(MacAddress, Option<IpAddr>)>()
+                .fetch_all(pool)
+                .await
+                .unwrap();
+            listed.extend(page.iter().map(|(mac_address, _)| *mac_address));
+
+            let Some((mac_address, last_known_ip)) =
+                page.last().filter(|_| page.len() == limit as usize)
+            else {
+                return listed;
+            };
+            paging = Paging::Keyset {
+                after: Some(Cursor {
+                    field: sort.field.to_string(),
+                    value: last_known_ip
+                        .expect("the rows without a value fit in the last page")
+                        .to_string(),
+                    key: mac_address.to_string(),
+                }),
+                limit,
+            };
+        }
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL server, set DATABASE_URL to run it"]
+    async fn keyset_paging_lists_tied_and_null_values_once(pool: PgPool) {
+        sqlx::raw_sql(
+            r#"
+            CREATE TABLE devices_paging (mac_address macaddr PRIMARY KEY, last_known_ip inet);
+            INSERT INTO devices_paging VALUES
+                ('00:00:00:00:00:01', '10.0.0.1'),
+                ('00:00:00:00:00:02', '10.0.0.2'),
+                ('00:00:00:00:00:03', '10.0.0.2'),
+                ('00:00:00:00:00:04', '10.0.0.2'),
+                ('00:00:00:00:00:05', '10.0.0.2'),
+                ('00:00:00:00:00:06', '10.0.0.3'),
+                ('00:00:00:00:00:07', NULL),
+                ('00:00:00:00:00:08', NULL);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let mac = |n: u8| MacAddress::new([0, 0, 0, 0, 0, n]);
+
+        // Ties span the page boundaries, the rows without a value come last in both directions
+        assert_eq!(
+            page_through(&pool, SortOrder::Asc, 3).await,
+            [1, 2, 3, 4, 5, 6, 7, 8].map(mac)
+        );
+        assert_eq!(
+            page_through(&pool, SortOrder::Desc, 3).await,
+            [6, 5, 4, 3, 2, 1, 8, 7].map(mac)
+        );
+    }
+}
@@ -1,14 +1,20 @@
 use std::{collections::HashSet, str::FromStr};
 
 use entities::{
-    ApplicationProtocol, Service, ServiceKind, ServicePort, ServicePortTemplate, TransportProtocol,
+    ApplicationProtocol, Service, ServiceFilter, ServiceKind, ServicePort, ServicePortTemplate,
+    ServiceQuery, TransportProtocol,
 };
 use ports::repositories::{Repository, RepositoryError, RepositoryResult, ServicesRepository};
-use sqlx::{PgConnection, Postgres, prelude::FromRow, types::mac_address::MacAddress};
+use sqlx::{
+    PgConnection, Postgres, QueryBuilder, prelude::FromRow, types::mac_address::MacAddress,
+};
 use tracing::{error, instrument};
 use uuid::Uuid;
 
-use crate::{PostgresUWP, PostgresUoW, map_sqlx_error};
+use crate::{
+    PostgresUWP, PostgresUoW, map_sqlx_error,
+    query::{contains_pattern, push_order, push_paging_condition, push_paging_limit},
+};
 
 #[derive(Clone)]
 pub struct PostgresServicesRepository;
@@ -21,12 +27,12 @@ struct ServiceWithPort {
     pub service_kind: String,
     pub service_is_managed: bool,
     pub service_token: String,
-    pub port_name: String,
-    #[sqlx(try_from = "i32")]
-    pub port_port: u16,
-    pub port_transport_protocol: String,
-    pub port_application_protocol: String,
-    pub port_is_online: bool,
+    // The port columns are NULL for a service without ports
+    pub port_name: Option<String>,
+    pub port_port: Option<i32>,
+    pub port_transport_protocol: Option<String>,
+    pub port_application_protocol: Option<String>,
+    pub port_is_online: Option<bool>,
 }
 
 fn service_with_port_group_to_service(
@@ -49,28 +55,31 @@ fn service_with_port_group_to_service(
     };
 
     for service_with_port in services_with_port {
+        let (
+            Some(name),
+            Some(port),
+            Some(transport_protocol),
+            Some(application_protocol),
+            Some(is_online),
+        ) = (
+            &service_with_port.port_name,
+            service_with_port.port_port,
+            &service_with_port.port_transport_protocol,
+            &service_with_port.port_application_protocol,
+            service_with_port.port_is_online,
+        )
+        else {
+            continue;
+        };
+
         service.ports.push(ServicePort {
-            name: service_with_port.port_name.clone(),
-            port: service_with_port.port_port,
-            transport_protocol: TransportProtocol::from_str(
-                &service_with_port.port_transport_protocol,
-            )
-            .map_err(|_| {
-                map_parse_err(
-                    "transport_protocol",
-                    &service_with_port.port_transport_protocol,
-                )
-            })?,
-            application_protocol: ApplicationProtocol::from_str(
-                &service_with_port.port_application_protocol,
-            )
-            .map_err(|_| {
-                map_parse_err(
-                    "application_protocol",
-                    &service_with_port.port_application_protocol,
-                )
-            })?,
-            is_online: service_with_port.port_is_online,
+            name: name.clone(),
+            port: u16::try_from(port).map_err(|_| map_parse_err("port", &port.to_string()))?,
+            transport_protocol: TransportProtocol::from_str(transport_protocol)
+                .map_err(|_| map_parse_err("transport_protocol", transport_protocol))?,
+            application_protocol: ApplicationProtocol::from_str(application_protocol)
+                .map_err(|_| map_parse_err("application_protocol", application_protocol))?,
+            is_online,
         });
     }
 
@@ -81,6 +90,66 @@ impl Repository<PostgresUWP> for PostgresServicesRepository {}
 
 #[async_trait::async_trait]
 impl ServicesRepository<PostgresUWP> for PostgresServicesRepository {
+    #[instrument(skip(connection))]
+    async fn fetch_all<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        query: ServiceQuery,
+    ) -> RepositoryResult<Vec<Service>> {
+        // The services are selected (and paginated) first, their ports are joined afterwards, so
+        // that the services without ports are returned, as they are counted
+        let mut builder = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT
+                s.service_id as service_id,
+                s.device_mac as service_device_mac,
+                s.display_name as service_display_name,
+                s.kind as service_kind,
+                s.is_managed as service_is_managed,
+                s.token as service_token,
+                sp.name as port_name,
+                sp.port as port_port,
+                sp.transport_protocol as port_transport_protocol,
+                sp.application_protocol as port_application_protocol,
+                sp.is_online as port_is_online
+            FROM (SELECT s.* FROM core.services s WHERE true"#,
+        );
+
+        push_filter(&mut builder, query.filter);
+        push_paging_condition(&mut builder, query.sort, query.paging.as_ref());
+        push_order(&mut builder, query.sort);
+        push_paging_limit(&mut builder, query.paging.as_ref());
+        builder.push(") s LEFT JOIN core.service_ports sp ON s.service_id = sp.service_id");
+        push_order(&mut builder, query.sort);
+
+        builder
+            .build_query_as::<ServiceWithPort>()
+            .fetch_all(connection as &'a mut PgConnection)
+            .await
+            .map_err(map_sqlx_error)?
+            .chunk_by(|s1, s2| s1.service_id == s2.service_id)
+            .map(service_with_port_group_to_service)
+            .collect()
+    }
+
+    #[instrument(skip(connection))]
+    async fn count<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        filter: ServiceFilter,
+    ) -> RepositoryResult<u64> {
+        // Counts the services with or without ports, as fetch_all returns both
+        let mut builder =
+            QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM core.services s WHERE true");
+
+        push_filter(&mut builder, filter);
+
+        builder
+            .build_query_scalar::<i64>()
+            .fetch_one(connection as &'a mut PgConnection)
+            .await
+            .map(|count| count as u64)
+            .map_err(map_sqlx_error)
+    }
+
     #[instrument(skip(connection))]
     async fn fetch_all_of_device<'a>(
         connection: &'a mut PostgresUoW<'_>,
@@ -110,8 +179,41 @@ impl ServicesRepository<PostgresUWP> for PostgresServicesRepository {
         .await
         .map_err(map_sqlx_error)?
         .chunk_by(|s1, s2| s1.service_id == s2.service_id)
-        .into_iter()
-        .map(|chunk| service_with_port_group_to_service(chunk))
+        .map(service_with_port_group_to_service)
+        .collect()
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_all_of_devices<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        mac_addresses: &[MacAddress],
+    ) -> RepositoryResult<Vec<Service>> {
+        sqlx::query_as::<Postgres, ServiceWithPort>(
+            r#"
+            SELECT
+                s.service_id as service_id,
+                s.device_mac as service_device_mac,
+                s.display_name as service_display_name,
+                s.kind as service_kind,
+                s.is_managed as service_is_managed,
+                s.token as service_token,
+                sp.name as port_name,
+                sp.port as port_port,
+                sp.transport_protocol as port_transport_protocol,
+                sp.application_protocol as port_application_protocol,
+                sp.is_online as port_is_online
+            FROM core.services s
+            INNER JOIN core.service_ports sp ON s.service_id = sp.service_id
+            WHERE device_mac = ANY($1)
+            ORDER BY s.device_mac, s.service_id
+        "#,
+        )
+        .bind(mac_addresses)
+        .fetch_all(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .chunk_by(|s1, s2| s1.service_id == s2.service_id)
+        .map(service_with_port_group_to_service)
         .collect()
     }
 
@@ -144,7 +246,7 @@ impl ServicesRepository<PostgresUWP> for PostgresServicesRepository {
         .await
         .map_err(map_sqlx_error)?;
 
-        if services.len() == 0 {
+        if services.is_empty() {
             Err(RepositoryError::NotFound)
         } else {
             Ok(service_with_port_group_to_service(&services)?)
@@ -183,8 +285,7 @@ impl ServicesRepository<PostgresUWP> for PostgresServicesRepository {
         .await
         .map_err(map_sqlx_error)?
         .chunk_by(|s1, s2| s1.service_id == s2.service_id)
-        .into_iter()
-        .map(|chunk| service_with_port_group_to_service(chunk))
+        .map(service_with_port_group_to_service)
         .collect::<Result<Vec<_>, _>>()?;
 
         // Check that the ports are the same
@@ -337,3 +438,22 @@ impl ServicesRepository<PostgresUWP> for PostgresServicesRepository {
         Ok(())
     }
 }
+
+fn push_filter(builder: &mut QueryBuilder<'_, Postgres>, filter: ServiceFilter) {
+    if let Some(device_mac) = filter.device_mac {
+        builder.push(" AND s.device_mac = ").push_bind(device_mac);
+    }
+    if let Some(kind) = filter.kind {
+        builder.push(" AND s.kind = ").push_bind(kind.to_string());
+    }
+    if let Some(is_online) = filter.is_online {
+        builder
+            .push(" AND EXISTS (SELECT 1 FROM core.service_ports p WHERE p.service_id = s.service_id AND p.is_online) = ")
+            .push_bind(is_online);
+    }
+    if let Some(search) = filter.search {
+        builder
+            .push(" AND s.display_name ILIKE ")
+            .push_bind(contains_pattern(&search));
+    }
+}
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use entities::WanOutage;
+use ports::repositories::{Repository, RepositoryResult, WanOutagesRepository};
+use sqlx::{PgConnection, Postgres, prelude::FromRow};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{PostgresUWP, PostgresUoW, map_sqlx_error};
+
+#[derive(Clone)]
+pub struct PostgresWanOutagesRepository;
+
+#[derive(FromRow)]
+struct WanOutageRow {
+    outage_id: Uuid,
+    started_at: DateTime<Utc>,
+    ended_at: Option<DateTime<Utc>>,
+}
+
+impl From<WanOutageRow> for WanOutage {
+    fn from(row: WanOutageRow) -> Self {
+        WanOutage {
+            outage_id: row.outage_id,
+            started_at: row.started_at,
+            ended_at: row.ended_at,
+            duration: row.ended_at.unwrap_or_else(Utc::now) - row.started_at,
+        }
+    }
+}
+
+impl Repository<PostgresUWP> for PostgresWanOutagesRepository {}
+
+#[async_trait::async_trait]
+impl WanOutagesRepository<PostgresUWP> for PostgresWanOutagesRepository {
+    #[instrument(skip(connection))]
+    async fn fetch_between<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> RepositoryResult<Vec<WanOutage>> {
+        Ok(sqlx::query_as::<Postgres, WanOutageRow>(
+            r#"
+            SELECT outage_id, started_at, ended_at
+            FROM core.wan_outages
+            WHERE started_at < $2 AND (ended_at IS NULL OR ended_at > $1)
+            ORDER BY started_at
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .into_iter()
+        .map(WanOutage::from)
+        .collect())
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_ongoing<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+    ) -> RepositoryResult<Option<WanOutage>> {
+        Ok(sqlx::query_as::<Postgres, WanOutageRow>(
+            r#"
+            SELECT outage_id, started_at, ended_at
+            FROM core.wan_outages
+            WHERE ended_at IS NULL
+            "#,
+        )
+        .fetch_optional(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .map(WanOutage::from))
+    }
+
+    #[instrument(skip(connection))]
+    async fn create<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        outage: WanOutage,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO core.wan_outages (
+                outage_id,
+                started_at,
+                ended_at
+            ) VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(outage.outage_id)
+        .bind(outage.started_at)
+        .bind(outage.ended_at)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+
+    #[instrument(skip(connection))]
+    async fn update<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        outage: WanOutage,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE core.wan_outages
+            SET started_at = $2,
+                ended_at = $3
+            WHERE outage_id = $1
+            "#,
+        )
+        .bind(outage.outage_id)
+        .bind(outage.started_at)
+        .bind(outage.ended_at)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,246 @@
+use chrono::{DateTime, Utc};
+use entities::{BandwidthSummary, WanHistoryPoint, WanStatsBucket, WanStatsSample};
+use ports::repositories::{Repository, RepositoryResult, WanStatsRepository};
+use sqlx::{PgConnection, Postgres, prelude::FromRow};
+use tracing::instrument;
+
+use crate::{PostgresUWP, PostgresUoW, map_sqlx_error};
+
+#[derive(Clone)]
+pub struct PostgresWanStatsRepository;
+
+/// Returns the `date_trunc` unit of the bucket.
+fn trunc_unit(bucket: WanStatsBucket) -> &'static str {
+    match bucket {
+        WanStatsBucket::Minute => "minute",
+        WanStatsBucket::Hour => "hour",
+        WanStatsBucket::Day => "day",
+    }
+}
+
+#[derive(FromRow)]
+struct WanStatsBucketRow {
+    bucket_start: DateTime<Utc>,
+    download_min: i64,
+    download_avg: f64,
+    download_max: i64,
+    upload_min: i64,
+    upload_avg: f64,
+    upload_max: i64,
+    status_samples: i32,
+    up_samples: i32,
+}
+
+impl From<WanStatsBucketRow> for WanHistoryPoint {
+    fn from(row: WanStatsBucketRow) -> Self {
+        WanHistoryPoint {
+            start: row.bucket_start,
+            download: BandwidthSummary {
+                min: row.download_min.max(0) as usize,
+                avg: row.download_avg,
+                max: row.download_max.max(0) as usize,
+            },
+            upload: BandwidthSummary {
+                min: row.upload_min.max(0) as usize,
+                avg: row.upload_avg,
+                max: row.upload_max.max(0) as usize,
+            },
+            availability: (row.status_samples > 0)
+                .then(|| 100.0 * row.up_samples as f64 / row.status_samples as f64),
+        }
+    }
+}
+
+impl Repository<PostgresUWP> for PostgresWanStatsRepository {}
+
+#[async_trait::async_trait]
+impl WanStatsRepository<PostgresUWP> for PostgresWanStatsRepository {
+    #[instrument(skip(connection))]
+    async fn create_sample<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        sample: WanStatsSample,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO core.wan_stats_samples (
+                sampled_at,
+                download_bandwidth,
+                upload_bandwidth,
+                status
+            ) VALUES ($1, $2, $3, $4)
+            ON CONFLICT (sampled_at) DO NOTHING
+            "#,
+        )
+        .bind(sample.sampled_at)
+        .bind(sample.download_bandwidth as i64)
+        .bind(sample.upload_bandwidth as i64)
+        .bind(sample.status.map(|status| status.to_string()))
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+
+    #[instrument(skip(connection))]
+    async fn rollup<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        bucket: WanStatsBucket,
+        since: DateTime<Utc>,
+    ) -> RepositoryResult<()> {
+        let source = match bucket {
+            WanStatsBucket::Minute => {
+                r#"
+                SELECT date_trunc($2, sampled_at, 'UTC') as bucket_start,
+                       count(*)::integer as samples,
+                       min(download_bandwidth) as download_min,
+                       avg(download_bandwidth)::double precision as download_avg,
+                       max(download_bandwidth) as download_max,
+                       min(upload_bandwidth) as upload_min,
+                       avg(upload_bandwidth)::double precision as upload_avg,
+                       max(upload_bandwidth) as upload_max,
+                       count(status)::integer as status_samples,
+                       (count(*) FILTER (WHERE status = 'Up'))::integer as up_samples
+                FROM core.wan_stats_samples
+                WHERE sampled_at >= date_trunc($2, $1, 'UTC')
+                GROUP BY 1
+                "#
+            }
+            // Averages are weighted by the samples of each finer bucket
+            WanStatsBucket::Hour | WanStatsBucket::Day => {
+                r#"
+                SELECT date_trunc($2, bucket_start, 'UTC') as bucket_start,
+                       sum(samples)::integer as samples,
+                       min(download_min) as download_min,
+                       sum(download_avg * samples) / sum(samples) as download_avg,
+                       max(download_max) as download_max,
+                       min(upload_min) as upload_min,
+                       sum(upload_avg * samples) / sum(samples) as upload_avg,
+                       max(upload_max) as upload_max,
+                       sum(status_samples)::integer as status_samples,
+                       sum(up_samples)::integer as up_samples
+                FROM core.wan_stats_buckets
+                WHERE bucket = $4 AND bucket_start >= date_trunc($2, $1, 'UTC')
+                GROUP BY 1
+                "#
+            }
+        };
+        let finer = match bucket {
+            WanStatsBucket::Minute | WanStatsBucket::Hour => WanStatsBucket::Minute,
+            WanStatsBucket::Day => WanStatsBucket::Hour,
+        };
+
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO core.wan_stats_buckets (
+                bucket,
+                bucket_start,
+                samples,
+                download_min,
+                download_avg,
+                download_max,
+                upload_min,
+                upload_avg,
+                upload_max,
+                status_samples,
+                up_samples
+            )
+            SELECT $3, source.*
+            FROM ({source}) source
+            ON CONFLICT (bucket, bucket_start) DO UPDATE
+            SET samples = EXCLUDED.samples,
+                download_min = EXCLUDED.download_min,
+                download_avg = EXCLUDED.download_avg,
+                download_max = EXCLUDED.download_max,
+                upload_min = EXCLUDED.upload_min,
+                upload_avg = EXCLUDED.upload_avg,
+                upload_max = EXCLUDED.upload_max,
+                status_samples = EXCLUDED.status_samples,
+                up_samples = EXCLUDED.up_samples,
+                updated_at = now()
+            "#
+        ))
+        .bind(since)
+        .bind(trunc_unit(bucket))
+        .bind(bucket.to_string())
+        .bind(finer.to_string())
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|_| ())
+    }
+
+    #[instrument(skip(connection))]
+    async fn fetch_history<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        bucket: WanStatsBucket,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> RepositoryResult<Vec<WanHistoryPoint>> {
+        Ok(sqlx::query_as::<Postgres, WanStatsBucketRow>(
+            r#"
+            SELECT bucket_start,
+                   download_min,
+                   download_avg,
+                   download_max,
+                   upload_min,
+                   upload_avg,
+                   upload_max,
+                   status_samples,
+                   up_samples
+            FROM core.wan_stats_buckets
+            WHERE bucket = $1
+              AND bucket_start >= date_trunc($2, $3, 'UTC')
+              AND bucket_start < $4
+            ORDER BY bucket_start
+            "#,
+        )
+        .bind(bucket.to_string())
+        .bind(trunc_unit(bucket))
+        .bind(from)
+        .bind(to)
+        .fetch_all(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)?
+        .into_iter()
+        .map(WanHistoryPoint::from)
+        .collect())
+    }
+
+    #[instrument(skip(connection))]
+    async fn delete_samples_before<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        before: DateTime<Utc>,
+    ) -> RepositoryResult<u64> {
+        sqlx::query(
+            r#"
+            DELETE FROM core.wan_stats_samples
+            WHERE sampled_at < $1
+            "#,
+        )
+        .bind(before)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|result| result.rows_affected())
+    }
+
+    #[instrument(skip(connection))]
+    async fn delete_buckets_before<'a>(
+        connection: &'a mut PostgresUoW<'_>,
+        bucket: WanStatsBucket,
+        before: DateTime<Utc>,
+    ) -> RepositoryResult<u64> {
+        sqlx::query(
+            r#"
+            DELETE FROM core.wan_stats_buckets
+            WHERE bucket = $1 AND bucket_start < $2
+            "#,
+        )
+        .bind(bucket.to_string())
+        .bind(before)
+        .execute(connection as &'a mut PgConnection)
+        .await
+        .map_err(map_sqlx_error)
+        .map(|result| result.rows_affected())
+    }
+}
@@ -0,0 +1,49 @@
+use std::time::Instant;
+
+use tokio::sync::Mutex;
+
+/// Computes the current bandwidth of an interface from two successive readings of its byte
+/// counters, for routers that only expose cumulative counters.
+pub(crate) struct BandwidthMeter {
+    last_sample: Mutex<Option<Sample>>,
+}
+
+struct Sample {
+    sampled_at: Instant,
+    rx_bytes: u64,
+    tx_bytes: u64,
+}
+
+impl BandwidthMeter {
+    pub(crate) fn new() -> Self {
+        Self {
+            last_sample: Mutex::new(None),
+        }
+    }
+
+    /// Records the given counters and returns the (download, upload) bandwidth in kbps since the
+    /// previous sample. The first sample, and samples taken after a counter reset, yield 0.
+    pub(crate) async fn sample(&self, rx_bytes: u64, tx_bytes: u64) -> (usize, usize) {
+        let now = Instant::now();
+        let mut last_sample = self.last_sample.lock().await;
+
+        let bandwidth = match last_sample.as_ref() {
+            Some(last) if rx_bytes >= last.rx_bytes && tx_bytes >= last.tx_bytes => {
+                let elapsed = (now - last.sampled_at).as_secs_f64().max(1.0);
+                (
+                    ((rx_bytes - last.rx_bytes) as f64 * 8.0 / 1000.0 / elapsed) as usize,
+                    ((tx_bytes - last.tx_bytes) as f64 * 8.0 / 1000.0 / elapsed) as usize,
+                )
+            }
+            _ => (0, 0),
+        };
+
+        *last_sample = Some(Sample {
+            sampled_at: now,
+            rx_bytes,
+            tx_bytes,
+        });
+
+        bandwidth
+    }
+}
@@ -1,7 +1,17 @@
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
 
+use chrono::{NaiveTime, Weekday};
 use common::hashmap;
-use entities::{Device, WanConnectivity, WanStats, WanStatsItem, WanStatus};
+use entities::{
+    AccessSlot, ConnectionType, Device, DeviceAccess, DeviceConnection, DeviceTrafficCounters,
+    GuestNetwork, PortForward, PortForwardProtocol, PortForwardRule, RouterInfo, StaticLease,
+    WanConnectivity, WanStats, WanStatsItem, WanStatus, WifiBand, WifiNetwork, WifiRadio,
+};
+use ipnetwork::IpNetwork;
+use mac_address::MacAddress;
 use ports::api::{RouterApi, RouterApiError, RouterApiResult};
 use reqwest::{StatusCode, Url};
 use serde::{Deserialize, de::Visitor};
@@ -39,6 +49,9 @@ impl From<BboxRouterApiError> for RouterApiError {
             BboxRouterApiError::UnexpectedStatus(status) if status == StatusCode::UNAUTHORIZED => {
                 RouterApiError::AuthenticationFailed
             }
+            BboxRouterApiError::UnexpectedStatus(status) if status == StatusCode::NOT_FOUND => {
+                RouterApiError::NotFound
+            }
             BboxRouterApiError::UnexpectedStatus(_)
             | BboxRouterApiError::ParseError(_)
             | BboxRouterApiError::MissingField(_)
@@ -97,10 +110,8 @@ impl<'de> Deserialize<'de> for Integer {
 
         deserializer
             .deserialize_any(IntegerVisitor)
-            .and_then(|integer: Integer| {
-                Ok(Integer {
-                    value: integer.value,
-                })
+            .map(|integer: Integer| Integer {
+                value: integer.value,
             })
     }
 }
@@ -110,9 +121,104 @@ struct BboxDevice {
     hostname: String,
     macaddress: String,
     ipaddress: IpAddr,
+    #[serde(default)]
+    ip6address: Vec<BboxHostIpv6Address>,
     lastseen: Integer,
     #[validate(range(min = 0, max = 1))]
     active: usize,
+    #[serde(default)]
+    link: String, // "Ethernet", "Wifi 2.4", "Wifi 5"...
+    wireless: Option<BboxHostWireless>,
+    ethernet: Option<BboxHostEthernet>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BboxHostIpv6Address {
+    ipaddress: String, // may be empty
+}
+
+#[derive(Deserialize, Debug)]
+struct BboxHostWireless {
+    band: String,
+    rssi0: Integer,
+    rate: Integer, // in Mbit/s
+}
+
+#[derive(Deserialize, Debug)]
+struct BboxHostEthernet {
+    speed: Integer, // in Mbit/s
+}
+
+impl BboxDevice {
+    fn to_connection(&self, bssids: &HashMap<WifiBand, String>) -> Option<DeviceConnection> {
+        let mac_address = self.macaddress.parse().ok()?;
+
+        if self.link.eq_ignore_ascii_case("ethernet") {
+            return Some(DeviceConnection::wired(
+                mac_address,
+                self.ethernet
+                    .as_ref()
+                    .and_then(|ethernet| ethernet.speed.value?.try_into().ok())
+                    .filter(|speed| *speed > 0),
+            ));
+        }
+
+        let wireless = self.wireless.as_ref();
+        let band = parse_band(self.link.trim_start_matches("Wifi").trim())
+            .or_else(|| parse_band(&wireless?.band))?;
+
+        Some(DeviceConnection {
+            mac_address,
+            connection_type: ConnectionType::Wifi,
+            band: Some(band),
+            rssi: wireless
+                .and_then(|wireless| wireless.rssi0.value?.try_into().ok())
+                // The Bbox reports 0 when the signal was not measured yet
+                .filter(|rssi| *rssi < 0),
+            link_rate: wireless
+                .and_then(|wireless| wireless.rate.value?.try_into().ok())
+                .filter(|rate| *rate > 0),
+            access_point: bssids.get(&band).cloned(),
+        })
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct BboxHostStats {
+    macaddress: String,
+    stats: BboxHostTraffic,
+}
+
+#[derive(Deserialize, Debug)]
+struct BboxHostTraffic {
+    rx: BboxTrafficCounter,
+    tx: BboxTrafficCounter,
+}
+
+#[derive(Deserialize, Debug)]
+struct BboxTrafficCounter {
+    bytes: Integer,
+}
+
+impl BboxHostStats {
+    /// The Bbox counts from its own side, what it receives was sent by the device.
+    fn to_counters(&self) -> Option<DeviceTrafficCounters> {
+        Some(DeviceTrafficCounters {
+            mac_address: self.macaddress.parse().ok()?,
+            rx_bytes: self.stats.tx.bytes.value?.try_into().ok()?,
+            tx_bytes: self.stats.rx.bytes.value?.try_into().ok()?,
+        })
+    }
+}
+
+/// Parses a band as named by the Bbox, e.g. "2.4", "24" or "5".
+fn parse_band(band: &str) -> Option<WifiBand> {
+    match band.trim_end_matches("GHz").trim() {
+        "2.4" | "24" => Some(WifiBand::Band2_4GHz),
+        "5" => Some(WifiBand::Band5GHz),
+        "6" => Some(WifiBand::Band6GHz),
+        _ => None,
+    }
 }
 
 #[derive(Deserialize)]
@@ -122,14 +228,21 @@ struct BboxWanIpv6Address {
 
 #[derive(Deserialize)]
 struct BboxWanIpResponse {
+    state: String,
     address: Ipv4Addr,
     gateway: Ipv4Addr,
     ip6address: Vec<BboxWanIpv6Address>,
 }
 
+#[derive(Deserialize)]
+struct BboxWanLinkResponse {
+    state: String,
+}
+
 #[derive(Deserialize)]
 struct BboxWanResponse {
     ip: BboxWanIpResponse,
+    link: BboxWanLinkResponse,
 }
 
 #[derive(Deserialize)]
@@ -156,6 +269,303 @@ struct BboxWanSessionsSummary {
 #[derive(Debug, Deserialize)]
 pub struct BboxInfo {
     uptime: Integer,
+    #[serde(default)]
+    modelname: String,
+    #[serde(default)]
+    serialnumber: Option<String>,
+    main: Option<BboxFirmware>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BboxFirmware {
+    version: String,
+}
+
+/// Endpoints holding the user configuration of the Bbox, exported together as a backup.
+const BBOX_CONFIG_PATHS: [&str; 6] = [
+    "/api/v1/nat/rules",
+    "/api/v1/dhcp",
+    "/api/v1/dhcp/clients",
+    "/api/v1/parentalcontrol",
+    "/api/v1/wireless",
+    "/api/v1/wireless/guest",
+];
+
+/// Keys of the configuration holding secrets (e.g. the Wi-Fi passphrases), left out of backups.
+const BBOX_SECRET_KEYS: [&str; 5] = ["passphrase", "password", "key", "wpakey", "wepkey"];
+
+/// Removes the secrets from a configuration endpoint, at any depth.
+fn strip_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(object) => {
+            object.retain(|key, _| !BBOX_SECRET_KEYS.contains(&key.to_lowercase().as_str()));
+            object.values_mut().for_each(strip_secrets);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_secrets),
+        _ => {}
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BboxNatRule {
+    id: Integer,
+    enable: Integer,
+    description: String,
+    protocol: String,
+    externalip: String,
+    externalport: Integer,
+    internalip: String,
+    internalport: Integer,
+}
+
+impl BboxNatRule {
+    /// Converts the rule, unless it uses features that cannot be represented (port ranges,
+    /// remote subnets...).
+    fn to_port_forward(&self) -> Option<PortForward> {
+        let protocol = match self.protocol.to_ascii_lowercase().as_str() {
+            "tcp" => PortForwardProtocol::TCP,
+            "udp" => PortForwardProtocol::UDP,
+            "all" | "tcp,udp" => PortForwardProtocol::Both,
+            _ => return None,
+        };
+
+        Some(PortForward {
+            port_forward_id: self.id.value?.to_string(),
+            rule: PortForwardRule {
+                description: self.description.clone(),
+                is_enabled: self.enable.value == Some(1),
+                protocol,
+                external_port: self.externalport.value?.try_into().ok()?,
+                internal_ip: self.internalip.parse().ok()?,
+                internal_port: self.internalport.value?.try_into().ok()?,
+                remote_ip: match self.externalip.as_str() {
+                    "" | "0.0.0.0" => None,
+                    remote_ip => Some(remote_ip.parse().ok()?),
+                },
+            },
+        })
+    }
+}
+
+fn nat_rule_form(rule: &PortForwardRule) -> HashMap<&'static str, String> {
+    hashmap! {
+        "enable" => u8::from(rule.is_enabled).to_string(),
+        "description" => rule.description.clone(),
+        "protocol" => match rule.protocol {
+            PortForwardProtocol::TCP => "tcp",
+            PortForwardProtocol::UDP => "udp",
+            PortForwardProtocol::Both => "all",
+        }
+        .to_string(),
+        "externalip" => rule.remote_ip.map(|ip| ip.to_string()).unwrap_or_default(),
+        "externalport" => rule.external_port.to_string(),
+        "internalip" => rule.internal_ip.to_string(),
+        "internalport" => rule.internal_port.to_string()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BboxDhcpClient {
+    id: Integer,
+    macaddress: String,
+    ipaddress: String,
+}
+
+impl BboxDhcpClient {
+    fn to_static_lease(&self) -> Option<StaticLease> {
+        Some(StaticLease {
+            mac_address: self.macaddress.parse().ok()?,
+            ip_address: self.ipaddress.parse().ok()?,
+        })
+    }
+}
+
+fn dhcp_client_form(mac_address: MacAddress, ip_address: IpAddr) -> HashMap<&'static str, String> {
+    hashmap! {
+        "enable" => 1.to_string(),
+        "macaddress" => mac_address.to_string().to_lowercase(),
+        "ipaddress" => ip_address.to_string()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BboxParentalControlHost {
+    id: Integer,
+    enable: Integer,
+    macaddress: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BboxSchedulerTime {
+    day: Integer, // 0 is Sunday
+    hour: Integer,
+    minute: Integer,
+}
+
+#[derive(Debug, Deserialize)]
+struct BboxSchedulerRule {
+    id: Integer,
+    enable: Integer,
+    macaddress: String,
+    start: BboxSchedulerTime,
+    end: BboxSchedulerTime,
+}
+
+impl BboxSchedulerTime {
+    fn weekday(&self) -> Option<Weekday> {
+        Weekday::try_from(u8::try_from(self.day.value?).ok()?)
+            .ok()
+            // Days start on Monday for chrono
+            .map(|day| day.pred())
+    }
+
+    fn time(&self) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(
+            self.hour.value?.try_into().ok()?,
+            self.minute.value?.try_into().ok()?,
+            0,
+        )
+    }
+}
+
+impl BboxSchedulerRule {
+    /// Converts the rule, unless it spans several days or ends at midnight.
+    fn to_access_slot(&self) -> Option<AccessSlot> {
+        let day = self.start.weekday()?;
+        let slot = AccessSlot {
+            day,
+            start: self.start.time()?,
+            end: self.end.time()?,
+        };
+
+        (self.end.weekday()? == day && slot.is_valid()).then_some(slot)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BboxWireless {
+    radio: HashMap<String, BboxWirelessRadio>, // by band
+    ssid: HashMap<String, BboxWirelessSsid>,   // by band
+}
+
+#[derive(Debug, Deserialize)]
+struct BboxWirelessRadio {
+    enable: Integer,
+    current_channel: Integer,
+}
+
+#[derive(Debug, Deserialize)]
+struct BboxWirelessSsid {
+    id: String,
+    enable: Integer,
+    hidden: Integer,
+    bssid: String,
+    security: BboxWirelessSecurity,
+}
+
+#[derive(Debug, Deserialize)]
+struct BboxWirelessSecurity {
+    protocol: String,
+}
+
+impl BboxWireless {
+    fn to_radios(&self) -> Vec<WifiRadio> {
+        let mut radios = self
+            .radio
+            .iter()
+            .filter_map(|(band, radio)| {
+                let Some(band_value) = parse_band(band) else {
+                    warn!(band, "Wi-Fi band cannot be parsed, ignoring it");
+                    return None;
+                };
+
+                Some(WifiRadio {
+                    band: band_value,
+                    is_enabled: radio.enable.value == Some(1),
+                    channel: radio
+                        .current_channel
+                        .value
+                        .and_then(|channel| channel.try_into().ok())
+                        .filter(|channel| *channel > 0),
+                    networks: self
+                        .ssid
+                        .get(band)
+                        .map(|ssid| WifiNetwork {
+                            ssid: ssid.id.clone(),
+                            bssid: ssid.bssid.parse().ok(),
+                            is_enabled: ssid.enable.value == Some(1),
+                            is_hidden: ssid.hidden.value == Some(1),
+                            security: match ssid.security.protocol.to_ascii_lowercase().as_str() {
+                                "" | "none" => None,
+                                _ => Some(ssid.security.protocol.clone()),
+                            },
+                        })
+                        .into_iter()
+                        .collect(),
+                })
+            })
+            .collect::<Vec<_>>();
+        radios.sort_by_key(|radio| radio.band);
+
+        radios
+    }
+
+    /// Returns the BSSID of the network of each band, which identifies the access point.
+    fn bssids(&self) -> HashMap<WifiBand, String> {
+        self.ssid
+            .iter()
+            .filter_map(|(band, ssid)| Some((parse_band(band)?, ssid.bssid.to_lowercase())))
+            .filter(|(_, bssid)| !bssid.is_empty())
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BboxGuestNetwork {
+    enable: Integer,
+    ssid: String,
+    #[serde(default)]
+    passphrase: String,
+}
+
+fn scheduler_rule_form(
+    mac_address: MacAddress,
+    slot: &AccessSlot,
+) -> HashMap<&'static str, String> {
+    use chrono::Timelike;
+
+    let day = slot.day.num_days_from_sunday().to_string();
+    hashmap! {
+        "enable" => 1.to_string(),
+        "macaddress" => mac_address.to_string().to_lowercase(),
+        "startday" => day.clone(),
+        "starthour" => slot.start.hour().to_string(),
+        "startminute" => slot.start.minute().to_string(),
+        "endday" => day,
+        "endhour" => slot.end.hour().to_string(),
+        "endminute" => slot.end.minute().to_string()
+    }
+}
+
+fn same_mac(bbox_mac: &str, mac_address: MacAddress) -> bool {
+    bbox_mac
+        .parse::<MacAddress>()
+        .is_ok_and(|bbox_mac| bbox_mac == mac_address)
+}
+
+/// Bbox rule identifiers are integers, anything else cannot exist.
+fn parse_nat_rule_id(port_forward_id: &str) -> RouterApiResult<u32> {
+    port_forward_id
+        .parse()
+        .map_err(|_| RouterApiError::NotFound)
+}
+
+fn ensure_success(response: reqwest::Response) -> Result<reqwest::Response, BboxRouterApiError> {
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        Err(BboxRouterApiError::UnexpectedStatus(response.status()))
+    }
 }
 
 impl BboxRouterApi {
@@ -200,6 +610,405 @@ impl BboxRouterApi {
         Ok(())
     }
 
+    /// Fetches the token required by the requests modifying the configuration.
+    #[instrument(skip(self))]
+    async fn write_token(&self) -> Result<String, BboxRouterApiError> {
+        #[derive(Deserialize)]
+        struct TokenOuter {
+            device: Token,
+        }
+
+        #[derive(Deserialize)]
+        struct Token {
+            token: String,
+        }
+
+        let response = self
+            .handle_disconnect(async |api| {
+                api.client
+                    .get(api.base_url.clone().join("/api/v1/device/token").unwrap())
+                    .header("Cookie", api.cookie.read().await.clone())
+            })
+            .await?;
+
+        ensure_success(response)?
+            .json::<Vec<TokenOuter>>()
+            .await?
+            .into_iter()
+            .next()
+            .map(|outer| outer.device.token)
+            .ok_or(BboxRouterApiError::MissingField("device.token".to_string()))
+    }
+
+    async fn fetch_nat_rules(&self) -> Result<Vec<BboxNatRule>, BboxRouterApiError> {
+        #[derive(Deserialize)]
+        struct NatOuter {
+            nat: Nat,
+        }
+
+        #[derive(Deserialize)]
+        struct Nat {
+            rules: Vec<BboxNatRule>,
+        }
+
+        let response = self
+            .handle_disconnect(async |api| {
+                api.client
+                    .get(api.base_url.clone().join("/api/v1/nat/rules").unwrap())
+                    .header("Cookie", api.cookie.read().await.clone())
+            })
+            .await?;
+
+        Ok(ensure_success(response)?
+            .json::<Vec<NatOuter>>()
+            .await?
+            .into_iter()
+            .next()
+            .map(|outer| outer.nat.rules)
+            .unwrap_or_default())
+    }
+
+    async fn fetch_dhcp_clients(&self) -> Result<Vec<BboxDhcpClient>, BboxRouterApiError> {
+        #[derive(Deserialize)]
+        struct DhcpOuter {
+            dhcp: Dhcp,
+        }
+
+        #[derive(Deserialize)]
+        struct Dhcp {
+            clients: Vec<BboxDhcpClient>,
+        }
+
+        let response = self
+            .handle_disconnect(async |api| {
+                api.client
+                    .get(api.base_url.clone().join("/api/v1/dhcp/clients").unwrap())
+                    .header("Cookie", api.cookie.read().await.clone())
+            })
+            .await?;
+
+        Ok(ensure_success(response)?
+            .json::<Vec<DhcpOuter>>()
+            .await?
+            .into_iter()
+            .next()
+            .map(|outer| outer.dhcp.clients)
+            .unwrap_or_default())
+    }
+
+    async fn find_dhcp_client_id(
+        &self,
+        mac_address: MacAddress,
+    ) -> Result<Option<isize>, BboxRouterApiError> {
+        Ok(self
+            .fetch_dhcp_clients()
+            .await?
+            .into_iter()
+            .find(|client| same_mac(&client.macaddress, mac_address))
+            .and_then(|client| client.id.value))
+    }
+
+    async fn fetch_parental_control_hosts(
+        &self,
+    ) -> Result<Vec<BboxParentalControlHost>, BboxRouterApiError> {
+        #[derive(Deserialize)]
+        struct ParentalControlOuter {
+            parentalcontrol: ParentalControl,
+        }
+
+        #[derive(Deserialize)]
+        struct ParentalControl {
+            list: Vec<BboxParentalControlHost>,
+        }
+
+        let response = self
+            .handle_disconnect(async |api| {
+                api.client
+                    .get(
+                        api.base_url
+                            .clone()
+                            .join("/api/v1/parentalcontrol")
+                            .unwrap(),
+                    )
+                    .header("Cookie", api.cookie.read().await.clone())
+            })
+            .await?;
+
+        Ok(ensure_success(response)?
+            .json::<Vec<ParentalControlOuter>>()
+            .await?
+            .into_iter()
+            .next()
+            .map(|outer| outer.parentalcontrol.list)
+            .unwrap_or_default())
+    }
+
+    async fn fetch_scheduler_rules(&self) -> Result<Vec<BboxSchedulerRule>, BboxRouterApiError> {
+        #[derive(Deserialize)]
+        struct SchedulerOuter {
+            scheduler: Scheduler,
+        }
+
+        #[derive(Deserialize)]
+        struct Scheduler {
+            rules: Vec<BboxSchedulerRule>,
+        }
+
+        let response = self
+            .handle_disconnect(async |api| {
+                api.client
+                    .get(
+                        api.base_url
+                            .clone()
+                            .join("/api/v1/parentalcontrol/scheduler")
+                            .unwrap(),
+                    )
+                    .header("Cookie", api.cookie.read().await.clone())
+            })
+            .await?;
+
+        Ok(ensure_success(response)?
+            .json::<Vec<SchedulerOuter>>()
+            .await?
+            .into_iter()
+            .next()
+            .map(|outer| outer.scheduler.rules)
+            .unwrap_or_default())
+    }
+
+    /// Rolls back a schedule update, deleting the rules of the device that are not part of the
+    /// previous ones.
+    async fn delete_created_scheduler_rules(
+        &self,
+        mac_address: MacAddress,
+        previous_ids: &[isize],
+    ) {
+        let rules = match self.fetch_scheduler_rules().await {
+            Ok(rules) => rules,
+            Err(err) => {
+                error!(
+                    error = err.to_string(),
+                    "Failed to roll back the scheduler rules"
+                );
+                return;
+            }
+        };
+
+        for rule in rules {
+            if let Some(id) = rule.id.value
+                && same_mac(&rule.macaddress, mac_address)
+                && !previous_ids.contains(&id)
+                && let Err(err) = self
+                    .write(
+                        reqwest::Method::DELETE,
+                        &format!("/api/v1/parentalcontrol/scheduler/{id}"),
+                        None,
+                    )
+                    .await
+            {
+                error!(
+                    error = err.to_string(),
+                    id, "Failed to roll back a scheduler rule"
+                );
+            }
+        }
+    }
+
+    async fn fetch_hosts(&self) -> Result<Vec<BboxDevice>, BboxRouterApiError> {
+        #[derive(Deserialize)]
+        struct HostsResponse {
+            hosts: Hosts,
+        }
+
+        #[derive(Deserialize)]
+        struct Hosts {
+            list: Vec<BboxDevice>,
+        }
+
+        let response = self
+            .handle_disconnect(async |api| {
+                api.client
+                    .get(api.base_url.clone().join("/api/v1/hosts").unwrap())
+                    .header("Cookie", api.cookie.read().await.clone())
+            })
+            .await?;
+
+        Ok(ensure_success(response)?
+            .json::<Vec<HostsResponse>>()
+            .await?
+            .into_iter()
+            .next()
+            .map(|outer| outer.hosts.list)
+            .unwrap_or_default())
+    }
+
+    async fn fetch_host_stats(&self) -> Result<Vec<BboxHostStats>, BboxRouterApiError> {
+        #[derive(Deserialize)]
+        struct HostsResponse {
+            hosts: Hosts,
+        }
+
+        #[derive(Deserialize)]
+        struct Hosts {
+            list: Vec<BboxHostStats>,
+        }
+
+        let response = self
+            .handle_disconnect(async |api| {
+                api.client
+                    .get(api.base_url.clone().join("/api/v1/hosts/stats").unwrap())
+                    .header("Cookie", api.cookie.read().await.clone())
+            })
+            .await?;
+
+        Ok(ensure_success(response)?
+            .json::<Vec<HostsResponse>>()
+            .await?
+            .into_iter()
+            .next()
+            .map(|outer| outer.hosts.list)
+            .unwrap_or_default())
+    }
+
+    async fn fetch_wireless(&self) -> Result<BboxWireless, BboxRouterApiError> {
+        #[derive(Deserialize)]
+        struct WirelessOuter {
+            wireless: BboxWireless,
+        }
+
+        let response = self
+            .handle_disconnect(async |api| {
+                api.client
+                    .get(api.base_url.clone().join("/api/v1/wireless").unwrap())
+                    .header("Cookie", api.cookie.read().await.clone())
+            })
+            .await?;
+
+        ensure_success(response)?
+            .json::<Vec<WirelessOuter>>()
+            .await?
+            .into_iter()
+            .next()
+            .map(|outer| outer.wireless)
+            .ok_or(BboxRouterApiError::MissingField("wireless".to_string()))
+    }
+
+    async fn fetch_guest_network(&self) -> Result<BboxGuestNetwork, BboxRouterApiError> {
+        #[derive(Deserialize)]
+        struct GuestOuter {
+            wireless: Guest,
+        }
+
+        #[derive(Deserialize)]
+        struct Guest {
+            guest: BboxGuestNetwork,
+        }
+
+        let response = self
+            .handle_disconnect(async |api| {
+                api.client
+                    .get(api.base_url.clone().join("/api/v1/wireless/guest").unwrap())
+                    .header("Cookie", api.cookie.read().await.clone())
+            })
+            .await?;
+
+        ensure_success(response)?
+            .json::<Vec<GuestOuter>>()
+            .await?
+            .into_iter()
+            .next()
+            .map(|outer| outer.wireless.guest)
+            .ok_or(BboxRouterApiError::MissingField(
+                "wireless.guest".to_string(),
+            ))
+    }
+
+    async fn fetch_info(&self) -> Result<BboxInfo, BboxRouterApiError> {
+        #[derive(Deserialize)]
+        struct InfoOuter {
+            device: BboxInfo,
+        }
+
+        let response = self
+            .handle_disconnect(async |api| {
+                api.client
+                    .get(api.base_url.clone().join("/api/v1/device").unwrap())
+                    .header("Cookie", api.cookie.read().await.clone())
+            })
+            .await?;
+
+        ensure_success(response)?
+            .json::<Vec<InfoOuter>>()
+            .await?
+            .into_iter()
+            .next()
+            .map(|outer| outer.device)
+            .ok_or(BboxRouterApiError::MissingField("device".to_string()))
+    }
+
+    /// Fetches an endpoint as raw JSON. `handle_disconnect` cannot be used as its callback must not
+    /// capture the path to stay `Send`.
+    async fn fetch_raw(&self, path: &str) -> Result<serde_json::Value, BboxRouterApiError> {
+        let url = self.base_url.clone().join(path).unwrap();
+        let mut response = self
+            .client
+            .get(url.clone())
+            .header("Cookie", self.cookie.read().await.clone())
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::UNAUTHORIZED {
+            self.authenticate().await?;
+            response = self
+                .client
+                .get(url)
+                .header("Cookie", self.cookie.read().await.clone())
+                .send()
+                .await?;
+        }
+
+        Ok(ensure_success(response)?.json().await?)
+    }
+
+    async fn send_write(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        form: Option<&HashMap<&'static str, String>>,
+    ) -> Result<reqwest::Response, BboxRouterApiError> {
+        let token = self.write_token().await?;
+        let mut url = self.base_url.clone().join(path).unwrap();
+        url.query_pairs_mut().append_pair("btoken", &token);
+
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("Cookie", self.cookie.read().await.clone());
+        if let Some(form) = form {
+            request = request.form(form);
+        }
+
+        Ok(request.send().await?)
+    }
+
+    /// Sends a request modifying the configuration, with a fresh token.
+    async fn write(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        form: Option<HashMap<&'static str, String>>,
+    ) -> Result<reqwest::Response, BboxRouterApiError> {
+        let mut response = self.send_write(method.clone(), path, form.as_ref()).await?;
+
+        // The token is bound to the session, it must be fetched again after authenticating
+        if response.status() == StatusCode::UNAUTHORIZED {
+            self.authenticate().await?;
+            response = self.send_write(method, path, form.as_ref()).await?;
+        }
+
+        ensure_success(response)
+    }
+
     async fn handle_disconnect<F>(
         &self,
         callback: F,
@@ -233,60 +1042,52 @@ impl RouterApi for BboxRouterApi {
         }
 
         let wan = &self
-            .client
-            .get(self.base_url.clone().join("/api/v1/wan/ip").unwrap())
-            .send()
-            .await
-            .map_err(BboxRouterApiError::from)?
+            .handle_disconnect(async |self| {
+                self.client
+                    .get(self.base_url.clone().join("/api/v1/wan/ip").unwrap())
+                    .header("Cookie", self.cookie.read().await.clone())
+            })
+            .await?
             .json::<Vec<WanOuter>>()
             .await
             .map_err(BboxRouterApiError::from)?[0]
             .wan;
 
         let info = &self
-            .client
-            .get(self.base_url.clone().join("/api/v1/device").unwrap())
-            .send()
-            .await
-            .map_err(BboxRouterApiError::from)?
+            .handle_disconnect(async |self| {
+                self.client
+                    .get(self.base_url.clone().join("/api/v1/device").unwrap())
+                    .header("Cookie", self.cookie.read().await.clone())
+            })
+            .await?
             .json::<Vec<InfoOuter>>()
             .await
             .map_err(BboxRouterApiError::from)?[0]
             .device;
 
+        // The IP session can be down while the physical link is still up (e.g. PPP failure)
+        let is_up =
+            wan.link.state.eq_ignore_ascii_case("up") && wan.ip.state.eq_ignore_ascii_case("up");
+
         Ok(WanConnectivity {
             ipv4: wan.ip.address,
             ipv6: wan.ip.ip6address.first().map(|ip| ip.ipaddress),
             gateway: IpAddr::V4(wan.ip.gateway),
-            status: WanStatus::Up, // TODO: Implement status detection
-            uptime: chrono::Duration::seconds(info.uptime.value.unwrap_or(0) as i64),
+            status: if is_up {
+                WanStatus::Up
+            } else {
+                WanStatus::Down
+            },
+            uptime: info
+                .uptime
+                .value
+                .map(|uptime| chrono::Duration::seconds(uptime as i64)),
         })
     }
 
     #[instrument(skip(self))]
     async fn list_devices(&self) -> RouterApiResult<Vec<Device>> {
-        #[derive(Deserialize)]
-        struct HostsResponse {
-            hosts: Hosts,
-        }
-
-        #[derive(Deserialize)]
-        struct Hosts {
-            list: Vec<BboxDevice>,
-        }
-
-        let devices = &self
-            .handle_disconnect(async |self| {
-                self.client
-                    .get(self.base_url.clone().join("/api/v1/hosts").unwrap())
-                    .header("Cookie", self.cookie.read().await.clone())
-            })
-            .await?
-            .json::<Vec<HostsResponse>>()
-            .await
-            .map_err(BboxRouterApiError::from)?[0]
-            .hosts
-            .list;
+        let devices = self.fetch_hosts().await?;
 
         if devices.is_empty() {
             warn!("Router API returned an empty list of devices. This should never happen!");
@@ -299,16 +1100,9 @@ impl RouterApi for BboxRouterApi {
                 device.validate().map_err(BboxRouterApiError::from)?;
                 Ok(Device {
                     last_seen: chrono::Utc::now()
-                        - chrono::Duration::seconds(
-                            device
-                                .lastseen
-                                .value
-                                .ok_or(BboxRouterApiError::MissingField(
-                                    "device.lastSeen".to_string(),
-                                ))
-                                .map_err(BboxRouterApiError::from)?
-                                as i64,
-                        ),
+                        - chrono::Duration::seconds(device.lastseen.value.ok_or(
+                            BboxRouterApiError::MissingField("device.lastSeen".to_string()),
+                        )? as i64),
                     mac_address: device.macaddress.parse().unwrap(),
                     last_known_ip: device.ipaddress,
                     display_name: device.hostname.clone(),
@@ -316,6 +1110,11 @@ impl RouterApi for BboxRouterApi {
                     notes: String::new(),
                     is_online: device.active == 1,
                     last_scanned: chrono::Utc::now(),
+                    other_ips: device
+                        .ip6address
+                        .iter()
+                        .filter_map(|address| address.ipaddress.parse().ok())
+                        .collect(),
                 })
             })
             .collect::<Result<Vec<_>, _>>()
@@ -421,4 +1220,456 @@ impl RouterApi for BboxRouterApi {
                 ))? as usize,
         })
     }
+
+    #[instrument(skip(self))]
+    async fn list_port_forwards(&self) -> RouterApiResult<Vec<PortForward>> {
+        Ok(self
+            .fetch_nat_rules()
+            .await?
+            .iter()
+            .filter_map(|rule| {
+                let port_forward = rule.to_port_forward();
+                if port_forward.is_none() {
+                    warn!(rule = ?rule, "NAT rule cannot be represented, ignoring it");
+                }
+                port_forward
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn create_port_forward(&self, rule: PortForwardRule) -> RouterApiResult<PortForward> {
+        let previous_ids = self
+            .fetch_nat_rules()
+            .await?
+            .iter()
+            .filter_map(|rule| rule.id.value)
+            .collect::<Vec<_>>();
+
+        self.write(
+            reqwest::Method::POST,
+            "/api/v1/nat/rules",
+            Some(nat_rule_form(&rule)),
+        )
+        .await?;
+
+        // The Bbox does not return the identifier of the new rule, it is the one that appeared
+        let created = self
+            .fetch_nat_rules()
+            .await?
+            .into_iter()
+            .filter(|rule| rule.id.value.is_some_and(|id| !previous_ids.contains(&id)))
+            .max_by_key(|rule| rule.id.value)
+            .ok_or(BboxRouterApiError::MissingField("nat.rules.id".to_string()))?;
+
+        Ok(created.to_port_forward().unwrap_or(PortForward {
+            port_forward_id: created.id.value.unwrap_or_default().to_string(),
+            rule,
+        }))
+    }
+
+    #[instrument(skip(self))]
+    async fn update_port_forward(
+        &self,
+        port_forward_id: &str,
+        rule: PortForwardRule,
+    ) -> RouterApiResult<PortForward> {
+        let id = parse_nat_rule_id(port_forward_id)?;
+
+        self.write(
+            reqwest::Method::PUT,
+            &format!("/api/v1/nat/rules/{id}"),
+            Some(nat_rule_form(&rule)),
+        )
+        .await?;
+
+        Ok(PortForward {
+            port_forward_id: id.to_string(),
+            rule,
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_port_forward(&self, port_forward_id: &str) -> RouterApiResult<()> {
+        let id = parse_nat_rule_id(port_forward_id)?;
+
+        self.write(
+            reqwest::Method::DELETE,
+            &format!("/api/v1/nat/rules/{id}"),
+            None,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn lan_network(&self) -> RouterApiResult<IpNetwork> {
+        #[derive(Deserialize)]
+        struct LanOuter {
+            lan: Lan,
+        }
+
+        #[derive(Deserialize)]
+        struct Lan {
+            ip: LanIp,
+        }
+
+        #[derive(Deserialize)]
+        struct LanIp {
+            ipaddress: Ipv4Addr,
+            netmask: Ipv4Addr,
+        }
+
+        let response = self
+            .handle_disconnect(async |self| {
+                self.client
+                    .get(self.base_url.clone().join("/api/v1/lan/ip").unwrap())
+                    .header("Cookie", self.cookie.read().await.clone())
+            })
+            .await?;
+
+        let lan = ensure_success(response)?
+            .json::<Vec<LanOuter>>()
+            .await
+            .map_err(BboxRouterApiError::from)?
+            .into_iter()
+            .next()
+            .ok_or(BboxRouterApiError::MissingField("lan.ip".to_string()))?
+            .lan
+            .ip;
+
+        Ok(
+            IpNetwork::with_netmask(IpAddr::V4(lan.ipaddress), IpAddr::V4(lan.netmask))
+                .map_err(|_| BboxRouterApiError::MissingField("lan.ip.netmask".to_string()))?,
+        )
+    }
+
+    #[instrument(skip(self))]
+    async fn list_static_leases(&self) -> RouterApiResult<Vec<StaticLease>> {
+        Ok(self
+            .fetch_dhcp_clients()
+            .await?
+            .iter()
+            .filter_map(|client| {
+                let lease = client.to_static_lease();
+                if lease.is_none() {
+                    warn!(client = ?client, "DHCP reservation cannot be parsed, ignoring it");
+                }
+                lease
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn reserve_ip(
+        &self,
+        mac_address: MacAddress,
+        ip_address: IpAddr,
+    ) -> RouterApiResult<StaticLease> {
+        let form = dhcp_client_form(mac_address, ip_address);
+
+        match self.find_dhcp_client_id(mac_address).await? {
+            Some(id) => {
+                self.write(
+                    reqwest::Method::PUT,
+                    &format!("/api/v1/dhcp/clients/{id}"),
+                    Some(form),
+                )
+                .await?
+            }
+            None => {
+                self.write(reqwest::Method::POST, "/api/v1/dhcp/clients", Some(form))
+                    .await?
+            }
+        };
+
+        Ok(StaticLease {
+            mac_address,
+            ip_address,
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn release_ip(&self, mac_address: MacAddress) -> RouterApiResult<()> {
+        let id = self
+            .find_dhcp_client_id(mac_address)
+            .await?
+            .ok_or(RouterApiError::NotFound)?;
+
+        self.write(
+            reqwest::Method::DELETE,
+            &format!("/api/v1/dhcp/clients/{id}"),
+            None,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_access(&self) -> RouterApiResult<Vec<DeviceAccess>> {
+        let mut accesses: HashMap<MacAddress, DeviceAccess> = HashMap::new();
+
+        for host in self.fetch_parental_control_hosts().await? {
+            let Ok(mac_address) = host.macaddress.parse::<MacAddress>() else {
+                warn!(host = ?host, "Parental control host cannot be parsed, ignoring it");
+                continue;
+            };
+
+            accesses
+                .entry(mac_address)
+                .or_insert_with(|| DeviceAccess::allowed(mac_address))
+                .is_blocked |= host.enable.value == Some(1);
+        }
+
+        for rule in self.fetch_scheduler_rules().await? {
+            if rule.enable.value != Some(1) {
+                continue;
+            }
+
+            let (Ok(mac_address), Some(slot)) =
+                (rule.macaddress.parse::<MacAddress>(), rule.to_access_slot())
+            else {
+                warn!(rule = ?rule, "Scheduler rule cannot be represented, ignoring it");
+                continue;
+            };
+
+            accesses
+                .entry(mac_address)
+                .or_insert_with(|| DeviceAccess::allowed(mac_address))
+                .schedule
+                .push(slot);
+        }
+
+        Ok(accesses
+            .into_values()
+            .filter(DeviceAccess::is_restricted)
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn set_device_blocked(
+        &self,
+        mac_address: MacAddress,
+        is_blocked: bool,
+    ) -> RouterApiResult<()> {
+        let host = self
+            .fetch_parental_control_hosts()
+            .await?
+            .into_iter()
+            .find(|host| same_mac(&host.macaddress, mac_address));
+
+        match (host.and_then(|host| host.id.value), is_blocked) {
+            (Some(id), true) => {
+                self.write(
+                    reqwest::Method::PUT,
+                    &format!("/api/v1/parentalcontrol/hosts/{id}"),
+                    Some(hashmap! { "enable" => 1.to_string() }),
+                )
+                .await?
+            }
+            (None, true) => {
+                self.write(
+                    reqwest::Method::POST,
+                    "/api/v1/parentalcontrol/hosts",
+                    Some(hashmap! {
+                        "enable" => 1.to_string(),
+                        "macaddress" => mac_address.to_string().to_lowercase()
+                    }),
+                )
+                .await?
+            }
+            (Some(id), false) => {
+                self.write(
+                    reqwest::Method::DELETE,
+                    &format!("/api/v1/parentalcontrol/hosts/{id}"),
+                    None,
+                )
+                .await?
+            }
+            (None, false) => return Ok(()),
+        };
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn set_device_schedule(
+        &self,
+        mac_address: MacAddress,
+        schedule: Vec<AccessSlot>,
+    ) -> RouterApiResult<()> {
+        let rules = self.fetch_scheduler_rules().await?;
+        let previous_ids = rules
+            .iter()
+            .filter_map(|rule| rule.id.value)
+            .collect::<Vec<_>>();
+        let replaced_ids = rules
+            .iter()
+            .filter(|rule| same_mac(&rule.macaddress, mac_address))
+            .filter_map(|rule| rule.id.value)
+            .collect::<Vec<_>>();
+
+        // The new rules are created before the previous ones are deleted, so that a failure leaves
+        // the previous schedule in place rather than none
+        for slot in &schedule {
+            if let Err(err) = self
+                .write(
+                    reqwest::Method::POST,
+                    "/api/v1/parentalcontrol/scheduler",
+                    Some(scheduler_rule_form(mac_address, slot)),
+                )
+                .await
+            {
+                self.delete_created_scheduler_rules(mac_address, &previous_ids)
+                    .await;
+                return Err(err.into());
+            }
+        }
+
+        // The new rules are deleted again if a previous one cannot be, rather than leaving both
+        // schedules applied
+        for (deleted, id) in replaced_ids.iter().enumerate() {
+            if let Err(err) = self
+                .write(
+                    reqwest::Method::DELETE,
+                    &format!("/api/v1/parentalcontrol/scheduler/{id}"),
+                    None,
+                )
+                .await
+            {
+                if deleted > 0 {
+                    warn!(
+                        %mac_address,
+                        deleted,
+                        remaining = replaced_ids.len() - deleted,
+                        "The previous schedule was only partially deleted"
+                    );
+                }
+                self.delete_created_scheduler_rules(mac_address, &previous_ids)
+                    .await;
+                return Err(err.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_wifi_radios(&self) -> RouterApiResult<Vec<WifiRadio>> {
+        Ok(self.fetch_wireless().await?.to_radios())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_connections(&self) -> RouterApiResult<Vec<DeviceConnection>> {
+        let bssids = self.fetch_wireless().await?.bssids();
+
+        Ok(self
+            .fetch_hosts()
+            .await?
+            .iter()
+            .filter(|device| device.active == 1)
+            .filter_map(|device| {
+                let connection = device.to_connection(&bssids);
+                if connection.is_none() {
+                    warn!(device = ?device, "Host connection cannot be represented, ignoring it");
+                }
+                connection
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_traffic(&self) -> RouterApiResult<Vec<DeviceTrafficCounters>> {
+        Ok(self
+            .fetch_host_stats()
+            .await?
+            .iter()
+            .filter_map(BboxHostStats::to_counters)
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn guest_network(&self) -> RouterApiResult<GuestNetwork> {
+        let guest_network = self.fetch_guest_network().await?;
+
+        Ok(GuestNetwork {
+            is_enabled: guest_network.enable.value == Some(1),
+            ssid: guest_network.ssid,
+            passphrase: guest_network.passphrase,
+            expires_at: None,
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn set_guest_network_enabled(&self, is_enabled: bool) -> RouterApiResult<()> {
+        self.write(
+            reqwest::Method::PUT,
+            "/api/v1/wireless/guest",
+            Some(hashmap! { "enable" => u8::from(is_enabled).to_string() }),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, passphrase))]
+    async fn set_guest_network_credentials(
+        &self,
+        ssid: &str,
+        passphrase: &str,
+    ) -> RouterApiResult<()> {
+        self.write(
+            reqwest::Method::PUT,
+            "/api/v1/wireless/guest",
+            Some(hashmap! {
+                "ssid" => ssid.to_string(),
+                "passphrase" => passphrase.to_string(),
+                "security" => if passphrase.is_empty() { "none" } else { "wpa2" }.to_string()
+            }),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn router_info(&self) -> RouterApiResult<RouterInfo> {
+        let info = self.fetch_info().await?;
+
+        Ok(RouterInfo {
+            model: info.modelname,
+            firmware_version: info
+                .main
+                .map(|main| main.version)
+                .ok_or(BboxRouterApiError::MissingField("device.main".to_string()))?,
+            serial_number: info.serialnumber.filter(|serial| !serial.is_empty()),
+            uptime: info
+                .uptime
+                .value
+                .map(|uptime| chrono::Duration::seconds(uptime as i64)),
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn reboot(&self) -> RouterApiResult<()> {
+        self.write(reqwest::Method::POST, "/api/v1/device/reboot", None)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn export_config(&self) -> RouterApiResult<String> {
+        // The Bbox has no export format, the configuration endpoints are gathered as JSON instead
+        let mut config = serde_json::Map::new();
+        for path in BBOX_CONFIG_PATHS {
+            let mut endpoint = self.fetch_raw(path).await?;
+            strip_secrets(&mut endpoint);
+            config.insert(path.to_string(), endpoint);
+        }
+
+        serde_json::to_string_pretty(&config).map_err(|err| BboxRouterApiError::from(err).into())
+    }
 }
@@ -0,0 +1,593 @@
+use std::{collections::HashMap, net::IpAddr, sync::Arc};
+
+use entities::{
+    AccessSlot, Device, DeviceAccess, DeviceConnection, DeviceTrafficCounters, GuestNetwork,
+    PortForward, PortForwardRule, RouterInfo, StaticLease, WanConnectivity, WanStats, WifiRadio,
+};
+use futures::future::join_all;
+use ipnetwork::IpNetwork;
+use mac_address::MacAddress;
+use ports::api::{RouterApi, RouterApiError, RouterApiResult};
+use tracing::{instrument, warn};
+
+/// A router API merged by [`CompositeRouterApi`].
+pub struct CompositeMember {
+    pub name: String,
+    pub router_api: Arc<dyn RouterApi>,
+}
+
+/// Router API merging the devices seen by several router APIs (main router, mesh access points,
+/// DHCP server...), each seeing a different slice of the network.
+///
+/// Members are ordered by priority and devices are merged by MAC address:
+/// - the name is the first one reported by a member, by priority, that is not a mere IP address,
+/// - the IP address is the one reported by the member that saw the device last,
+/// - the device is online if any member sees it online.
+///
+/// The connection of a device is the Wi-Fi one reported by a member, by priority, falling back to
+/// the wired one, as access points usually see the devices of the others through their uplink.
+///
+/// The WAN information and the router settings (port forwarding, DHCP reservations, parental
+/// controls, Wi-Fi radios) come from the primary (first) member. Secondary members that are
+/// unavailable, or that could not be built at startup, are skipped so that the others can still be
+/// synced. The primary member must be built for the composite router API to start.
+pub struct CompositeRouterApi {
+    members: Vec<CompositeMember>,
+}
+
+impl CompositeRouterApi {
+    pub fn new(members: Vec<CompositeMember>) -> RouterApiResult<Self> {
+        if members.is_empty() {
+            return Err(RouterApiError::Unknown(
+                "The composite router API has no members".to_string(),
+            ));
+        }
+
+        Ok(Self { members })
+    }
+
+    fn primary(&self) -> &CompositeMember {
+        &self.members[0]
+    }
+}
+
+/// Whether a member failing with the error is skipped, so that the others can still be synced.
+/// Unknown errors (e.g. network ones) of the primary member are not, they would hide its settings.
+fn is_skipped(index: usize, err: &RouterApiError) -> bool {
+    match err {
+        RouterApiError::Unavailable => true,
+        RouterApiError::Unknown(_) => index > 0,
+        _ => false,
+    }
+}
+
+/// Whether the display name is an actual hostname, router APIs fall back to the IP address.
+fn has_hostname(device: &Device) -> bool {
+    device.display_name != device.last_known_ip.to_string()
+}
+
+/// Merges a device seen by a lower priority member into the one seen by higher priority members.
+fn merge_device(device: &mut Device, other: Device) {
+    let previous_ip = device.last_known_ip;
+    if !has_hostname(device) && has_hostname(&other) {
+        device.display_name = other.display_name.clone();
+    }
+
+    if other.last_seen > device.last_seen {
+        // Keep the fallback name in sync with the address it is made of
+        if !has_hostname(device) {
+            device.display_name = other.last_known_ip.to_string();
+        }
+        device.last_known_ip = other.last_known_ip;
+        device.last_seen = other.last_seen;
+    }
+
+    device.is_online |= other.is_online;
+    device.last_scanned = device.last_scanned.max(other.last_scanned);
+
+    // The addresses seen by every member are kept for the IP history
+    for ip in [previous_ip, other.last_known_ip]
+        .into_iter()
+        .chain(other.other_ips)
+    {
+        if ip != device.last_known_ip && !device.other_ips.contains(&ip) {
+            device.other_ips.push(ip);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RouterApi for CompositeRouterApi {
+    #[instrument(skip(self))]
+    async fn wan_connectivity(&self) -> RouterApiResult<WanConnectivity> {
+        self.primary().router_api.wan_connectivity().await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_devices(&self) -> RouterApiResult<Vec<Device>> {
+        let results = join_all(
+            self.members
+                .iter()
+                .map(|member| member.router_api.list_devices()),
+        )
+        .await;
+
+        let mut devices: Vec<Device> = Vec::new();
+        let mut indexes: HashMap<MacAddress, usize> = HashMap::new();
+        let mut available_members = 0;
+
+        for (index, (member, result)) in self.members.iter().zip(results).enumerate() {
+            let member_devices = match result {
+                Ok(member_devices) => member_devices,
+                Err(err) if is_skipped(index, &err) => {
+                    warn!(
+                        member = member.name,
+                        "Router API member failed, skipping it: {}", err
+                    );
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            available_members += 1;
+
+            for device in member_devices {
+                match indexes.get(&device.mac_address) {
+                    Some(&index) => merge_device(&mut devices[index], device),
+                    None => {
+                        indexes.insert(device.mac_address, devices.len());
+                        devices.push(device);
+                    }
+                }
+            }
+        }
+
+        if available_members == 0 {
+            return Err(RouterApiError::Unavailable);
+        }
+
+        Ok(devices)
+    }
+
+    #[instrument(skip(self))]
+    async fn wan_stats(&self) -> RouterApiResult<WanStats> {
+        self.primary().router_api.wan_stats().await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_port_forwards(&self) -> RouterApiResult<Vec<PortForward>> {
+        self.primary().router_api.list_port_forwards().await
+    }
+
+    #[instrument(skip(self))]
+    async fn create_port_forward(&self, rule: PortForwardRule) -> RouterApiResult<PortForward> {
+        self.primary().router_api.create_port_forward(rule).await
+    }
+
+    #[instrument(skip(self))]
+    async fn update_port_forward(
+        &self,
+        port_forward_id: &str,
+        rule: PortForwardRule,
+    ) -> RouterApiResult<PortForward> {
+        self.primary()
+            .router_api
+            .update_port_forward(port_forward_id, rule)
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_port_forward(&self, port_forward_id: &str) -> RouterApiResult<()> {
+        self.primary()
+            .router_api
+            .delete_port_forward(port_forward_id)
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn lan_network(&self) -> RouterApiResult<IpNetwork> {
+        self.primary().router_api.lan_network().await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_static_leases(&self) -> RouterApiResult<Vec<StaticLease>> {
+        self.primary().router_api.list_static_leases().await
+    }
+
+    #[instrument(skip(self))]
+    async fn reserve_ip(
+        &self,
+        mac_address: MacAddress,
+        ip_address: IpAddr,
+    ) -> RouterApiResult<StaticLease> {
+        self.primary()
+            .router_api
+            .reserve_ip(mac_address, ip_address)
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn release_ip(&self, mac_address: MacAddress) -> RouterApiResult<()> {
+        self.primary().router_api.release_ip(mac_address).await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_access(&self) -> RouterApiResult<Vec<DeviceAccess>> {
+        self.primary().router_api.list_device_access().await
+    }
+
+    #[instrument(skip(self))]
+    async fn set_device_blocked(
+        &self,
+        mac_address: MacAddress,
+        is_blocked: bool,
+    ) -> RouterApiResult<()> {
+        self.primary()
+            .router_api
+            .set_device_blocked(mac_address, is_blocked)
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn set_device_schedule(
+        &self,
+        mac_address: MacAddress,
+        schedule: Vec<AccessSlot>,
+    ) -> RouterApiResult<()> {
+        self.primary()
+            .router_api
+            .set_device_schedule(mac_address, schedule)
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_wifi_radios(&self) -> RouterApiResult<Vec<WifiRadio>> {
+        self.primary().router_api.list_wifi_radios().await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_connections(&self) -> RouterApiResult<Vec<DeviceConnection>> {
+        let results = join_all(
+            self.members
+                .iter()
+                .map(|member| member.router_api.list_device_connections()),
+        )
+        .await;
+
+        let mut connections: HashMap<MacAddress, DeviceConnection> = HashMap::new();
+        let mut available_members = 0;
+        let mut unavailable_members = 0;
+
+        for (index, (member, result)) in self.members.iter().zip(results).enumerate() {
+            let member_connections = match result {
+                Ok(member_connections) => member_connections,
+                Err(RouterApiError::Unsupported) => continue,
+                Err(err) if is_skipped(index, &err) => {
+                    warn!(
+                        member = member.name,
+                        "Router API member failed, skipping it: {}", err
+                    );
+                    unavailable_members += 1;
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            available_members += 1;
+
+            for mut connection in member_connections {
+                if connection.access_point.is_none() {
+                    connection.access_point = Some(member.name.clone());
+                }
+
+                match connections.get(&connection.mac_address) {
+                    Some(known) if known.is_wifi() || !connection.is_wifi() => (),
+                    _ => {
+                        connections.insert(connection.mac_address, connection);
+                    }
+                }
+            }
+        }
+
+        match (available_members, unavailable_members) {
+            (0, 0) => return Err(RouterApiError::Unsupported),
+            (0, _) => return Err(RouterApiError::Unavailable),
+            _ => (),
+        }
+
+        Ok(connections.into_values().collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_traffic(&self) -> RouterApiResult<Vec<DeviceTrafficCounters>> {
+        self.primary().router_api.list_device_traffic().await
+    }
+
+    #[instrument(skip(self))]
+    async fn guest_network(&self) -> RouterApiResult<GuestNetwork> {
+        self.primary().router_api.guest_network().await
+    }
+
+    #[instrument(skip(self))]
+    async fn set_guest_network_enabled(&self, is_enabled: bool) -> RouterApiResult<()> {
+        self.primary()
+            .router_api
+            .set_guest_network_enabled(is_enabled)
+            .await
+    }
+
+    #[instrument(skip(self, passphrase))]
+    async fn set_guest_network_credentials(
+        &self,
+        ssid: &str,
+        passphrase: &str,
+    ) -> RouterApiResult<()> {
+        self.primary()
+            .router_api
+            .set_guest_network_credentials(ssid, passphrase)
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn router_info(&self) -> RouterApiResult<RouterInfo> {
+        self.primary().router_api.router_info().await
+    }
+
+    #[instrument(skip(self))]
+    async fn reboot(&self) -> RouterApiResult<()> {
+        self.primary().router_api.reboot().await
+    }
+
+    #[instrument(skip(self))]
+    async fn export_config(&self) -> RouterApiResult<String> {
+        self.primary().router_api.export_config().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use entities::{ConnectionType, WifiBand};
+
+    use super::*;
+
+    struct StubRouterApi {
+        devices: RouterApiResult<Vec<Device>>,
+        connections: RouterApiResult<Vec<DeviceConnection>>,
+    }
+
+    #[async_trait::async_trait]
+    impl RouterApi for StubRouterApi {
+        async fn wan_connectivity(&self) -> RouterApiResult<WanConnectivity> {
+            Err(RouterApiError::Unsupported)
+        }
+
+        async fn list_devices(&self) -> RouterApiResult<Vec<Device>> {
+            self.devices.clone()
+        }
+
+        async fn wan_stats(&self) -> RouterApiResult<WanStats> {
+            Err(RouterApiError::Unsupported)
+        }
+
+        async fn list_device_connections(&self) -> RouterApiResult<Vec<DeviceConnection>> {
+            self.connections.clone()
+        }
+    }
+
+    fn mac(index: u8) -> MacAddress {
+        MacAddress::new([0x02, 0, 0, 0, 0, index])
+    }
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    fn device(ip_address: &str, name: Option<&str>, last_seen: DateTime<Utc>) -> Device {
+        Device {
+            mac_address: mac(1),
+            last_known_ip: ip(ip_address),
+            display_name: name.unwrap_or(ip_address).to_string(),
+            is_name_custom: false,
+            notes: String::new(),
+            is_online: true,
+            last_seen,
+            last_scanned: last_seen,
+            other_ips: Vec::new(),
+        }
+    }
+
+    fn wifi(access_point: Option<&str>) -> DeviceConnection {
+        DeviceConnection {
+            mac_address: mac(1),
+            connection_type: ConnectionType::Wifi,
+            band: Some(WifiBand::Band5GHz),
+            rssi: Some(-50),
+            link_rate: Some(866),
+            access_point: access_point.map(str::to_string),
+        }
+    }
+
+    fn member(name: &str, devices: RouterApiResult<Vec<Device>>) -> CompositeMember {
+        connections_member(name, devices, Err(RouterApiError::Unsupported))
+    }
+
+    fn connections_member(
+        name: &str,
+        devices: RouterApiResult<Vec<Device>>,
+        connections: RouterApiResult<Vec<DeviceConnection>>,
+    ) -> CompositeMember {
+        CompositeMember {
+            name: name.to_string(),
+            router_api: Arc::new(StubRouterApi {
+                devices,
+                connections,
+            }),
+        }
+    }
+
+    async fn merged(members: Vec<CompositeMember>) -> RouterApiResult<Vec<Device>> {
+        CompositeRouterApi::new(members)
+            .unwrap()
+            .list_devices()
+            .await
+    }
+
+    #[tokio::test]
+    async fn hostname_comes_from_the_first_member_knowing_it() {
+        let now = Utc::now();
+
+        let devices = merged(vec![
+            member("router", Ok(vec![device("192.168.1.10", None, now)])),
+            member("ap", Ok(vec![device("192.168.1.10", Some("tv"), now)])),
+            member("dhcp", Ok(vec![device("192.168.1.10", Some("tv-2"), now)])),
+        ])
+        .await
+        .unwrap();
+
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].display_name, "tv");
+    }
+
+    #[tokio::test]
+    async fn address_comes_from_the_member_that_saw_the_device_last() {
+        let now = Utc::now();
+        let mut seen_by_ap = device("192.168.1.20", None, now);
+        seen_by_ap.other_ips = vec![ip("fd00::20"), ip("192.168.1.10")];
+
+        let devices = merged(vec![
+            member(
+                "router",
+                Ok(vec![device(
+                    "192.168.1.10",
+                    None,
+                    now - chrono::Duration::minutes(10),
+                )]),
+            ),
+            member("ap", Ok(vec![seen_by_ap])),
+        ])
+        .await
+        .unwrap();
+
+        assert_eq!(devices[0].last_known_ip, ip("192.168.1.20"));
+        assert_eq!(devices[0].last_seen, now);
+        // The fallback name follows the address
+        assert_eq!(devices[0].display_name, "192.168.1.20");
+        // Every address seen is kept once
+        assert_eq!(devices[0].other_ips, [ip("192.168.1.10"), ip("fd00::20")]);
+    }
+
+    #[tokio::test]
+    async fn device_is_online_if_any_member_sees_it_online() {
+        let now = Utc::now();
+        let mut offline = device("192.168.1.10", Some("phone"), now);
+        offline.is_online = false;
+
+        let devices = merged(vec![
+            member("router", Ok(vec![offline.clone()])),
+            member(
+                "ap",
+                Ok(vec![device(
+                    "192.168.1.10",
+                    None,
+                    now - chrono::Duration::hours(1),
+                )]),
+            ),
+        ])
+        .await
+        .unwrap();
+        assert!(devices[0].is_online);
+
+        let devices = merged(vec![member("router", Ok(vec![offline]))])
+            .await
+            .unwrap();
+        assert!(!devices[0].is_online);
+    }
+
+    #[tokio::test]
+    async fn failing_members_are_skipped() {
+        let now = Utc::now();
+        let devices = || Ok(vec![device("192.168.1.10", Some("tv"), now)]);
+
+        // Secondary members are skipped on unavailability and unknown errors
+        let result = merged(vec![
+            member("router", devices()),
+            member("ap", Err(RouterApiError::Unavailable)),
+            member("dhcp", Err(RouterApiError::Unknown("timeout".to_string()))),
+        ])
+        .await;
+        assert_eq!(result.unwrap().len(), 1);
+
+        // The primary member is skipped when unavailable only
+        let result = merged(vec![
+            member("router", Err(RouterApiError::Unavailable)),
+            member("ap", devices()),
+        ])
+        .await;
+        assert_eq!(result.unwrap().len(), 1);
+
+        let result = merged(vec![
+            member(
+                "router",
+                Err(RouterApiError::Unknown("timeout".to_string())),
+            ),
+            member("ap", devices()),
+        ])
+        .await;
+        assert!(matches!(result, Err(RouterApiError::Unknown(_))));
+
+        // Other errors are never skipped
+        let result = merged(vec![
+            member("router", devices()),
+            member("ap", Err(RouterApiError::AuthenticationFailed)),
+        ])
+        .await;
+        assert!(matches!(result, Err(RouterApiError::AuthenticationFailed)));
+
+        let result = merged(vec![
+            member("router", Err(RouterApiError::Unavailable)),
+            member("ap", Err(RouterApiError::Unavailable)),
+        ])
+        .await;
+        assert!(matches!(result, Err(RouterApiError::Unavailable)));
+    }
+
+    #[tokio::test]
+    async fn wifi_connections_win_over_wired_ones() {
+        let router_api = CompositeRouterApi::new(vec![
+            // The router sees the device through the uplink of the access point
+            connections_member(
+                "router",
+                Ok(Vec::new()),
+                Ok(vec![DeviceConnection::wired(mac(1), Some(1000))]),
+            ),
+            connections_member("ap", Ok(Vec::new()), Ok(vec![wifi(None)])),
+            connections_member("ap-2", Ok(Vec::new()), Ok(vec![wifi(Some("ap-2-5g"))])),
+            connections_member(
+                "dhcp",
+                Ok(Vec::new()),
+                Err(RouterApiError::Unknown("timeout".to_string())),
+            ),
+        ])
+        .unwrap();
+
+        let connections = router_api.list_device_connections().await.unwrap();
+
+        assert_eq!(connections.len(), 1);
+        assert!(connections[0].is_wifi());
+        // The first Wi-Fi connection wins, named after its member when the access point is unknown
+        assert_eq!(connections[0].access_point.as_deref(), Some("ap"));
+    }
+
+    #[tokio::test]
+    async fn connections_are_unsupported_if_no_member_supports_them() {
+        let router_api = CompositeRouterApi::new(vec![
+            member("router", Ok(Vec::new())),
+            member("ap", Ok(Vec::new())),
+        ])
+        .unwrap();
+
+        assert!(matches!(
+            router_api.list_device_connections().await,
+            Err(RouterApiError::Unsupported)
+        ));
+    }
+}
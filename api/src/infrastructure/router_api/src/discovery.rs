@@ -0,0 +1,224 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    time::Duration,
+};
+
+use common::SweepMethod;
+use entities::{Device, WanConnectivity, WanStats};
+use futures::{StreamExt, stream};
+use ipnetwork::IpNetwork;
+use ports::api::{RouterApi, RouterApiError, RouterApiResult};
+use surge_ping::{Client, Config, PingIdentifier, PingSequence};
+use thiserror::Error;
+use tokio::net::UdpSocket;
+use tracing::{debug, error, instrument, warn};
+
+use crate::neighbours::parse_proc_net_arp;
+
+/// Largest IPv4 subnet that will be swept (a /16), to avoid flooding the network by mistake.
+const MAX_SWEEP_PREFIX: u8 = 16;
+
+/// Discard protocol port, probes sent to it are not expected to be answered.
+const DISCARD_PORT: u16 = 9;
+
+#[derive(Error, Debug)]
+enum DiscoveryRouterApiError {
+    #[error("Failed to read the neighbour table: {0}")]
+    NeighbourTable(std::io::Error),
+
+    #[error("Failed to open the sweep socket: {0}")]
+    Socket(std::io::Error),
+}
+
+impl From<DiscoveryRouterApiError> for RouterApiError {
+    fn from(err: DiscoveryRouterApiError) -> Self {
+        error!(
+            error = err.to_string(),
+            "An error occurred while using the router API"
+        );
+        match err {
+            DiscoveryRouterApiError::NeighbourTable(_) => RouterApiError::Unavailable,
+            DiscoveryRouterApiError::Socket(_) => RouterApiError::Unknown(err.to_string()),
+        }
+    }
+}
+
+/// Router API that does not talk to a router: devices are found in the neighbour table of the
+/// host, optionally filled beforehand by sweeping the configured subnets.
+pub struct DiscoveryRouterApi {
+    neighbour_table: PathBuf,
+    subnets: Vec<IpNetwork>,
+    sweep_method: SweepMethod,
+    sweep_timeout: Duration,
+    sweep_concurrency: usize,
+    resolve_hostnames: bool,
+    pinger: Option<Client>,
+}
+
+impl DiscoveryRouterApi {
+    pub fn new(
+        neighbour_table: PathBuf,
+        subnets: Vec<IpNetwork>,
+        sweep_method: SweepMethod,
+        sweep_timeout: Duration,
+        sweep_concurrency: usize,
+        resolve_hostnames: bool,
+    ) -> RouterApiResult<Self> {
+        let pinger = match sweep_method {
+            SweepMethod::Icmp if !subnets.is_empty() => {
+                Some(Client::new(&Config::default()).map_err(DiscoveryRouterApiError::Socket)?)
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            neighbour_table,
+            subnets,
+            sweep_method,
+            sweep_timeout,
+            sweep_concurrency: sweep_concurrency.max(1),
+            resolve_hostnames,
+            pinger,
+        })
+    }
+
+    /// Lists the addresses to probe, only IPv4 subnets up to [`MAX_SWEEP_PREFIX`] are swept.
+    fn sweep_targets(&self) -> Vec<Ipv4Addr> {
+        let mut targets = Vec::new();
+
+        for subnet in &self.subnets {
+            let IpNetwork::V4(subnet) = subnet else {
+                warn!(subnet = %subnet, "IPv6 subnets cannot be swept, ignoring");
+                continue;
+            };
+
+            if subnet.prefix() < MAX_SWEEP_PREFIX {
+                warn!(subnet = %subnet, "Subnet is too large to be swept, ignoring");
+                continue;
+            }
+
+            targets.extend(subnet.iter().filter(|address| {
+                // Network and broadcast addresses do not belong to a host, except in /31 and /32
+                subnet.prefix() >= 31
+                    || (*address != subnet.network() && *address != subnet.broadcast())
+            }));
+        }
+
+        targets
+    }
+
+    /// Probes every address of the configured subnets, so that the kernel resolves (and adds to
+    /// its neighbour table) the devices that did not talk to the host recently.
+    #[instrument(skip(self))]
+    async fn sweep(&self) -> RouterApiResult<()> {
+        let targets = self.sweep_targets();
+        if targets.is_empty() {
+            return Ok(());
+        }
+
+        match (self.sweep_method, &self.pinger) {
+            (SweepMethod::Icmp, Some(pinger)) => {
+                let payload = [0; 8];
+                stream::iter(targets.into_iter().enumerate())
+                    .for_each_concurrent(self.sweep_concurrency, |(index, address)| async move {
+                        let mut pinger = pinger
+                            .pinger(IpAddr::V4(address), PingIdentifier(index as u16))
+                            .await;
+                        pinger.timeout(self.sweep_timeout);
+                        if let Err(err) = pinger.ping(PingSequence(0), &payload).await {
+                            debug!(address = %address, error = %err, "No answer to ping");
+                        }
+                    })
+                    .await;
+            }
+            _ => {
+                let socket = UdpSocket::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+                    .await
+                    .map_err(DiscoveryRouterApiError::Socket)?;
+
+                for address in targets {
+                    // Unreachable hosts are expected, they are simply not added to the table
+                    if let Err(err) = socket.send_to(&[], (address, DISCARD_PORT)).await {
+                        debug!(address = %address, error = %err, "Failed to send probe");
+                    }
+                }
+
+                // Leave some time to the neighbours to answer the resolution requests
+                tokio::time::sleep(self.sweep_timeout).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the hostname of the given address through the system resolver (reverse DNS,
+    /// hosts file, mDNS...), if any.
+    async fn resolve_hostname(ip_address: IpAddr) -> Option<String> {
+        tokio::task::spawn_blocking(move || dns_lookup::lookup_addr(&ip_address))
+            .await
+            .ok()?
+            .ok()
+            // The resolver falls back to the textual address when there is no name
+            .filter(|hostname| hostname.parse::<IpAddr>().is_err())
+            .map(|hostname| hostname.trim_end_matches('.').to_string())
+    }
+}
+
+#[async_trait::async_trait]
+impl RouterApi for DiscoveryRouterApi {
+    #[instrument(skip(self))]
+    async fn wan_connectivity(&self) -> RouterApiResult<WanConnectivity> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_devices(&self) -> RouterApiResult<Vec<Device>> {
+        self.sweep().await?;
+
+        let content = tokio::fs::read_to_string(&self.neighbour_table)
+            .await
+            .map_err(DiscoveryRouterApiError::NeighbourTable)?;
+
+        // A device can appear several times if it has multiple addresses, only keep the first one
+        let mut neighbours = HashMap::new();
+        for neighbour in parse_proc_net_arp(&content) {
+            neighbours
+                .entry(neighbour.mac_address)
+                .or_insert(neighbour.ip_address);
+        }
+
+        let now = chrono::Utc::now();
+        let resolve_hostnames = self.resolve_hostnames;
+
+        Ok(stream::iter(neighbours)
+            .map(|(mac_address, ip_address)| async move {
+                let hostname = if resolve_hostnames {
+                    Self::resolve_hostname(ip_address).await
+                } else {
+                    None
+                };
+
+                Device {
+                    mac_address,
+                    last_known_ip: ip_address,
+                    display_name: hostname.unwrap_or_else(|| ip_address.to_string()),
+                    is_name_custom: false,
+                    notes: String::new(),
+                    is_online: true,
+                    last_seen: now,
+                    last_scanned: now,
+                    other_ips: Vec::new(),
+                }
+            })
+            .buffer_unordered(self.sweep_concurrency)
+            .collect()
+            .await)
+    }
+
+    #[instrument(skip(self))]
+    async fn wan_stats(&self) -> RouterApiResult<WanStats> {
+        Err(RouterApiError::Unsupported)
+    }
+}
@@ -0,0 +1,397 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use digest_auth::{AuthContext, WwwAuthenticateHeader};
+use entities::{Device, WanConnectivity, WanStats, WanStatsItem, WanStatus};
+use mac_address::MacAddress;
+use ports::api::{RouterApi, RouterApiError, RouterApiResult};
+use quick_xml::{escape::escape, events::Event};
+use reqwest::{StatusCode, Url, header};
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tracing::{error, instrument, warn};
+
+/// UPnP error code returned when the user is not allowed to perform an action.
+const ACTION_NOT_AUTHORIZED: u32 = 606;
+
+#[derive(Error, Debug)]
+enum FritzboxRouterApiError {
+    #[error("Request failed: {0}")]
+    RequestFailed(#[from] reqwest::Error),
+
+    #[error("API returned unexpected status: {0}")]
+    UnexpectedStatus(StatusCode),
+
+    #[error("Failed to parse response body: {0}")]
+    ParseError(String),
+
+    #[error("Missing required field: {0}")]
+    MissingField(String),
+
+    #[error("Digest authentication failed: {0}")]
+    DigestError(#[from] digest_auth::Error),
+
+    #[error("SOAP fault {code}: {description}")]
+    Fault { code: u32, description: String },
+}
+
+impl From<quick_xml::Error> for FritzboxRouterApiError {
+    fn from(err: quick_xml::Error) -> Self {
+        FritzboxRouterApiError::ParseError(err.to_string())
+    }
+}
+
+impl From<FritzboxRouterApiError> for RouterApiError {
+    fn from(err: FritzboxRouterApiError) -> Self {
+        error!(
+            error = err.to_string(),
+            "An error occurred while using the router API"
+        );
+        match err {
+            FritzboxRouterApiError::RequestFailed(_) => RouterApiError::Unavailable,
+            FritzboxRouterApiError::UnexpectedStatus(StatusCode::UNAUTHORIZED)
+            | FritzboxRouterApiError::DigestError(_)
+            | FritzboxRouterApiError::Fault {
+                code: ACTION_NOT_AUTHORIZED,
+                ..
+            } => RouterApiError::AuthenticationFailed,
+            FritzboxRouterApiError::UnexpectedStatus(_)
+            | FritzboxRouterApiError::ParseError(_)
+            | FritzboxRouterApiError::MissingField(_) => {
+                RouterApiError::InvalidResponse(err.to_string())
+            }
+            FritzboxRouterApiError::Fault { .. } => RouterApiError::Unknown(err.to_string()),
+        }
+    }
+}
+
+/// A TR-064 service, identified by its type and the URL of its control endpoint.
+struct Tr064Service {
+    service_type: &'static str,
+    control_url: &'static str,
+}
+
+const HOSTS: Tr064Service = Tr064Service {
+    service_type: "urn:dslforum-org:service:Hosts:1",
+    control_url: "/upnp/control/hosts",
+};
+
+const WAN_IP_CONNECTION: Tr064Service = Tr064Service {
+    service_type: "urn:dslforum-org:service:WANIPConnection:1",
+    control_url: "/upnp/control/wanipconnection1",
+};
+
+const WAN_COMMON_INTERFACE_CONFIG: Tr064Service = Tr064Service {
+    service_type: "urn:dslforum-org:service:WANCommonInterfaceConfig:1",
+    control_url: "/upnp/control/wancommonifconfig1",
+};
+
+/// The output arguments of a TR-064 action, indexed by name (e.g. `NewUptime`).
+struct Tr064Response(HashMap<String, String>);
+
+impl Tr064Response {
+    fn parse(body: &str) -> Result<Self, FritzboxRouterApiError> {
+        let mut reader = quick_xml::Reader::from_str(body);
+        reader.config_mut().trim_text(true);
+
+        let mut values = HashMap::new();
+        let mut current = None;
+
+        loop {
+            match reader.read_event()? {
+                Event::Start(element) => {
+                    let name = String::from_utf8_lossy(element.local_name().as_ref()).to_string();
+                    values.insert(name.clone(), String::new());
+                    current = Some(name);
+                }
+                Event::Empty(element) => {
+                    let name = String::from_utf8_lossy(element.local_name().as_ref()).to_string();
+                    values.insert(name, String::new());
+                }
+                Event::Text(text) => {
+                    if let Some(name) = &current {
+                        values.insert(
+                            name.clone(),
+                            text.unescape()
+                                .map_err(|err| FritzboxRouterApiError::ParseError(err.to_string()))?
+                                .to_string(),
+                        );
+                    }
+                }
+                Event::End(_) => current = None,
+                Event::Eof => break,
+                _ => (),
+            }
+        }
+
+        Ok(Self(values))
+    }
+
+    fn fault(&self) -> Option<FritzboxRouterApiError> {
+        self.0
+            .get("errorCode")
+            .map(|code| FritzboxRouterApiError::Fault {
+                code: code.parse().unwrap_or_default(),
+                description: self.0.get("errorDescription").cloned().unwrap_or_default(),
+            })
+    }
+
+    fn get<T: FromStr>(&self, name: &str) -> Result<T, FritzboxRouterApiError> {
+        self.0
+            .get(name)
+            .ok_or_else(|| FritzboxRouterApiError::MissingField(name.to_string()))?
+            .parse()
+            .map_err(|_| FritzboxRouterApiError::ParseError(format!("Invalid value for {}", name)))
+    }
+
+    /// Same as `get`, but treats empty values as absent.
+    fn get_optional<T: FromStr>(&self, name: &str) -> Result<Option<T>, FritzboxRouterApiError> {
+        match self.0.get(name) {
+            Some(value) if !value.is_empty() => self.get(name).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+pub struct FritzboxRouterApi {
+    client: reqwest::Client,
+    base_url: Url,
+    username: String,
+    password: String,
+    challenge: Mutex<Option<WwwAuthenticateHeader>>,
+}
+
+impl FritzboxRouterApi {
+    pub async fn new(
+        base_url: Url,
+        username: String,
+        password: String,
+    ) -> Result<Self, RouterApiError> {
+        let api = Self {
+            client: reqwest::Client::new(),
+            base_url,
+            username,
+            password,
+            challenge: Mutex::new(None),
+        };
+
+        // Make sure the credentials are valid before accepting any call
+        api.call(&HOSTS, "GetHostNumberOfEntries", &[]).await?;
+
+        Ok(api)
+    }
+
+    async fn send(
+        &self,
+        service: &Tr064Service,
+        action: &str,
+        body: &str,
+    ) -> Result<reqwest::Response, FritzboxRouterApiError> {
+        let mut request = self
+            .client
+            .post(self.base_url.join(service.control_url).unwrap())
+            .header(header::CONTENT_TYPE, r#"text/xml; charset="utf-8""#)
+            .header("SOAPACTION", format!("{}#{}", service.service_type, action))
+            .body(body.to_string());
+
+        if let Some(challenge) = self.challenge.lock().await.as_mut() {
+            let context = AuthContext::new_post(
+                &self.username,
+                &self.password,
+                service.control_url,
+                Some(body.as_bytes()),
+            );
+            request = request.header(
+                header::AUTHORIZATION,
+                challenge.respond(&context)?.to_header_string(),
+            );
+        }
+
+        Ok(request.send().await?)
+    }
+
+    /// Sends the request, answering the digest challenge and retrying once if the router asks
+    /// for (re-)authentication.
+    async fn handle_disconnect(
+        &self,
+        service: &Tr064Service,
+        action: &str,
+        body: &str,
+    ) -> Result<reqwest::Response, FritzboxRouterApiError> {
+        let response = self.send(service, action, body).await?;
+
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        let challenge = response
+            .headers()
+            .get(header::WWW_AUTHENTICATE)
+            .ok_or(FritzboxRouterApiError::MissingField(
+                "WWW-Authenticate".to_string(),
+            ))?
+            .to_str()
+            .map_err(|_| FritzboxRouterApiError::MissingField("WWW-Authenticate".to_string()))?;
+
+        *self.challenge.lock().await = Some(digest_auth::parse(challenge)?);
+
+        self.send(service, action, body).await
+    }
+
+    #[instrument(skip(self, service), fields(service = service.service_type))]
+    async fn call(
+        &self,
+        service: &Tr064Service,
+        action: &str,
+        arguments: &[(&str, String)],
+    ) -> Result<Tr064Response, FritzboxRouterApiError> {
+        let arguments = arguments
+            .iter()
+            .map(|(name, value)| format!("<{name}>{}</{name}>", escape(value.as_str())))
+            .collect::<String>();
+
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/"><s:Body><u:{action} xmlns:u="{}">{arguments}</u:{action}></s:Body></s:Envelope>"#,
+            service.service_type
+        );
+
+        let response = self.handle_disconnect(service, action, &body).await?;
+        let status = response.status();
+        let response = Tr064Response::parse(&response.text().await?)?;
+
+        // Faults are returned with a 500 status code
+        if let Some(fault) = response.fault() {
+            return Err(fault);
+        }
+
+        if status != StatusCode::OK {
+            return Err(FritzboxRouterApiError::UnexpectedStatus(status));
+        }
+
+        Ok(response)
+    }
+}
+
+#[async_trait::async_trait]
+impl RouterApi for FritzboxRouterApi {
+    #[instrument(skip(self))]
+    async fn wan_connectivity(&self) -> RouterApiResult<WanConnectivity> {
+        let info = self.call(&WAN_IP_CONNECTION, "GetInfo", &[]).await?;
+
+        // Only available on firmwares with IPv6 enabled
+        let ipv6 = match self
+            .call(&WAN_IP_CONNECTION, "X_AVM_DE_GetExternalIPv6Address", &[])
+            .await
+        {
+            Ok(response) => response
+                .get_optional::<Ipv6Addr>("NewExternalIPv6Address")
+                .map_err(RouterApiError::from)?,
+            Err(err) => {
+                warn!(
+                    error = err.to_string(),
+                    "Failed to fetch the external IPv6 address"
+                );
+                None
+            }
+        };
+
+        Ok(WanConnectivity {
+            ipv4: info
+                .get_optional("NewExternalIPAddress")?
+                .unwrap_or(Ipv4Addr::UNSPECIFIED),
+            ipv6,
+            gateway: info
+                .get_optional("NewDefaultGateway")?
+                .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+            status: match info.get::<String>("NewConnectionStatus")?.as_str() {
+                "Connected" => WanStatus::Up,
+                _ => WanStatus::Down,
+            },
+            uptime: Some(chrono::Duration::seconds(info.get("NewUptime")?)),
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn list_devices(&self) -> RouterApiResult<Vec<Device>> {
+        let count: usize = self
+            .call(&HOSTS, "GetHostNumberOfEntries", &[])
+            .await?
+            .get("NewHostNumberOfEntries")?;
+
+        let now = chrono::Utc::now();
+        let mut devices = Vec::with_capacity(count);
+
+        for index in 0..count {
+            let host = self
+                .call(
+                    &HOSTS,
+                    "GetGenericHostEntry",
+                    &[("NewIndex", index.to_string())],
+                )
+                .await?;
+
+            // The FRITZ!Box does not tell when an inactive host was last seen. They are skipped
+            // so that the synchronization marks them offline without touching their `last_seen`.
+            if host.get::<u8>("NewActive")? != 1 {
+                continue;
+            }
+
+            let (Some(mac_address), Some(ip_address)) = (
+                host.get_optional::<MacAddress>("NewMACAddress")?,
+                host.get_optional::<IpAddr>("NewIPAddress")?,
+            ) else {
+                continue;
+            };
+
+            devices.push(Device {
+                mac_address,
+                last_known_ip: ip_address,
+                display_name: host
+                    .get_optional("NewHostName")?
+                    .unwrap_or_else(|| ip_address.to_string()),
+                is_name_custom: false,
+                notes: String::new(),
+                is_online: true,
+                last_seen: now,
+                last_scanned: now,
+                other_ips: Vec::new(),
+            });
+        }
+
+        if devices.is_empty() {
+            warn!("Router API returned an empty list of devices. This should never happen!");
+        }
+
+        Ok(devices)
+    }
+
+    #[instrument(skip(self))]
+    async fn wan_stats(&self) -> RouterApiResult<WanStats> {
+        let link = self
+            .call(&WAN_COMMON_INTERFACE_CONFIG, "GetCommonLinkProperties", &[])
+            .await?;
+        let addon = self
+            .call(&WAN_COMMON_INTERFACE_CONFIG, "GetAddonInfos", &[])
+            .await?;
+
+        // Rates are in bytes per second, bitrates in bits per second
+        Ok(WanStats {
+            download: WanStatsItem {
+                max_bandwidth: link.get::<usize>("NewLayer1DownstreamMaxBitRate")? / 1000,
+                current_bandwidth: addon.get::<usize>("NewByteReceiveRate")? * 8 / 1000,
+                total_since_last_reboot: addon.get("NewX_AVM_DE_TotalBytesReceived64")?,
+                packets_lost: 0, // Not exposed through TR-064
+            },
+            upload: WanStatsItem {
+                max_bandwidth: link.get::<usize>("NewLayer1UpstreamMaxBitRate")? / 1000,
+                current_bandwidth: addon.get::<usize>("NewByteSendRate")? * 8 / 1000,
+                total_since_last_reboot: addon.get("NewX_AVM_DE_TotalBytesSent64")?,
+                packets_lost: 0, // Not exposed through TR-064
+            },
+            active_sessions: 0, // Not exposed through TR-064
+        })
+    }
+}
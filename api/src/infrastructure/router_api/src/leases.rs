@@ -0,0 +1,731 @@
+use std::{collections::HashMap, net::IpAddr, path::PathBuf, sync::Arc, time::SystemTime};
+
+use chrono::{DateTime, Utc};
+use entities::{
+    AccessSlot, Device, DeviceAccess, DeviceConnection, DeviceTrafficCounters, GuestNetwork,
+    PortForward, PortForwardRule, RouterInfo, StaticLease, WanConnectivity, WanStats, WifiRadio,
+};
+use ipnetwork::IpNetwork;
+use mac_address::MacAddress;
+use ports::api::{RouterApi, RouterApiError, RouterApiResult};
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tracing::{debug, error, instrument, warn};
+
+#[derive(Error, Debug)]
+enum LeasesRouterApiError {
+    #[error("Failed to read lease file {0}: {1}")]
+    ReadFailed(PathBuf, std::io::Error),
+}
+
+impl From<LeasesRouterApiError> for RouterApiError {
+    fn from(err: LeasesRouterApiError) -> Self {
+        error!(
+            error = err.to_string(),
+            "An error occurred while using the router API"
+        );
+        match err {
+            LeasesRouterApiError::ReadFailed(_, _) => RouterApiError::Unavailable,
+        }
+    }
+}
+
+/// A DHCP lease, as found in a lease file.
+#[derive(Clone)]
+struct Lease {
+    mac_address: MacAddress,
+    ip_address: IpAddr,
+    hostname: Option<String>,
+    renewed_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>, // None if the lease never expires
+}
+
+/// Address family of a lease, devices may hold both a DHCPv4 and a DHCPv6 lease.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Family {
+    V4,
+    V6,
+}
+
+impl Family {
+    fn of(ip_address: IpAddr) -> Self {
+        match ip_address {
+            IpAddr::V4(_) => Family::V4,
+            IpAddr::V6(_) => Family::V6,
+        }
+    }
+}
+
+impl Lease {
+    fn family(&self) -> Family {
+        Family::of(self.ip_address)
+    }
+
+    fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+
+    fn outlives(&self, other: &Lease) -> bool {
+        match (self.expires_at, other.expires_at) {
+            (None, _) => true,
+            (_, None) => false,
+            (Some(expires_at), Some(other_expires_at)) => expires_at > other_expires_at,
+        }
+    }
+}
+
+/// Parses a dnsmasq lease file (`<expiry> <mac> <ip> <hostname> <client id>` lines).
+///
+/// dnsmasq only records the expiry of the lease, `lease_time` is used to estimate when it was
+/// last renewed. DHCPv6 leases are identified by their IAID instead of a MAC address and are
+/// skipped.
+fn parse_dnsmasq_leases(content: &str, lease_time: chrono::Duration) -> Vec<Lease> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+            if columns.len() < 4 {
+                return None;
+            }
+
+            let expires_at = match columns[0].parse::<i64>().ok()? {
+                0 => None,
+                expiry => Some(DateTime::from_timestamp(expiry, 0)?),
+            };
+
+            Some(Lease {
+                mac_address: columns[1].parse().ok()?,
+                ip_address: columns[2].parse().ok()?,
+                hostname: Some(columns[3])
+                    .filter(|hostname| *hostname != "*")
+                    .map(str::to_string),
+                renewed_at: expires_at.map(|expires_at| expires_at - lease_time),
+                expires_at,
+            })
+        })
+        .collect()
+}
+
+/// Parses a Kea memfile lease file (CSV with a header, for both DHCPv4 and DHCPv6).
+///
+/// The file is append-only until Kea cleans it up, so the last line of an address wins.
+fn parse_kea_leases(content: &str) -> Vec<Lease> {
+    const STATE_DEFAULT: &str = "0";
+
+    let mut lines = content.lines();
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+
+    let columns = header.split(',').collect::<Vec<_>>();
+    let index = |name: &str| columns.iter().position(|column| *column == name);
+    let (Some(address), Some(hwaddr), Some(expire)) =
+        (index("address"), index("hwaddr"), index("expire"))
+    else {
+        warn!("Kea lease file header is missing required columns");
+        return Vec::new();
+    };
+    let valid_lifetime = index("valid_lifetime");
+    let hostname = index("hostname");
+    let state = index("state");
+
+    let mut leases = HashMap::new();
+    for line in lines {
+        let fields = line.split(',').collect::<Vec<_>>();
+        let field = |index: Option<usize>| index.and_then(|index| fields.get(index).copied());
+
+        let Some(ip_address) = field(Some(address)).and_then(|ip| ip.parse::<IpAddr>().ok()) else {
+            continue;
+        };
+
+        // Declined and reclaimed leases do not belong to anyone anymore
+        if field(state).is_some_and(|state| state != STATE_DEFAULT) {
+            leases.remove(&ip_address);
+            continue;
+        }
+
+        let Some(mac_address) = field(Some(hwaddr)).and_then(|mac| mac.parse().ok()) else {
+            continue;
+        };
+        let Some(expires_at) = field(Some(expire))
+            .and_then(|expire| expire.parse().ok())
+            .and_then(|expire| DateTime::from_timestamp(expire, 0))
+        else {
+            continue;
+        };
+
+        leases.insert(
+            ip_address,
+            Lease {
+                mac_address,
+                ip_address,
+                hostname: field(hostname)
+                    .map(|hostname| {
+                        hostname
+                            .replace("&#x2c", ",")
+                            .trim_end_matches('.')
+                            .to_string()
+                    })
+                    .filter(|hostname| !hostname.is_empty()),
+                renewed_at: field(valid_lifetime)
+                    .and_then(|lifetime| lifetime.parse().ok())
+                    .map(|lifetime| expires_at - chrono::Duration::seconds(lifetime)),
+                expires_at: Some(expires_at),
+            },
+        );
+    }
+
+    leases.into_values().collect()
+}
+
+/// A lease file, parsed again whenever it is modified.
+struct LeaseFile {
+    path: PathBuf,
+    cache: Mutex<Option<(SystemTime, Vec<Lease>)>>,
+}
+
+impl LeaseFile {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            cache: Mutex::new(None),
+        }
+    }
+
+    async fn leases(&self, lease_time: chrono::Duration) -> RouterApiResult<Vec<Lease>> {
+        let read_failed = |err| LeasesRouterApiError::ReadFailed(self.path.clone(), err);
+
+        let modified_at = tokio::fs::metadata(&self.path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .map_err(read_failed)?;
+
+        let mut cache = self.cache.lock().await;
+        if let Some((cached_at, leases)) = cache.as_ref()
+            && *cached_at == modified_at
+        {
+            return Ok(leases.clone());
+        }
+
+        debug!(path = %self.path.display(), "Lease file changed, parsing it again");
+        let content = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(read_failed)?;
+
+        // Kea memfiles are CSV files whose header starts with the address column
+        let leases = if content.starts_with("address,") {
+            parse_kea_leases(&content)
+        } else {
+            parse_dnsmasq_leases(&content, lease_time)
+        };
+
+        *cache = Some((modified_at, leases.clone()));
+        Ok(leases)
+    }
+}
+
+/// The leases of a set of lease files, indexed by MAC address.
+pub struct LeaseDatabase {
+    files: Vec<LeaseFile>,
+    lease_time: chrono::Duration,
+}
+
+impl LeaseDatabase {
+    pub fn new(paths: Vec<PathBuf>, lease_time: chrono::Duration) -> Self {
+        Self {
+            files: paths.into_iter().map(LeaseFile::new).collect(),
+            lease_time,
+        }
+    }
+
+    /// Returns the most recent lease of each device, for each address family.
+    async fn leases(&self) -> RouterApiResult<HashMap<(MacAddress, Family), Lease>> {
+        let mut leases = Vec::new();
+        for file in &self.files {
+            leases.extend(file.leases(self.lease_time).await?);
+        }
+
+        Ok(latest_leases(leases))
+    }
+}
+
+fn latest_leases(leases: Vec<Lease>) -> HashMap<(MacAddress, Family), Lease> {
+    let mut latest: HashMap<(MacAddress, Family), Lease> = HashMap::new();
+
+    for lease in leases {
+        let key = (lease.mac_address, lease.family());
+        if latest
+            .get(&key)
+            .is_none_or(|existing| lease.outlives(existing))
+        {
+            latest.insert(key, lease);
+        }
+    }
+
+    latest
+}
+
+/// Fills the name and addresses of a device from one of its leases. An active lease replaces the
+/// address of the same family, IPv4 addresses being preferred to IPv6 ones as the main address.
+fn apply_lease(device: &mut Device, lease: &Lease, now: DateTime<Utc>) {
+    if let Some(hostname) = &lease.hostname {
+        device.display_name = hostname.clone();
+    }
+
+    if !lease.is_active(now) || lease.ip_address == device.last_known_ip {
+        return;
+    }
+
+    let other_ip = match (Family::of(device.last_known_ip), lease.family()) {
+        (Family::V4, Family::V6) => Some(lease.ip_address),
+        (Family::V6, Family::V4) => Some(std::mem::replace(
+            &mut device.last_known_ip,
+            lease.ip_address,
+        )),
+        _ => {
+            device.last_known_ip = lease.ip_address;
+            None
+        }
+    };
+
+    device.other_ips.retain(|ip| *ip != device.last_known_ip);
+    if let Some(other_ip) = other_ip
+        && !device.other_ips.contains(&other_ip)
+    {
+        device.other_ips.push(other_ip);
+    }
+}
+
+/// Router API listing the devices holding a DHCP lease, for networks whose DHCP server is not
+/// the router. WAN information is not available with this backend.
+pub struct LeasesRouterApi {
+    database: LeaseDatabase,
+}
+
+impl LeasesRouterApi {
+    pub fn new(database: LeaseDatabase) -> Self {
+        Self { database }
+    }
+}
+
+#[async_trait::async_trait]
+impl RouterApi for LeasesRouterApi {
+    #[instrument(skip(self))]
+    async fn wan_connectivity(&self) -> RouterApiResult<WanConnectivity> {
+        Err(RouterApiError::Unsupported)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_devices(&self) -> RouterApiResult<Vec<Device>> {
+        let now = Utc::now();
+
+        let mut leases = self
+            .database
+            .leases()
+            .await?
+            .into_values()
+            .collect::<Vec<_>>();
+        // The device is built from its IPv4 lease when it has one
+        leases.sort_by_key(|lease| lease.family() == Family::V6);
+
+        let mut devices: HashMap<MacAddress, Device> = HashMap::new();
+        for lease in leases {
+            let is_online = lease.is_active(now);
+            let last_seen = if is_online {
+                now
+            } else {
+                lease.renewed_at.unwrap_or(now).min(now)
+            };
+
+            match devices.get_mut(&lease.mac_address) {
+                Some(device) => {
+                    if !device.other_ips.contains(&lease.ip_address) {
+                        device.other_ips.push(lease.ip_address);
+                    }
+                    if device.display_name == device.last_known_ip.to_string()
+                        && let Some(hostname) = lease.hostname
+                    {
+                        device.display_name = hostname;
+                    }
+                    device.is_online |= is_online;
+                    device.last_seen = device.last_seen.max(last_seen);
+                }
+                None => {
+                    devices.insert(
+                        lease.mac_address,
+                        Device {
+                            mac_address: lease.mac_address,
+                            last_known_ip: lease.ip_address,
+                            display_name: lease
+                                .hostname
+                                .unwrap_or_else(|| lease.ip_address.to_string()),
+                            is_name_custom: false,
+                            notes: String::new(),
+                            is_online,
+                            last_seen,
+                            last_scanned: now,
+                            other_ips: Vec::new(),
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(devices.into_values().collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn wan_stats(&self) -> RouterApiResult<WanStats> {
+        Err(RouterApiError::Unsupported)
+    }
+}
+
+/// Router API decorator filling the name and address of the devices found by another router API
+/// from the DHCP leases, which usually know the hostnames better than the router.
+pub struct LeaseEnrichedRouterApi {
+    inner: Arc<dyn RouterApi>,
+    database: LeaseDatabase,
+}
+
+impl LeaseEnrichedRouterApi {
+    pub fn new(inner: Arc<dyn RouterApi>, database: LeaseDatabase) -> Self {
+        Self { inner, database }
+    }
+}
+
+#[async_trait::async_trait]
+impl RouterApi for LeaseEnrichedRouterApi {
+    #[instrument(skip(self))]
+    async fn wan_connectivity(&self) -> RouterApiResult<WanConnectivity> {
+        self.inner.wan_connectivity().await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_devices(&self) -> RouterApiResult<Vec<Device>> {
+        let mut devices = self.inner.list_devices().await?;
+
+        // The devices are still worth syncing without their leases
+        let leases = match self.database.leases().await {
+            Ok(leases) => leases,
+            Err(err) => {
+                warn!(error = %err, "Failed to read leases, devices are not enriched");
+                return Ok(devices);
+            }
+        };
+
+        let now = Utc::now();
+        for device in &mut devices {
+            // The IPv4 lease comes last so that its hostname wins
+            for family in [Family::V6, Family::V4] {
+                if let Some(lease) = leases.get(&(device.mac_address, family)) {
+                    apply_lease(device, lease, now);
+                }
+            }
+        }
+
+        Ok(devices)
+    }
+
+    #[instrument(skip(self))]
+    async fn wan_stats(&self) -> RouterApiResult<WanStats> {
+        self.inner.wan_stats().await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_port_forwards(&self) -> RouterApiResult<Vec<PortForward>> {
+        self.inner.list_port_forwards().await
+    }
+
+    #[instrument(skip(self))]
+    async fn create_port_forward(&self, rule: PortForwardRule) -> RouterApiResult<PortForward> {
+        self.inner.create_port_forward(rule).await
+    }
+
+    #[instrument(skip(self))]
+    async fn update_port_forward(
+        &self,
+        port_forward_id: &str,
+        rule: PortForwardRule,
+    ) -> RouterApiResult<PortForward> {
+        self.inner.update_port_forward(port_forward_id, rule).await
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_port_forward(&self, port_forward_id: &str) -> RouterApiResult<()> {
+        self.inner.delete_port_forward(port_forward_id).await
+    }
+
+    #[instrument(skip(self))]
+    async fn lan_network(&self) -> RouterApiResult<IpNetwork> {
+        self.inner.lan_network().await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_static_leases(&self) -> RouterApiResult<Vec<StaticLease>> {
+        self.inner.list_static_leases().await
+    }
+
+    #[instrument(skip(self))]
+    async fn reserve_ip(
+        &self,
+        mac_address: MacAddress,
+        ip_address: IpAddr,
+    ) -> RouterApiResult<StaticLease> {
+        self.inner.reserve_ip(mac_address, ip_address).await
+    }
+
+    #[instrument(skip(self))]
+    async fn release_ip(&self, mac_address: MacAddress) -> RouterApiResult<()> {
+        self.inner.release_ip(mac_address).await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_access(&self) -> RouterApiResult<Vec<DeviceAccess>> {
+        self.inner.list_device_access().await
+    }
+
+    #[instrument(skip(self))]
+    async fn set_device_blocked(
+        &self,
+        mac_address: MacAddress,
+        is_blocked: bool,
+    ) -> RouterApiResult<()> {
+        self.inner.set_device_blocked(mac_address, is_blocked).await
+    }
+
+    #[instrument(skip(self))]
+    async fn set_device_schedule(
+        &self,
+        mac_address: MacAddress,
+        schedule: Vec<AccessSlot>,
+    ) -> RouterApiResult<()> {
+        self.inner.set_device_schedule(mac_address, schedule).await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_wifi_radios(&self) -> RouterApiResult<Vec<WifiRadio>> {
+        self.inner.list_wifi_radios().await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_connections(&self) -> RouterApiResult<Vec<DeviceConnection>> {
+        self.inner.list_device_connections().await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_traffic(&self) -> RouterApiResult<Vec<DeviceTrafficCounters>> {
+        self.inner.list_device_traffic().await
+    }
+
+    #[instrument(skip(self))]
+    async fn guest_network(&self) -> RouterApiResult<GuestNetwork> {
+        self.inner.guest_network().await
+    }
+
+    #[instrument(skip(self))]
+    async fn set_guest_network_enabled(&self, is_enabled: bool) -> RouterApiResult<()> {
+        self.inner.set_guest_network_enabled(is_enabled).await
+    }
+
+    #[instrument(skip(self, passphrase))]
+    async fn set_guest_network_credentials(
+        &self,
+        ssid: &str,
+        passphrase: &str,
+    ) -> RouterApiResult<()> {
+        self.inner
+            .set_guest_network_credentials(ssid, passphrase)
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn router_info(&self) -> RouterApiResult<RouterInfo> {
+        self.inner.router_info().await
+    }
+
+    #[instrument(skip(self))]
+    async fn reboot(&self) -> RouterApiResult<()> {
+        self.inner.reboot().await
+    }
+
+    #[instrument(skip(self))]
+    async fn export_config(&self) -> RouterApiResult<String> {
+        self.inner.export_config().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamp(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(seconds, 0).unwrap()
+    }
+
+    fn lease(ip_address: &str, hostname: Option<&str>, expires_at: DateTime<Utc>) -> Lease {
+        Lease {
+            mac_address: "aa:bb:cc:dd:ee:01".parse().unwrap(),
+            ip_address: ip_address.parse().unwrap(),
+            hostname: hostname.map(str::to_string),
+            renewed_at: None,
+            expires_at: Some(expires_at),
+        }
+    }
+
+    fn device(ip_address: &str) -> Device {
+        let now = Utc::now();
+        Device {
+            mac_address: "aa:bb:cc:dd:ee:01".parse().unwrap(),
+            last_known_ip: ip_address.parse().unwrap(),
+            display_name: ip_address.to_string(),
+            is_name_custom: false,
+            notes: String::new(),
+            is_online: true,
+            last_seen: now,
+            last_scanned: now,
+            other_ips: Vec::new(),
+        }
+    }
+
+    fn sorted(mut leases: Vec<Lease>) -> Vec<Lease> {
+        leases.sort_by_key(|lease| lease.ip_address);
+        leases
+    }
+
+    #[test]
+    fn dnsmasq_leases_are_parsed() {
+        let content = "\
+1735689600 aa:bb:cc:dd:ee:01 192.168.1.10 laptop 01:aa:bb:cc:dd:ee:01
+0 aa:bb:cc:dd:ee:02 192.168.1.11 * *
+duid 00:01:00:01:2c:5e:8f:1a:aa:bb:cc:dd:ee:01
+1735689600 43981 fd00::10 nas 00:01:00:01:2c:5e:8f:1a:aa:bb:cc:dd:ee:03
+1735689600 not-a-mac 192.168.1.12 broken *
+";
+
+        let leases = sorted(parse_dnsmasq_leases(content, chrono::Duration::hours(12)));
+
+        assert_eq!(leases.len(), 2);
+        assert_eq!(leases[0].mac_address, "aa:bb:cc:dd:ee:01".parse().unwrap());
+        assert_eq!(
+            leases[0].ip_address,
+            "192.168.1.10".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(leases[0].hostname.as_deref(), Some("laptop"));
+        assert_eq!(leases[0].expires_at, Some(timestamp(1735689600)));
+        assert_eq!(
+            leases[0].renewed_at,
+            Some(timestamp(1735689600 - 12 * 3600))
+        );
+        // Infinite lease of a client without a hostname
+        assert_eq!(leases[1].hostname, None);
+        assert_eq!(leases[1].expires_at, None);
+        assert_eq!(leases[1].renewed_at, None);
+    }
+
+    #[test]
+    fn kea_v4_leases_keep_the_last_line_of_each_address() {
+        let content = "\
+address,hwaddr,client_id,valid_lifetime,expire,subnet_id,fqdn_fwd,fqdn_rev,hostname,state,user_context,pool_id
+192.168.1.20,aa:bb:cc:dd:ee:03,,3600,1735689600,1,0,0,phone.,0,,0
+192.168.1.21,aa:bb:cc:dd:ee:04,,3600,1735689600,1,0,0,tv,0,,0
+192.168.1.21,aa:bb:cc:dd:ee:04,,3600,1735693200,1,0,0,tv,0,,0
+192.168.1.22,aa:bb:cc:dd:ee:05,,3600,1735689600,1,0,0,old,0,,0
+192.168.1.22,,,0,1735689600,1,0,0,,2,,0
+192.168.1.23,aa:bb:cc:dd:ee:06,,3600,1735689600,1,0,0,living&#x2croom,0,,0
+192.168.1.24,aa:bb:cc:dd:ee:07,,3600,1735689600,1,0,0,,0,,0
+";
+
+        let leases = sorted(parse_kea_leases(content));
+
+        assert_eq!(leases.len(), 4);
+        assert_eq!(leases[0].mac_address, "aa:bb:cc:dd:ee:03".parse().unwrap());
+        assert_eq!(leases[0].hostname.as_deref(), Some("phone"));
+        assert_eq!(leases[0].expires_at, Some(timestamp(1735689600)));
+        assert_eq!(leases[0].renewed_at, Some(timestamp(1735689600 - 3600)));
+        // Renewed lease
+        assert_eq!(leases[1].expires_at, Some(timestamp(1735693200)));
+        // The reclaimed lease of 192.168.1.22 is gone
+        assert_eq!(
+            leases[2].ip_address,
+            "192.168.1.23".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(leases[2].hostname.as_deref(), Some("living,room"));
+        assert_eq!(leases[3].hostname, None);
+    }
+
+    #[test]
+    fn kea_v6_leases_are_parsed_by_column_name() {
+        let content = "\
+address,duid,valid_lifetime,expire,subnet_id,pref_lifetime,lease_type,iaid,prefix_len,fqdn_fwd,fqdn_rev,hostname,hwaddr,state,user_context,hwtype,hwaddr_source,pool_id
+fd00::20,00:01:00:01:2c:5e:8f:1a:aa:bb:cc:dd:ee:08,7200,1735689600,1,3600,0,1,128,0,0,desktop,aa:bb:cc:dd:ee:08,0,,1,4,0
+fd00::21,00:01:00:01:2c:5e:8f:1a:aa:bb:cc:dd:ee:09,7200,1735689600,1,3600,0,1,128,0,0,printer,,0,,1,4,0
+";
+
+        let leases = parse_kea_leases(content);
+
+        // Leases without a hardware address cannot be matched to a device
+        assert_eq!(leases.len(), 1);
+        assert_eq!(leases[0].mac_address, "aa:bb:cc:dd:ee:08".parse().unwrap());
+        assert_eq!(leases[0].ip_address, "fd00::20".parse::<IpAddr>().unwrap());
+        assert_eq!(leases[0].renewed_at, Some(timestamp(1735689600 - 7200)));
+    }
+
+    #[test]
+    fn kea_leases_require_the_address_columns() {
+        assert!(parse_kea_leases("").is_empty());
+        assert!(parse_kea_leases("address,expire\n192.168.1.20,1735689600\n").is_empty());
+    }
+
+    #[test]
+    fn dual_stack_leases_are_merged_by_family() {
+        let now = Utc::now();
+        let leases = latest_leases(vec![
+            lease("192.168.1.9", Some("old"), now - chrono::Duration::hours(1)),
+            lease(
+                "192.168.1.10",
+                Some("laptop"),
+                now + chrono::Duration::hours(1),
+            ),
+            // Outlives the IPv4 lease, which must not hide it
+            lease("fd00::10", None, now + chrono::Duration::hours(2)),
+        ]);
+        assert_eq!(leases.len(), 2);
+
+        let apply_leases = |device: &mut Device| {
+            for family in [Family::V6, Family::V4] {
+                apply_lease(device, &leases[&(device.mac_address, family)], now);
+            }
+        };
+
+        // Seen by the router over IPv4
+        let mut dual_stack = device("192.168.1.10");
+        apply_leases(&mut dual_stack);
+        assert_eq!(
+            dual_stack.last_known_ip,
+            "192.168.1.10".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(
+            dual_stack.other_ips,
+            ["fd00::10".parse::<IpAddr>().unwrap()]
+        );
+        assert_eq!(dual_stack.display_name, "laptop");
+
+        // Seen by the router over IPv6 only, the IPv4 lease becomes the main address
+        let mut ipv6_only = device("fd00::10");
+        apply_leases(&mut ipv6_only);
+        assert_eq!(
+            ipv6_only.last_known_ip,
+            "192.168.1.10".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(ipv6_only.other_ips, ["fd00::10".parse::<IpAddr>().unwrap()]);
+
+        // Stale address of the same family, replaced by the lease
+        let mut stale = device("192.168.1.50");
+        apply_leases(&mut stale);
+        assert_eq!(
+            stale.last_known_ip,
+            "192.168.1.10".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(stale.other_ips, ["fd00::10".parse::<IpAddr>().unwrap()]);
+    }
+}
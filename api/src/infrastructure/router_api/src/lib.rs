@@ -1 +1,151 @@
+use std::{sync::Arc, time::Duration};
+
+use common::{RouterApiConfig, RouterKind, SnmpVersion};
+use ports::api::{RouterApi, RouterApiError, RouterApiResult};
+use reqwest::Url;
+use tracing::error;
+
+use crate::{
+    bouygues::BboxRouterApi,
+    composite::{CompositeMember, CompositeRouterApi},
+    discovery::DiscoveryRouterApi,
+    fritzbox::FritzboxRouterApi,
+    leases::{LeaseDatabase, LeaseEnrichedRouterApi, LeasesRouterApi},
+    openwrt::OpenWrtRouterApi,
+    resilient::ResilientRouterApi,
+    simulated::SimulatedRouterApi,
+    snmp::{SnmpCredentials, SnmpRouterApi},
+};
+
+mod bandwidth;
 pub mod bouygues;
+pub mod composite;
+pub mod discovery;
+pub mod fritzbox;
+pub mod leases;
+mod neighbours;
+pub mod openwrt;
+pub mod resilient;
+pub mod simulated;
+pub mod snmp;
+
+/// Instantiates the router API backend selected by the configuration, behind the cache and the
+/// circuit breaker.
+pub async fn from_config(config: &RouterApiConfig) -> RouterApiResult<Arc<dyn RouterApi>> {
+    Ok(Arc::new(ResilientRouterApi::new(
+        build(config).await?,
+        &config.resilience,
+    )))
+}
+
+async fn build(config: &RouterApiConfig) -> RouterApiResult<Arc<dyn RouterApi>> {
+    let router_api: Arc<dyn RouterApi> = match config.kind {
+        RouterKind::Bbox => {
+            Arc::new(BboxRouterApi::new(base_url(config)?, config.password.clone()).await?)
+        }
+        RouterKind::OpenWrt => Arc::new(
+            OpenWrtRouterApi::new(
+                base_url(config)?,
+                config.openwrt.username.clone(),
+                config.password.clone(),
+                config.openwrt.wan_interface.clone(),
+                config.openwrt.wan6_interface.clone(),
+                chrono::Duration::seconds(config.openwrt.lease_time),
+            )
+            .await?,
+        ),
+        RouterKind::Fritzbox => Arc::new(
+            FritzboxRouterApi::new(
+                base_url(config)?,
+                config.fritzbox.username.clone(),
+                config.password.clone(),
+            )
+            .await?,
+        ),
+        RouterKind::Snmp => {
+            let base_url = base_url(config)?;
+            let host = base_url.host_str().ok_or_else(|| {
+                RouterApiError::Unknown("The router API base URL has no host".to_string())
+            })?;
+
+            Arc::new(
+                SnmpRouterApi::new(
+                    format!("{host}:{}", base_url.port().unwrap_or(161)),
+                    match config.snmp.version {
+                        SnmpVersion::V2c => SnmpCredentials::V2c {
+                            community: config.snmp.community.clone(),
+                        },
+                        SnmpVersion::V3 => SnmpCredentials::V3 {
+                            username: config.snmp.username.clone(),
+                            auth_protocol: config.snmp.auth_protocol,
+                            auth_password: config.password.clone(),
+                            privacy_protocol: config.snmp.privacy_protocol,
+                            privacy_password: config.snmp.privacy_password.clone(),
+                        },
+                    },
+                    config.snmp.wan_if_index,
+                    Duration::from_secs(config.snmp.timeout),
+                )
+                .await?,
+            )
+        }
+        RouterKind::Discovery => Arc::new(DiscoveryRouterApi::new(
+            config.discovery.neighbour_table.clone(),
+            config.discovery.subnets.to_vec(),
+            config.discovery.sweep_method,
+            Duration::from_millis(config.discovery.sweep_timeout),
+            config.discovery.sweep_concurrency,
+            config.discovery.resolve_hostnames,
+        )?),
+        RouterKind::Leases => Arc::new(LeasesRouterApi::new(lease_database(config))),
+        RouterKind::Simulated => Arc::new(
+            SimulatedRouterApi::new(config.simulated.scenario.clone(), config.simulated.seed)
+                .await?,
+        ),
+        RouterKind::Composite => {
+            // A secondary member down at startup must not prevent the others from being used,
+            // but the primary one provides the WAN information and the router settings
+            let mut members = Vec::new();
+            for (index, member) in config.composite.members.iter().enumerate() {
+                match Box::pin(build(&member.config)).await {
+                    Ok(router_api) => members.push(CompositeMember {
+                        name: member.name.clone(),
+                        router_api,
+                    }),
+                    Err(err) if index == 0 => return Err(err),
+                    Err(err) => {
+                        error!(
+                            member = member.name,
+                            "Failed to build router API member: {}", err
+                        )
+                    }
+                }
+            }
+
+            Arc::new(CompositeRouterApi::new(members)?)
+        }
+    };
+
+    Ok(match config.kind {
+        RouterKind::Leases => router_api,
+        _ if config.leases.files.is_empty() => router_api,
+        _ => Arc::new(LeaseEnrichedRouterApi::new(
+            router_api,
+            lease_database(config),
+        )),
+    })
+}
+
+fn lease_database(config: &RouterApiConfig) -> LeaseDatabase {
+    LeaseDatabase::new(
+        config.leases.files.to_vec(),
+        chrono::Duration::seconds(config.leases.lease_time),
+    )
+}
+
+/// Returns the router API base URL, which every backend talking to a router requires.
+fn base_url(config: &RouterApiConfig) -> RouterApiResult<Url> {
+    config.base_url.clone().ok_or_else(|| {
+        RouterApiError::Unknown("The router API base URL is not configured".to_string())
+    })
+}
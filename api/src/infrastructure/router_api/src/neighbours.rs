@@ -0,0 +1,35 @@
+use std::net::IpAddr;
+
+use mac_address::MacAddress;
+
+/// A complete entry of the kernel neighbour table (`/proc/net/arp`).
+pub(crate) struct NeighbourEntry {
+    pub(crate) ip_address: IpAddr,
+    pub(crate) mac_address: MacAddress,
+}
+
+pub(crate) fn parse_proc_net_arp(content: &str) -> Vec<NeighbourEntry> {
+    const ATF_COM: u32 = 0x2;
+
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+            if columns.len() < 4 {
+                return None;
+            }
+
+            let flags = u32::from_str_radix(columns[2].trim_start_matches("0x"), 16).ok()?;
+            let mac_address: MacAddress = columns[3].parse().ok()?;
+            if flags & ATF_COM == 0 || mac_address.bytes() == [0; 6] {
+                return None;
+            }
+
+            Some(NeighbourEntry {
+                ip_address: columns[0].parse().ok()?,
+                mac_address,
+            })
+        })
+        .collect()
+}
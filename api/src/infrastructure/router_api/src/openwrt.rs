@@ -0,0 +1,507 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr},
+};
+
+use entities::{Device, WanConnectivity, WanStats, WanStatsItem, WanStatus};
+use mac_address::MacAddress;
+use ports::api::{RouterApi, RouterApiError, RouterApiResult};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::json;
+use thiserror::Error;
+use tokio::sync::RwLock;
+use tracing::{error, instrument, warn};
+
+use crate::{bandwidth::BandwidthMeter, neighbours::parse_proc_net_arp};
+
+/// Session identifier used by ubus for unauthenticated calls (only `session.login` is allowed).
+const ANONYMOUS_SESSION: &str = "00000000000000000000000000000000";
+
+/// JSON-RPC error code returned by rpcd when the session is unknown or has expired.
+const ACCESS_DENIED: i64 = -32002;
+
+#[derive(Error, Debug)]
+enum OpenWrtRouterApiError {
+    #[error("Request failed: {0}")]
+    RequestFailed(#[from] reqwest::Error),
+
+    #[error("API returned unexpected status: {0}")]
+    UnexpectedStatus(StatusCode),
+
+    #[error("Failed to parse response body: {0}")]
+    ParseError(#[from] serde_json::Error),
+
+    #[error("Missing required field: {0}")]
+    MissingField(String),
+
+    #[error("The ubus session is invalid or has expired")]
+    SessionExpired,
+
+    #[error("JSON-RPC error {code}: {message}")]
+    RpcError { code: i64, message: String },
+
+    #[error("ubus call {0} failed with status {1}")]
+    UbusStatus(String, i64),
+}
+
+impl From<OpenWrtRouterApiError> for RouterApiError {
+    fn from(err: OpenWrtRouterApiError) -> Self {
+        error!(
+            error = err.to_string(),
+            "An error occurred while using the router API"
+        );
+        match err {
+            OpenWrtRouterApiError::RequestFailed(_) => RouterApiError::Unavailable,
+            OpenWrtRouterApiError::UnexpectedStatus(status)
+                if status == StatusCode::UNAUTHORIZED =>
+            {
+                RouterApiError::AuthenticationFailed
+            }
+            OpenWrtRouterApiError::SessionExpired => RouterApiError::AuthenticationFailed,
+            OpenWrtRouterApiError::UnexpectedStatus(_)
+            | OpenWrtRouterApiError::ParseError(_)
+            | OpenWrtRouterApiError::MissingField(_)
+            | OpenWrtRouterApiError::RpcError { .. }
+            | OpenWrtRouterApiError::UbusStatus(..) => {
+                RouterApiError::InvalidResponse(err.to_string())
+            }
+        }
+    }
+}
+
+pub struct OpenWrtRouterApi {
+    client: reqwest::Client,
+    endpoint: Url,
+    username: String,
+    password: String,
+    wan_interface: String,
+    wan6_interface: String,
+    lease_time: chrono::Duration,
+    session: RwLock<String>,
+    bandwidth_meter: BandwidthMeter,
+}
+
+#[derive(Deserialize)]
+struct UbusResponse {
+    result: Option<Vec<serde_json::Value>>,
+    error: Option<UbusRpcError>,
+}
+
+#[derive(Deserialize)]
+struct UbusRpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct OpenWrtSession {
+    ubus_rpc_session: String,
+}
+
+#[derive(Deserialize)]
+struct OpenWrtDhcpLeases {
+    #[serde(default)]
+    dhcp_leases: Vec<OpenWrtDhcpLease>,
+}
+
+#[derive(Deserialize)]
+struct OpenWrtDhcpLease {
+    macaddr: String,
+    ipaddr: IpAddr,
+    #[serde(default)]
+    hostname: Option<String>,
+    /// Remaining lease time in seconds, `false` for static leases.
+    #[serde(default)]
+    expires: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct OpenWrtFile {
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct OpenWrtAddress {
+    address: IpAddr,
+}
+
+#[derive(Deserialize)]
+struct OpenWrtRoute {
+    target: String,
+    nexthop: IpAddr,
+}
+
+#[derive(Deserialize)]
+struct OpenWrtInterfaceStatus {
+    up: bool,
+    #[serde(default)]
+    uptime: i64,
+    l3_device: Option<String>,
+    #[serde(rename = "ipv4-address", default)]
+    ipv4_address: Vec<OpenWrtAddress>,
+    #[serde(rename = "ipv6-address", default)]
+    ipv6_address: Vec<OpenWrtAddress>,
+    #[serde(default)]
+    route: Vec<OpenWrtRoute>,
+}
+
+#[derive(Deserialize)]
+struct OpenWrtDeviceStatistics {
+    rx_bytes: u64,
+    tx_bytes: u64,
+    #[serde(default)]
+    rx_errors: u64,
+    #[serde(default)]
+    tx_errors: u64,
+    #[serde(default)]
+    rx_dropped: u64,
+    #[serde(default)]
+    tx_dropped: u64,
+}
+
+#[derive(Deserialize)]
+struct OpenWrtDeviceStatus {
+    statistics: OpenWrtDeviceStatistics,
+    /// Negotiated link speed, e.g. `1000F` for 1 Gbps full duplex.
+    speed: Option<String>,
+}
+
+/// Parses a link speed as reported by netifd (`1000F`, `100H`, ...) into kbps.
+fn parse_link_speed(speed: Option<&str>) -> usize {
+    speed
+        .map(|speed| speed.trim_end_matches(['F', 'H']))
+        .and_then(|speed| speed.parse::<usize>().ok())
+        .map(|mbps| mbps * 1000)
+        .unwrap_or(0)
+}
+
+impl OpenWrtRouterApi {
+    pub async fn new(
+        base_url: Url,
+        username: String,
+        password: String,
+        wan_interface: String,
+        wan6_interface: String,
+        lease_time: chrono::Duration,
+    ) -> Result<Self, RouterApiError> {
+        let api = Self {
+            client: reqwest::Client::new(),
+            endpoint: base_url.join("/ubus").unwrap(),
+            username,
+            password,
+            wan_interface,
+            wan6_interface,
+            lease_time,
+            session: RwLock::new(ANONYMOUS_SESSION.to_string()),
+            bandwidth_meter: BandwidthMeter::new(),
+        };
+
+        api.authenticate().await?;
+
+        Ok(api)
+    }
+
+    #[instrument(skip(self))]
+    async fn authenticate(&self) -> Result<(), OpenWrtRouterApiError> {
+        let session = self
+            .call_once::<OpenWrtSession>(
+                ANONYMOUS_SESSION,
+                "session",
+                "login",
+                &json!({
+                    "username": self.username,
+                    "password": self.password,
+                }),
+            )
+            .await
+            .map_err(|err| match err {
+                // rpcd answers a login with bad credentials with an access denied status
+                OpenWrtRouterApiError::UbusStatus(_, 6) => {
+                    OpenWrtRouterApiError::UnexpectedStatus(StatusCode::UNAUTHORIZED)
+                }
+                err => err,
+            })?
+            .ok_or(OpenWrtRouterApiError::MissingField(
+                "ubus_rpc_session".to_string(),
+            ))?;
+
+        *self.session.write().await = session.ubus_rpc_session;
+
+        Ok(())
+    }
+
+    /// Performs a single ubus call through the JSON-RPC endpoint, returning the data attached to
+    /// the reply if any.
+    async fn call_once<T: DeserializeOwned>(
+        &self,
+        session: &str,
+        object: &str,
+        method: &str,
+        args: &serde_json::Value,
+    ) -> Result<Option<T>, OpenWrtRouterApiError> {
+        let response = self
+            .client
+            .post(self.endpoint.clone())
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "call",
+                "params": [session, object, method, args],
+            }))
+            .send()
+            .await?;
+
+        if response.status() != StatusCode::OK {
+            return Err(OpenWrtRouterApiError::UnexpectedStatus(response.status()));
+        }
+
+        let response = response.json::<UbusResponse>().await?;
+
+        if let Some(error) = response.error {
+            return Err(if error.code == ACCESS_DENIED {
+                OpenWrtRouterApiError::SessionExpired
+            } else {
+                OpenWrtRouterApiError::RpcError {
+                    code: error.code,
+                    message: error.message,
+                }
+            });
+        }
+
+        let mut result = response
+            .result
+            .ok_or(OpenWrtRouterApiError::MissingField("result".to_string()))?
+            .into_iter();
+
+        match result.next().and_then(|status| status.as_i64()) {
+            Some(0) => (),
+            Some(status) => {
+                return Err(OpenWrtRouterApiError::UbusStatus(
+                    format!("{}.{}", object, method),
+                    status,
+                ));
+            }
+            None => return Err(OpenWrtRouterApiError::MissingField("status".to_string())),
+        }
+
+        Ok(result.next().map(serde_json::from_value).transpose()?)
+    }
+
+    /// Calls a ubus method with the current session, logging in again if it has expired.
+    async fn call<T: DeserializeOwned>(
+        &self,
+        object: &str,
+        method: &str,
+        args: serde_json::Value,
+    ) -> Result<T, OpenWrtRouterApiError> {
+        let session = self.session.read().await.clone();
+        let result = match self.call_once(&session, object, method, &args).await {
+            Err(OpenWrtRouterApiError::SessionExpired) => {
+                self.authenticate().await?;
+                let session = self.session.read().await.clone();
+                self.call_once(&session, object, method, &args).await
+            }
+            result => result,
+        }?;
+
+        result.ok_or(OpenWrtRouterApiError::MissingField(format!(
+            "{}.{}",
+            object, method
+        )))
+    }
+
+    async fn read_file(&self, path: &str) -> Result<String, OpenWrtRouterApiError> {
+        Ok(self
+            .call::<OpenWrtFile>("file", "read", json!({ "path": path }))
+            .await?
+            .data)
+    }
+
+    async fn interface_status(
+        &self,
+        interface: &str,
+    ) -> Result<OpenWrtInterfaceStatus, OpenWrtRouterApiError> {
+        self.call(
+            &format!("network.interface.{}", interface),
+            "status",
+            json!({}),
+        )
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl RouterApi for OpenWrtRouterApi {
+    #[instrument(skip(self))]
+    async fn wan_connectivity(&self) -> RouterApiResult<WanConnectivity> {
+        let wan = self.interface_status(&self.wan_interface).await?;
+
+        // IPv6 is usually configured on a separate logical interface, which may not exist
+        let ipv6 = match self.interface_status(&self.wan6_interface).await {
+            Ok(wan6) => wan6.ipv6_address,
+            Err(err) => {
+                warn!(
+                    error = err.to_string(),
+                    "Failed to fetch the IPv6 WAN interface"
+                );
+                Vec::new()
+            }
+        };
+
+        let ipv4 = wan
+            .ipv4_address
+            .iter()
+            .find_map(|address| match address.address {
+                IpAddr::V4(ip) => Some(ip),
+                IpAddr::V6(_) => None,
+            })
+            .unwrap_or(Ipv4Addr::UNSPECIFIED);
+
+        let gateway = wan
+            .route
+            .iter()
+            .find(|route| route.target == "0.0.0.0")
+            .map(|route| route.nexthop)
+            .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+        Ok(WanConnectivity {
+            ipv4,
+            ipv6: wan
+                .ipv6_address
+                .iter()
+                .chain(ipv6.iter())
+                .find_map(|address| match address.address {
+                    IpAddr::V6(ip) => Some(ip),
+                    IpAddr::V4(_) => None,
+                }),
+            gateway,
+            status: if wan.up {
+                WanStatus::Up
+            } else {
+                WanStatus::Down
+            },
+            uptime: Some(chrono::Duration::seconds(wan.uptime)),
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn list_devices(&self) -> RouterApiResult<Vec<Device>> {
+        let leases = self
+            .call::<OpenWrtDhcpLeases>("luci-rpc", "getDHCPLeases", json!({}))
+            .await?
+            .dhcp_leases;
+
+        let neighbours = parse_proc_net_arp(&self.read_file("/proc/net/arp").await?);
+
+        let now = chrono::Utc::now();
+        let mut devices = HashMap::new();
+
+        for lease in leases {
+            let Ok(mac_address) = lease.macaddr.parse::<MacAddress>() else {
+                warn!(
+                    mac_address = lease.macaddr,
+                    "Ignoring lease with invalid MAC address"
+                );
+                continue;
+            };
+
+            // The lease was last renewed `lease_time - expires` seconds ago, which is the last
+            // time we know for sure that the device was on the network.
+            let last_seen = match lease.expires.as_i64() {
+                Some(expires) => {
+                    now - (self.lease_time - chrono::Duration::seconds(expires))
+                        .max(chrono::Duration::zero())
+                }
+                None => now,
+            };
+
+            devices.insert(
+                mac_address,
+                Device {
+                    mac_address,
+                    last_known_ip: lease.ipaddr,
+                    display_name: lease
+                        .hostname
+                        .filter(|hostname| !hostname.is_empty())
+                        .unwrap_or_else(|| lease.ipaddr.to_string()),
+                    is_name_custom: false,
+                    notes: String::new(),
+                    is_online: false,
+                    last_seen,
+                    last_scanned: now,
+                    other_ips: Vec::new(),
+                },
+            );
+        }
+
+        for neighbour in neighbours {
+            let device = devices
+                .entry(neighbour.mac_address)
+                .or_insert_with(|| Device {
+                    mac_address: neighbour.mac_address,
+                    last_known_ip: neighbour.ip_address,
+                    display_name: neighbour.ip_address.to_string(),
+                    is_name_custom: false,
+                    notes: String::new(),
+                    is_online: true,
+                    last_seen: now,
+                    last_scanned: now,
+                    other_ips: Vec::new(),
+                });
+
+            device.last_known_ip = neighbour.ip_address;
+            device.is_online = true;
+            device.last_seen = now;
+        }
+
+        if devices.is_empty() {
+            warn!("Router API returned an empty list of devices. This should never happen!");
+        }
+
+        Ok(devices.into_values().collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn wan_stats(&self) -> RouterApiResult<WanStats> {
+        let wan = self.interface_status(&self.wan_interface).await?;
+        let l3_device = wan.l3_device.ok_or(OpenWrtRouterApiError::MissingField(
+            "wan.l3_device".to_string(),
+        ))?;
+
+        let device = self
+            .call::<OpenWrtDeviceStatus>("network.device", "status", json!({ "name": l3_device }))
+            .await?;
+
+        let active_sessions = self
+            .read_file("/proc/sys/net/netfilter/nf_conntrack_count")
+            .await?
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| OpenWrtRouterApiError::MissingField("nf_conntrack_count".to_string()))?;
+
+        let statistics = device.statistics;
+        let (download_bandwidth, upload_bandwidth) = self
+            .bandwidth_meter
+            .sample(statistics.rx_bytes, statistics.tx_bytes)
+            .await;
+
+        let max_bandwidth = parse_link_speed(device.speed.as_deref());
+
+        Ok(WanStats {
+            download: WanStatsItem {
+                max_bandwidth,
+                current_bandwidth: download_bandwidth,
+                total_since_last_reboot: statistics.rx_bytes as usize,
+                packets_lost: (statistics.rx_dropped + statistics.rx_errors) as usize,
+            },
+            upload: WanStatsItem {
+                max_bandwidth,
+                current_bandwidth: upload_bandwidth,
+                total_since_last_reboot: statistics.tx_bytes as usize,
+                packets_lost: (statistics.tx_dropped + statistics.tx_errors) as usize,
+            },
+            active_sessions,
+        })
+    }
+}
@@ -0,0 +1,634 @@
+use std::{
+    future::Future,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use common::ResilienceConfig;
+use entities::{
+    AccessSlot, Device, DeviceAccess, DeviceConnection, DeviceTrafficCounters, GuestNetwork,
+    PortForward, PortForwardRule, RouterInfo, StaticLease, WanConnectivity, WanStats, WifiRadio,
+};
+use futures::future::{BoxFuture, FutureExt, Shared};
+use ipnetwork::IpNetwork;
+use mac_address::MacAddress;
+use ports::api::{RouterApi, RouterApiError, RouterApiResult};
+use tokio::time::Instant;
+use tracing::{debug, info, instrument, warn};
+
+/// Result of a read method, and its pending call shared by every concurrent caller.
+struct CachedCall<T> {
+    ttl: Duration,
+    state: Mutex<CachedCallState<T>>,
+}
+
+struct CachedCallState<T> {
+    value: Option<(Instant, T)>,
+    pending: Option<Shared<BoxFuture<'static, RouterApiResult<T>>>>,
+}
+
+impl<T: Clone + Send + Sync + 'static> CachedCall<T> {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            state: Mutex::new(CachedCallState {
+                value: None,
+                pending: None,
+            }),
+        }
+    }
+
+    /// Returns the cached value while it is fresh, otherwise joins the pending call or starts one.
+    async fn get(
+        &self,
+        call: impl FnOnce() -> BoxFuture<'static, RouterApiResult<T>>,
+    ) -> RouterApiResult<T> {
+        let pending = {
+            let mut state = self.state.lock().unwrap();
+            if let Some((fetched_at, value)) = &state.value
+                && fetched_at.elapsed() < self.ttl
+            {
+                return Ok(value.clone());
+            }
+
+            state.pending.get_or_insert_with(|| call().shared()).clone()
+        };
+
+        let result = pending.clone().await;
+
+        // The first caller to finish stores the result, unless the cache was invalidated meanwhile
+        let mut state = self.state.lock().unwrap();
+        if state
+            .pending
+            .as_ref()
+            .is_some_and(|current| current.ptr_eq(&pending))
+        {
+            state.pending = None;
+            if let Ok(value) = &result
+                && !self.ttl.is_zero()
+            {
+                state.value = Some((Instant::now(), value.clone()));
+            }
+        }
+
+        result
+    }
+
+    fn invalidate(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.value = None;
+        state.pending = None;
+    }
+}
+
+enum CircuitState {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen { until: Instant }, // a single call probes the router, another may once it expired
+}
+
+struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<CircuitState>,
+}
+
+impl CircuitBreaker {
+    /// Returns whether a call may reach the router.
+    fn acquire(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        match *state {
+            CircuitState::Closed { .. } => true,
+            // The probe may be dropped before it records its result (e.g. the client went away)
+            CircuitState::Open { until } | CircuitState::HalfOpen { until } if until <= now => {
+                *state = CircuitState::HalfOpen {
+                    until: now + self.cooldown,
+                };
+                true
+            }
+            CircuitState::Open { .. } | CircuitState::HalfOpen { .. } => false,
+        }
+    }
+
+    /// Only an unavailable router counts as a failure, other errors come from the call itself.
+    fn record<T>(&self, result: &RouterApiResult<T>) {
+        let is_failure = matches!(result, Err(RouterApiError::Unavailable));
+        let mut state = self.state.lock().unwrap();
+
+        *state = match (&*state, is_failure) {
+            (CircuitState::Closed { .. }, false) => CircuitState::Closed { failures: 0 },
+            (_, false) => {
+                info!("The router API answered again, circuit closed");
+                CircuitState::Closed { failures: 0 }
+            }
+            (CircuitState::Closed { failures }, true)
+                if self.threshold == 0 || failures + 1 < self.threshold =>
+            {
+                CircuitState::Closed {
+                    failures: failures + 1,
+                }
+            }
+            (CircuitState::Open { until }, true) => CircuitState::Open { until: *until },
+            (CircuitState::Closed { .. } | CircuitState::HalfOpen { .. }, true) => {
+                warn!(
+                    "The router API is unavailable, calls fail fast for {}s",
+                    self.cooldown.as_secs()
+                );
+                CircuitState::Open {
+                    until: Instant::now() + self.cooldown,
+                }
+            }
+        };
+    }
+}
+
+/// Retries and circuit breaking, shared with the calls running in the background of the cache.
+struct Resilience {
+    breaker: CircuitBreaker,
+    retry_attempts: u32,
+    retry_backoff: Duration,
+}
+
+impl Resilience {
+    /// Sends a single call, failing fast while the circuit is open.
+    async fn guard<T>(&self, call: impl Future<Output = RouterApiResult<T>>) -> RouterApiResult<T> {
+        if !self.breaker.acquire() {
+            return Err(RouterApiError::Unavailable);
+        }
+
+        let result = call.await;
+        self.breaker.record(&result);
+        result
+    }
+
+    /// Sends a call until the router is available, with an exponential backoff. Only reads are
+    /// retried, as a write may have been applied before the router failed to respond.
+    ///
+    /// The circuit breaker counts the call once whatever its number of attempts, so that a single
+    /// call cannot open the circuit on its own.
+    async fn retry<T, F, Fut>(&self, call: F) -> RouterApiResult<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = RouterApiResult<T>>,
+    {
+        self.guard(async {
+            let mut backoff = self.retry_backoff;
+            let mut attempt = 1;
+
+            loop {
+                match call().await {
+                    Err(RouterApiError::Unavailable) if attempt < self.retry_attempts => {
+                        debug!(
+                            attempt,
+                            "The router API is unavailable, retrying in {}ms",
+                            backoff.as_millis()
+                        );
+                        tokio::time::sleep(backoff).await;
+                        backoff *= 2;
+                        attempt += 1;
+                    }
+                    result => return result,
+                }
+            }
+        })
+        .await
+    }
+}
+
+/// Shields the router from bursts of identical calls, and the API from a failing router. Reads
+/// are cached and coalesced, writes invalidate every cached read.
+pub struct ResilientRouterApi {
+    inner: Arc<dyn RouterApi>,
+    resilience: Arc<Resilience>,
+    wan_connectivity: CachedCall<WanConnectivity>,
+    list_devices: CachedCall<Vec<Device>>,
+    wan_stats: CachedCall<WanStats>,
+    list_port_forwards: CachedCall<Vec<PortForward>>,
+    lan_network: CachedCall<IpNetwork>,
+    list_static_leases: CachedCall<Vec<StaticLease>>,
+    list_device_access: CachedCall<Vec<DeviceAccess>>,
+    list_wifi_radios: CachedCall<Vec<WifiRadio>>,
+    list_device_connections: CachedCall<Vec<DeviceConnection>>,
+    list_device_traffic: CachedCall<Vec<DeviceTrafficCounters>>,
+    guest_network: CachedCall<GuestNetwork>,
+    router_info: CachedCall<RouterInfo>,
+}
+
+/// Reads through the cache of the method, retrying the call to the inner router API.
+macro_rules! cached_read {
+    ($self:ident, $method:ident) => {{
+        let inner = $self.inner.clone();
+        let resilience = $self.resilience.clone();
+
+        $self
+            .$method
+            .get(move || async move { resilience.retry(|| inner.$method()).await }.boxed())
+            .await
+    }};
+}
+
+impl ResilientRouterApi {
+    pub fn new(inner: Arc<dyn RouterApi>, config: &ResilienceConfig) -> Self {
+        let ttl = |method: &str| {
+            Duration::from_secs(
+                config
+                    .cache_ttls
+                    .iter()
+                    .find(|cache_ttl| cache_ttl.method == method)
+                    .map_or(0, |cache_ttl| cache_ttl.ttl),
+            )
+        };
+
+        Self {
+            inner,
+            resilience: Arc::new(Resilience {
+                breaker: CircuitBreaker {
+                    threshold: config.breaker_threshold,
+                    cooldown: Duration::from_secs(config.breaker_cooldown),
+                    state: Mutex::new(CircuitState::Closed { failures: 0 }),
+                },
+                retry_attempts: config.retry_attempts,
+                retry_backoff: Duration::from_millis(config.retry_backoff),
+            }),
+            wan_connectivity: CachedCall::new(ttl("wan_connectivity")),
+            list_devices: CachedCall::new(ttl("list_devices")),
+            wan_stats: CachedCall::new(ttl("wan_stats")),
+            list_port_forwards: CachedCall::new(ttl("list_port_forwards")),
+            lan_network: CachedCall::new(ttl("lan_network")),
+            list_static_leases: CachedCall::new(ttl("list_static_leases")),
+            list_device_access: CachedCall::new(ttl("list_device_access")),
+            list_wifi_radios: CachedCall::new(ttl("list_wifi_radios")),
+            list_device_connections: CachedCall::new(ttl("list_device_connections")),
+            list_device_traffic: CachedCall::new(ttl("list_device_traffic")),
+            guest_network: CachedCall::new(ttl("guest_network")),
+            router_info: CachedCall::new(ttl("router_info")),
+        }
+    }
+
+    /// Sends a write, the reads cached before it may no longer be accurate.
+    async fn write<T>(&self, call: impl Future<Output = RouterApiResult<T>>) -> RouterApiResult<T> {
+        let result = self.resilience.guard(call).await;
+
+        self.wan_connectivity.invalidate();
+        self.list_devices.invalidate();
+        self.wan_stats.invalidate();
+        self.list_port_forwards.invalidate();
+        self.lan_network.invalidate();
+        self.list_static_leases.invalidate();
+        self.list_device_access.invalidate();
+        self.list_wifi_radios.invalidate();
+        self.list_device_connections.invalidate();
+        self.list_device_traffic.invalidate();
+        self.guest_network.invalidate();
+        self.router_info.invalidate();
+
+        result
+    }
+}
+
+#[async_trait::async_trait]
+impl RouterApi for ResilientRouterApi {
+    #[instrument(skip(self))]
+    async fn wan_connectivity(&self) -> RouterApiResult<WanConnectivity> {
+        cached_read!(self, wan_connectivity)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_devices(&self) -> RouterApiResult<Vec<Device>> {
+        cached_read!(self, list_devices)
+    }
+
+    #[instrument(skip(self))]
+    async fn wan_stats(&self) -> RouterApiResult<WanStats> {
+        cached_read!(self, wan_stats)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_port_forwards(&self) -> RouterApiResult<Vec<PortForward>> {
+        cached_read!(self, list_port_forwards)
+    }
+
+    #[instrument(skip(self))]
+    async fn create_port_forward(&self, rule: PortForwardRule) -> RouterApiResult<PortForward> {
+        self.write(self.inner.create_port_forward(rule)).await
+    }
+
+    #[instrument(skip(self))]
+    async fn update_port_forward(
+        &self,
+        port_forward_id: &str,
+        rule: PortForwardRule,
+    ) -> RouterApiResult<PortForward> {
+        self.write(self.inner.update_port_forward(port_forward_id, rule))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_port_forward(&self, port_forward_id: &str) -> RouterApiResult<()> {
+        self.write(self.inner.delete_port_forward(port_forward_id))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn lan_network(&self) -> RouterApiResult<IpNetwork> {
+        cached_read!(self, lan_network)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_static_leases(&self) -> RouterApiResult<Vec<StaticLease>> {
+        cached_read!(self, list_static_leases)
+    }
+
+    #[instrument(skip(self))]
+    async fn reserve_ip(
+        &self,
+        mac_address: MacAddress,
+        ip_address: IpAddr,
+    ) -> RouterApiResult<StaticLease> {
+        self.write(self.inner.reserve_ip(mac_address, ip_address))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn release_ip(&self, mac_address: MacAddress) -> RouterApiResult<()> {
+        self.write(self.inner.release_ip(mac_address)).await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_access(&self) -> RouterApiResult<Vec<DeviceAccess>> {
+        cached_read!(self, list_device_access)
+    }
+
+    #[instrument(skip(self))]
+    async fn set_device_blocked(
+        &self,
+        mac_address: MacAddress,
+        is_blocked: bool,
+    ) -> RouterApiResult<()> {
+        self.write(self.inner.set_device_blocked(mac_address, is_blocked))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn set_device_schedule(
+        &self,
+        mac_address: MacAddress,
+        schedule: Vec<AccessSlot>,
+    ) -> RouterApiResult<()> {
+        self.write(self.inner.set_device_schedule(mac_address, schedule))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_wifi_radios(&self) -> RouterApiResult<Vec<WifiRadio>> {
+        cached_read!(self, list_wifi_radios)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_connections(&self) -> RouterApiResult<Vec<DeviceConnection>> {
+        cached_read!(self, list_device_connections)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_traffic(&self) -> RouterApiResult<Vec<DeviceTrafficCounters>> {
+        cached_read!(self, list_device_traffic)
+    }
+
+    #[instrument(skip(self))]
+    async fn guest_network(&self) -> RouterApiResult<GuestNetwork> {
+        cached_read!(self, guest_network)
+    }
+
+    #[instrument(skip(self))]
+    async fn set_guest_network_enabled(&self, is_enabled: bool) -> RouterApiResult<()> {
+        self.write(self.inner.set_guest_network_enabled(is_enabled))
+            .await
+    }
+
+    #[instrument(skip(self, passphrase))]
+    async fn set_guest_network_credentials(
+        &self,
+        ssid: &str,
+        passphrase: &str,
+    ) -> RouterApiResult<()> {
+        self.write(self.inner.set_guest_network_credentials(ssid, passphrase))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn router_info(&self) -> RouterApiResult<RouterInfo> {
+        cached_read!(self, router_info)
+    }
+
+    #[instrument(skip(self))]
+    async fn reboot(&self) -> RouterApiResult<()> {
+        self.write(self.inner.reboot()).await
+    }
+
+    #[instrument(skip(self))]
+    async fn export_config(&self) -> RouterApiResult<String> {
+        self.resilience.retry(|| self.inner.export_config()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use common::List;
+
+    use super::*;
+
+    /// Router API answering `list_devices` after a delay, failing the given number of calls first.
+    struct StubRouterApi {
+        calls: AtomicU32,
+        failures: AtomicU32,
+    }
+
+    impl StubRouterApi {
+        fn failing(failures: u32) -> Arc<Self> {
+            Arc::new(Self {
+                calls: AtomicU32::new(0),
+                failures: AtomicU32::new(failures),
+            })
+        }
+
+        fn calls(&self) -> u32 {
+            self.calls.load(Ordering::SeqCst)
+        }
+
+        fn answer(&self) -> RouterApiResult<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |failures| {
+                    failures.checked_sub(1)
+                }) {
+                Ok(_) => Err(RouterApiError::Unavailable),
+                Err(_) => Ok(()),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl RouterApi for StubRouterApi {
+        async fn wan_connectivity(&self) -> RouterApiResult<WanConnectivity> {
+            Err(RouterApiError::Unsupported)
+        }
+
+        async fn list_devices(&self) -> RouterApiResult<Vec<Device>> {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            self.answer().map(|_| Vec::new())
+        }
+
+        async fn wan_stats(&self) -> RouterApiResult<WanStats> {
+            Err(RouterApiError::Unsupported)
+        }
+
+        async fn set_device_blocked(
+            &self,
+            _mac_address: MacAddress,
+            _is_blocked: bool,
+        ) -> RouterApiResult<()> {
+            self.answer()
+        }
+    }
+
+    fn resilient(inner: Arc<StubRouterApi>, cache_ttls: &str) -> ResilientRouterApi {
+        ResilientRouterApi::new(
+            inner,
+            &ResilienceConfig {
+                cache_ttls: cache_ttls.parse::<List<_>>().unwrap(),
+                retry_attempts: 3,
+                retry_backoff: 100,
+                breaker_threshold: 2,
+                breaker_cooldown: 30,
+            },
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_reads_are_coalesced_and_cached() {
+        let inner = StubRouterApi::failing(0);
+        let router_api = resilient(inner.clone(), "list_devices=10");
+
+        let (first, second, third) = tokio::join!(
+            router_api.list_devices(),
+            router_api.list_devices(),
+            router_api.list_devices()
+        );
+        assert!(first.is_ok() && second.is_ok() && third.is_ok());
+        assert_eq!(inner.calls(), 1);
+
+        router_api.list_devices().await.unwrap();
+        assert_eq!(inner.calls(), 1);
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        router_api.list_devices().await.unwrap();
+        assert_eq!(inner.calls(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reads_are_retried_but_writes_are_not() {
+        let inner = StubRouterApi::failing(2);
+        let router_api = resilient(inner.clone(), "");
+
+        router_api.list_devices().await.unwrap();
+        assert_eq!(inner.calls(), 3);
+
+        inner.failures.store(1, Ordering::SeqCst);
+        assert!(matches!(
+            router_api
+                .set_device_blocked(MacAddress::default(), true)
+                .await,
+            Err(RouterApiError::Unavailable)
+        ));
+        assert_eq!(inner.calls(), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn writes_invalidate_cached_reads() {
+        let inner = StubRouterApi::failing(0);
+        let router_api = resilient(inner.clone(), "list_devices=10");
+
+        router_api.list_devices().await.unwrap();
+        router_api
+            .set_device_blocked(MacAddress::default(), true)
+            .await
+            .unwrap();
+        router_api.list_devices().await.unwrap();
+
+        assert_eq!(inner.calls(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn circuit_opens_after_consecutive_failed_calls() {
+        let inner = StubRouterApi::failing(u32::MAX);
+        let router_api = resilient(inner.clone(), "");
+
+        // Every attempt of a call counts as a single failure
+        assert!(router_api.list_devices().await.is_err());
+        assert_eq!(inner.calls(), 3);
+        assert!(router_api.list_devices().await.is_err());
+        assert_eq!(inner.calls(), 6);
+
+        // Open: calls fail fast
+        assert!(matches!(
+            router_api.list_devices().await,
+            Err(RouterApiError::Unavailable)
+        ));
+        assert_eq!(inner.calls(), 6);
+
+        // Half-open: a failed probe opens the circuit again
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert!(router_api.list_devices().await.is_err());
+        assert_eq!(inner.calls(), 9);
+        assert!(router_api.list_devices().await.is_err());
+        assert_eq!(inner.calls(), 9);
+
+        // Half-open: a successful probe closes the circuit
+        tokio::time::advance(Duration::from_secs(30)).await;
+        inner.failures.store(0, Ordering::SeqCst);
+        router_api.list_devices().await.unwrap();
+        router_api.list_devices().await.unwrap();
+        assert_eq!(inner.calls(), 11);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn half_open_circuit_lets_a_probe_through_at_a_time() {
+        let inner = StubRouterApi::failing(u32::MAX);
+        let router_api = resilient(inner.clone(), "");
+        for _ in 0..2 {
+            let _ = router_api.list_devices().await;
+        }
+        tokio::time::advance(Duration::from_secs(30)).await;
+        inner.failures.store(0, Ordering::SeqCst);
+
+        // The probe is pending, other calls fail fast (concurrent reads would join the probe)
+        let (probe, other) = tokio::join!(router_api.list_devices(), async {
+            tokio::task::yield_now().await;
+            router_api
+                .set_device_blocked(MacAddress::default(), true)
+                .await
+        });
+        assert!(probe.is_ok());
+        assert!(matches!(other, Err(RouterApiError::Unavailable)));
+
+        // A dropped probe does not keep the circuit half-open forever
+        let inner = StubRouterApi::failing(u32::MAX);
+        let router_api = resilient(inner.clone(), "");
+        for _ in 0..2 {
+            let _ = router_api.list_devices().await;
+        }
+        tokio::time::advance(Duration::from_secs(30)).await;
+        inner.failures.store(0, Ordering::SeqCst);
+        let dropped =
+            tokio::time::timeout(Duration::from_millis(10), router_api.list_devices()).await;
+        assert!(dropped.is_err());
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        router_api.list_devices().await.unwrap();
+    }
+}
@@ -0,0 +1,931 @@
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use entities::{
+    AccessSlot, ConnectionType, Device, DeviceAccess, DeviceConnection, DeviceTrafficCounters,
+    GuestNetwork, PortForward, PortForwardRule, RouterInfo, StaticLease, WanConnectivity, WanStats,
+    WanStatsItem, WanStatus, WifiBand, WifiNetwork, WifiRadio,
+};
+use ipnetwork::{IpNetwork, Ipv4Network};
+use mac_address::MacAddress;
+use ports::api::{RouterApi, RouterApiError, RouterApiResult};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tracing::{debug, error, instrument};
+
+/// Maximum number of steps simulated at once, when the API was not used for a long time.
+const MAX_PENDING_STEPS: u64 = 1440;
+
+/// Kinds of generated devices, with the OUI of a manufacturer of such devices and whether they
+/// are usually wired.
+const DEVICE_KINDS: &[(&str, [u8; 3], bool)] = &[
+    ("iphone", [0x3c, 0x22, 0xfb], false),
+    ("macbook-pro", [0xa4, 0x83, 0xe7], false),
+    ("ipad", [0x28, 0xcf, 0xe9], false),
+    ("galaxy-s23", [0x8c, 0x77, 0x12], false),
+    ("samsung-tv", [0xf4, 0x7b, 0x09], true),
+    ("pixel-8", [0xf4, 0xf5, 0xd8], false),
+    ("chromecast", [0x54, 0x60, 0x09], false),
+    ("echo-dot", [0x74, 0xc2, 0x46], false),
+    ("raspberrypi", [0xb8, 0x27, 0xeb], true),
+    ("ps5", [0xbc, 0x60, 0xa7], true),
+    ("xbox", [0x98, 0x5f, 0xd3], true),
+    ("hp-printer", [0x3c, 0xd9, 0x2b], false),
+    ("synology-nas", [0x00, 0x11, 0x32], true),
+    ("thinkpad", [0x8c, 0x16, 0x45], false),
+    ("nest-thermostat", [0x18, 0xb4, 0x30], false),
+    ("hue-bridge", [0x00, 0x17, 0x88], true),
+];
+
+#[derive(Error, Debug)]
+enum SimulatedRouterApiError {
+    #[error("Failed to read the scenario file: {0}")]
+    ScenarioRead(#[from] std::io::Error),
+
+    #[error("Failed to parse the scenario file: {0}")]
+    ScenarioParse(#[from] serde_json::Error),
+
+    #[error("The scenario subnet has no room for {0} devices")]
+    SubnetTooSmall(usize),
+
+    #[error("The scenario subnet {0} has no room for the router and a device")]
+    SubnetWithoutHosts(Ipv4Network),
+
+    #[error("The scenario field {0} must be between 0 and 1, got {1}")]
+    OutOfRange(&'static str, f64),
+}
+
+impl From<SimulatedRouterApiError> for RouterApiError {
+    fn from(err: SimulatedRouterApiError) -> Self {
+        error!(
+            error = err.to_string(),
+            "An error occurred while using the router API"
+        );
+        RouterApiError::Unknown(err.to_string())
+    }
+}
+
+/// Scenario of the simulation, loaded from a JSON file. Every field is optional.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct Scenario {
+    /// Number of generated devices, in addition to `devices`.
+    population: usize,
+    subnet: Ipv4Network,
+    /// Devices always part of the population, e.g. to reproduce a specific network.
+    devices: Vec<ScenarioDevice>,
+    /// Simulated time between two steps, in seconds.
+    step: u64,
+    /// Per step probabilities.
+    join_probability: f64,
+    leave_probability: f64,
+    ip_change_probability: f64,
+    new_device_probability: f64,
+    wan: ScenarioWan,
+    /// Name of the Wi-Fi network, broadcast on both bands.
+    ssid: String,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            population: 20,
+            subnet: Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 0), 24).unwrap(),
+            devices: Vec::new(),
+            step: 60,
+            join_probability: 0.1,
+            leave_probability: 0.05,
+            ip_change_probability: 0.01,
+            new_device_probability: 0.01,
+            wan: ScenarioWan::default(),
+            ssid: "Helios".to_string(),
+        }
+    }
+}
+
+impl Scenario {
+    /// Rejects the values the simulation cannot run with, as scenario files are written by hand.
+    fn validate(&self) -> Result<(), SimulatedRouterApiError> {
+        // The router takes the first address, devices the following ones up to the broadcast one
+        if self.subnet.prefix() > 30 {
+            return Err(SimulatedRouterApiError::SubnetWithoutHosts(self.subnet));
+        }
+
+        for (field, value) in [
+            ("joinProbability", self.join_probability),
+            ("leaveProbability", self.leave_probability),
+            ("ipChangeProbability", self.ip_change_probability),
+            ("newDeviceProbability", self.new_device_probability),
+            ("wan.usage", self.wan.usage),
+            ("wan.outageProbability", self.wan.outage_probability),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(SimulatedRouterApiError::OutOfRange(field, value));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScenarioDevice {
+    mac_address: MacAddress,
+    hostname: String,
+    ip_address: Option<Ipv4Addr>,
+    #[serde(default)]
+    always_online: bool,
+    #[serde(default)]
+    wired: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct ScenarioWan {
+    ipv4: Ipv4Addr,
+    ipv6: Option<Ipv6Addr>,
+    gateway: IpAddr,
+    max_download: usize, // in kbps
+    max_upload: usize,   // in kbps
+    /// Average share of the bandwidth in use, between 0 and 1.
+    usage: f64,
+    /// Per step probability of the WAN going down.
+    outage_probability: f64,
+}
+
+impl Default for ScenarioWan {
+    fn default() -> Self {
+        Self {
+            ipv4: Ipv4Addr::new(203, 0, 113, 42),
+            ipv6: Some(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0x42)),
+            gateway: IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1)),
+            max_download: 1_000_000,
+            max_upload: 700_000,
+            usage: 0.1,
+            outage_probability: 0.0,
+        }
+    }
+}
+
+struct SimulatedDevice {
+    mac_address: MacAddress,
+    hostname: String,
+    ip_address: Ipv4Addr,
+    always_online: bool,
+    is_wired: bool,
+    is_online: bool,
+    last_seen: DateTime<Utc>,
+    rx_bytes: f64,
+    tx_bytes: f64,
+}
+
+/// Address of the device on the simulated IPv6 network, derived from its MAC address (EUI-64).
+fn simulated_ipv6(mac_address: MacAddress) -> Ipv6Addr {
+    let [a, b, c, d, e, f] = mac_address.bytes();
+    Ipv6Addr::new(
+        0xfd00,
+        0,
+        0,
+        0,
+        u16::from_be_bytes([a ^ 0x02, b]),
+        u16::from_be_bytes([c, 0xff]),
+        u16::from_be_bytes([0xfe, d]),
+        u16::from_be_bytes([e, f]),
+    )
+}
+
+/// BSSID of the simulated network broadcast on the band.
+fn simulated_bssid(band: WifiBand) -> MacAddress {
+    let index = match band {
+        WifiBand::Band2_4GHz => 1,
+        WifiBand::Band5GHz => 2,
+        WifiBand::Band6GHz => 3,
+    };
+
+    MacAddress::new([0x02, 0, 0, 0, 0, index])
+}
+
+impl SimulatedDevice {
+    /// Share of the traffic of the network used by the device when online, derived from the MAC
+    /// address so that the busiest devices stay the same.
+    fn traffic_weight(&self) -> f64 {
+        1.0 + (self.mac_address.bytes()[5] % 8) as f64
+    }
+
+    /// Derives the connection from the MAC address, so that it does not consume the random
+    /// generator and stays stable across steps.
+    fn connection(&self) -> DeviceConnection {
+        let bytes = self.mac_address.bytes();
+        if self.is_wired {
+            return DeviceConnection::wired(self.mac_address, Some(1000));
+        }
+
+        let band = if bytes[4].is_multiple_of(3) {
+            WifiBand::Band2_4GHz
+        } else {
+            WifiBand::Band5GHz
+        };
+        let rssi = -35 - (bytes[5] % 50) as i16;
+        DeviceConnection {
+            mac_address: self.mac_address,
+            connection_type: ConnectionType::Wifi,
+            band: Some(band),
+            rssi: Some(rssi),
+            // Rates drop with the signal, from about 1200 Mbit/s at -35 dBm
+            link_rate: Some((1200 + (rssi as i32 + 35) * 22).max(6) as u32),
+            access_point: Some(simulated_bssid(band).to_string().to_lowercase()),
+        }
+    }
+}
+
+#[derive(Default)]
+struct SimulatedLink {
+    bandwidth: f64, // in kbps
+    total: f64,     // in bytes
+    packets_lost: usize,
+}
+
+struct Simulation {
+    scenario: Scenario,
+    rng: StdRng,
+    devices: Vec<SimulatedDevice>,
+    last_step: Instant,
+    booted_at: DateTime<Utc>,
+    wan_up_since: Option<DateTime<Utc>>,
+    download: SimulatedLink,
+    upload: SimulatedLink,
+    port_forwards: Vec<PortForward>,
+    next_port_forward_id: u32,
+    static_leases: Vec<StaticLease>,
+    device_access: HashMap<MacAddress, DeviceAccess>,
+    guest_network: GuestNetwork,
+}
+
+impl Simulation {
+    fn new(scenario: Scenario, seed: u64) -> Result<Self, SimulatedRouterApiError> {
+        let now = Utc::now();
+        let mut simulation = Self {
+            rng: StdRng::seed_from_u64(seed),
+            devices: Vec::new(),
+            last_step: Instant::now(),
+            booted_at: now,
+            wan_up_since: Some(now),
+            download: SimulatedLink::default(),
+            upload: SimulatedLink::default(),
+            port_forwards: Vec::new(),
+            next_port_forward_id: 1,
+            static_leases: Vec::new(),
+            device_access: HashMap::new(),
+            guest_network: GuestNetwork {
+                is_enabled: false,
+                ssid: format!("{}-Guest", scenario.ssid),
+                passphrase: "welcome-to-helios".to_string(),
+                expires_at: None,
+            },
+            scenario,
+        };
+
+        let mut used_ips = simulation
+            .scenario
+            .devices
+            .iter()
+            .filter_map(|device| device.ip_address)
+            .collect::<HashSet<_>>();
+
+        for index in 0..simulation.scenario.devices.len() {
+            let ip_address = match simulation.scenario.devices[index].ip_address {
+                Some(ip_address) => ip_address,
+                None => simulation.free_ip(&used_ips)?,
+            };
+            used_ips.insert(ip_address);
+
+            let device = &simulation.scenario.devices[index];
+            simulation.devices.push(SimulatedDevice {
+                mac_address: device.mac_address,
+                hostname: device.hostname.clone(),
+                ip_address,
+                always_online: device.always_online,
+                is_wired: device.wired,
+                is_online: true,
+                last_seen: now,
+                rx_bytes: 0.0,
+                tx_bytes: 0.0,
+            });
+        }
+
+        for _ in 0..simulation.scenario.population {
+            simulation.add_device(now)?;
+        }
+
+        // Start with some traffic rather than an idle link
+        simulation.step_bandwidth(0.0);
+
+        Ok(simulation)
+    }
+
+    /// Lists the addresses that cannot be given to a device, including the reserved ones.
+    fn used_ips(&self) -> HashSet<Ipv4Addr> {
+        self.devices
+            .iter()
+            .map(|device| device.ip_address)
+            .chain(
+                self.static_leases
+                    .iter()
+                    .filter_map(|lease| match lease.ip_address {
+                        IpAddr::V4(ip_address) => Some(ip_address),
+                        IpAddr::V6(_) => None,
+                    }),
+            )
+            .collect()
+    }
+
+    fn reserved_ip(&self, mac_address: MacAddress) -> Option<Ipv4Addr> {
+        self.static_leases
+            .iter()
+            .find(|lease| lease.mac_address == mac_address)
+            .and_then(|lease| match lease.ip_address {
+                IpAddr::V4(ip_address) => Some(ip_address),
+                IpAddr::V6(_) => None,
+            })
+    }
+
+    /// Picks a random address of the subnet that is not used, the first one being the router.
+    fn free_ip(
+        &mut self,
+        used_ips: &HashSet<Ipv4Addr>,
+    ) -> Result<Ipv4Addr, SimulatedRouterApiError> {
+        let subnet = self.scenario.subnet;
+        let candidates = subnet
+            .iter()
+            .skip(2)
+            .filter(|address| *address != subnet.broadcast() && !used_ips.contains(address))
+            .collect::<Vec<_>>();
+
+        candidates
+            .choose(&mut self.rng)
+            .copied()
+            .ok_or(SimulatedRouterApiError::SubnetTooSmall(
+                self.devices.len() + 1,
+            ))
+    }
+
+    fn add_device(&mut self, now: DateTime<Utc>) -> Result<(), SimulatedRouterApiError> {
+        let ip_address = self.free_ip(&self.used_ips())?;
+        let (kind, oui, is_wired) = *DEVICE_KINDS.choose(&mut self.rng).unwrap();
+
+        let mac_address = MacAddress::new([
+            oui[0],
+            oui[1],
+            oui[2],
+            self.rng.random(),
+            self.rng.random(),
+            self.rng.random(),
+        ]);
+
+        let same_kind = self
+            .devices
+            .iter()
+            .filter(|device| device.hostname.starts_with(kind))
+            .count();
+        let hostname = match same_kind {
+            0 => kind.to_string(),
+            count => format!("{}-{}", kind, count + 1),
+        };
+
+        let is_online = self.rng.random_bool(0.7);
+        let last_seen = if is_online {
+            now
+        } else {
+            now - chrono::Duration::minutes(self.rng.random_range(5..7 * 24 * 60))
+        };
+
+        self.devices.push(SimulatedDevice {
+            mac_address,
+            hostname,
+            ip_address,
+            always_online: false,
+            is_wired,
+            is_online,
+            last_seen,
+            rx_bytes: 0.0,
+            tx_bytes: 0.0,
+        });
+
+        Ok(())
+    }
+
+    /// Runs the steps that should have happened since the last one.
+    fn advance(&mut self) {
+        let step = Duration::from_secs(self.scenario.step.max(1));
+        let pending_steps =
+            (self.last_step.elapsed().as_secs() / step.as_secs()).min(MAX_PENDING_STEPS);
+        if pending_steps == 0 {
+            return;
+        }
+
+        let now = Utc::now();
+        for remaining in (0..pending_steps).rev() {
+            let step_time = now - chrono::Duration::seconds((remaining * step.as_secs()) as i64);
+            self.step(step_time, step.as_secs_f64());
+        }
+
+        debug!(steps = pending_steps, "Advanced the simulation");
+        self.last_step = Instant::now();
+    }
+
+    fn step(&mut self, now: DateTime<Utc>, seconds: f64) {
+        let mut used_ips = self.used_ips();
+
+        for index in 0..self.devices.len() {
+            let device = &self.devices[index];
+            let reserved_ip = self.reserved_ip(device.mac_address);
+            let changes_ip = self.rng.random_bool(self.scenario.ip_change_probability);
+            let toggles = if device.always_online {
+                false
+            } else if device.is_online {
+                self.rng.random_bool(self.scenario.leave_probability)
+            } else {
+                self.rng.random_bool(self.scenario.join_probability)
+            };
+
+            // Devices usually get a new address when they come back after a while, unless reserved
+            if changes_ip
+                && (!device.is_online || toggles)
+                && let Some(ip_address) = reserved_ip.or_else(|| self.free_ip(&used_ips).ok())
+            {
+                used_ips.remove(&self.devices[index].ip_address);
+                used_ips.insert(ip_address);
+                self.devices[index].ip_address = ip_address;
+            }
+
+            let device = &mut self.devices[index];
+            device.is_online ^= toggles;
+            if device.is_online {
+                device.last_seen = now;
+            }
+        }
+
+        if self.rng.random_bool(self.scenario.new_device_probability) {
+            // The subnet may be full, which simply stops the population from growing
+            let _ = self.add_device(now);
+        }
+
+        self.step_outage(now);
+        self.step_bandwidth(seconds);
+    }
+
+    fn step_outage(&mut self, now: DateTime<Utc>) {
+        self.wan_up_since = match self.wan_up_since {
+            Some(_) if self.rng.random_bool(self.scenario.wan.outage_probability) => None,
+            None if self.rng.random_bool(0.5) => Some(now),
+            wan_up_since => wan_up_since,
+        };
+    }
+
+    fn step_bandwidth(&mut self, seconds: f64) {
+        let wan = &self.scenario.wan;
+
+        let online_devices = self
+            .devices
+            .iter()
+            .filter(|device| device.is_online)
+            .count();
+        let activity = match self.wan_up_since {
+            Some(_) => (online_devices as f64 / self.devices.len().max(1) as f64) * 2.0,
+            None => 0.0,
+        };
+
+        let (max_download, max_upload, usage) = (wan.max_download, wan.max_upload, wan.usage);
+        for (link, max_bandwidth) in [
+            (&mut self.download, max_download),
+            (&mut self.upload, max_upload),
+        ] {
+            // Mean-reverting random walk around the average usage, with occasional bursts
+            let mut target = max_bandwidth as f64 * usage * activity;
+            if self.rng.random_bool(0.05) {
+                target = max_bandwidth as f64 * self.rng.random_range(0.5..0.95);
+            }
+            let noise = self.rng.random_range(-0.05..0.05) * max_bandwidth as f64;
+
+            link.bandwidth = (link.bandwidth + (target - link.bandwidth) * 0.5 + noise)
+                .clamp(0.0, max_bandwidth as f64 * activity.min(1.0));
+            link.total += link.bandwidth * 1000.0 / 8.0 * seconds;
+            if link.bandwidth > 0.0 && self.rng.random_bool(0.1) {
+                link.packets_lost += self.rng.random_range(1..10);
+            }
+        }
+
+        // The traffic of the WAN is shared between the online devices
+        let total_weight: f64 = self
+            .devices
+            .iter()
+            .filter(|device| device.is_online)
+            .map(SimulatedDevice::traffic_weight)
+            .sum();
+        if total_weight > 0.0 {
+            let downloaded = self.download.bandwidth * 1000.0 / 8.0 * seconds;
+            let uploaded = self.upload.bandwidth * 1000.0 / 8.0 * seconds;
+            for device in self.devices.iter_mut().filter(|device| device.is_online) {
+                let share = device.traffic_weight() / total_weight;
+                device.rx_bytes += downloaded * share;
+                device.tx_bytes += uploaded * share;
+            }
+        }
+    }
+
+    fn wan_stats_item(link: &SimulatedLink, max_bandwidth: usize) -> WanStatsItem {
+        WanStatsItem {
+            max_bandwidth,
+            current_bandwidth: link.bandwidth as usize,
+            total_since_last_reboot: link.total as usize,
+            packets_lost: link.packets_lost,
+        }
+    }
+}
+
+/// Router API simulating a network, for demos and development without hardware.
+///
+/// Devices join, leave and change addresses over time, and the WAN bandwidth follows the activity
+/// of the network. The simulation is driven by a seeded random generator and advances in steps of
+/// real time, so the API can be used end-to-end with the other components. The router settings
+/// (port forwarding, DHCP reservations, parental controls) are only kept in memory.
+pub struct SimulatedRouterApi {
+    simulation: Mutex<Simulation>,
+}
+
+impl SimulatedRouterApi {
+    pub async fn new(scenario: Option<PathBuf>, seed: u64) -> RouterApiResult<Self> {
+        let scenario = match scenario {
+            Some(path) => serde_json::from_str(
+                &tokio::fs::read_to_string(path)
+                    .await
+                    .map_err(SimulatedRouterApiError::from)?,
+            )
+            .map_err(SimulatedRouterApiError::from)?,
+            None => Scenario::default(),
+        };
+        scenario.validate()?;
+
+        Ok(Self {
+            simulation: Mutex::new(Simulation::new(scenario, seed)?),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl RouterApi for SimulatedRouterApi {
+    #[instrument(skip(self))]
+    async fn wan_connectivity(&self) -> RouterApiResult<WanConnectivity> {
+        let mut simulation = self.simulation.lock().await;
+        simulation.advance();
+
+        let wan = &simulation.scenario.wan;
+        Ok(WanConnectivity {
+            ipv4: wan.ipv4,
+            ipv6: wan.ipv6,
+            gateway: wan.gateway,
+            status: match simulation.wan_up_since {
+                Some(_) => WanStatus::Up,
+                None => WanStatus::Down,
+            },
+            uptime: simulation
+                .wan_up_since
+                .map(|wan_up_since| Utc::now() - wan_up_since),
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn list_devices(&self) -> RouterApiResult<Vec<Device>> {
+        let mut simulation = self.simulation.lock().await;
+        simulation.advance();
+
+        let now = Utc::now();
+        Ok(simulation
+            .devices
+            .iter()
+            .map(|device| Device {
+                mac_address: device.mac_address,
+                last_known_ip: IpAddr::V4(device.ip_address),
+                display_name: device.hostname.clone(),
+                is_name_custom: false,
+                notes: String::new(),
+                is_online: device.is_online,
+                last_seen: device.last_seen,
+                last_scanned: now,
+                other_ips: vec![IpAddr::V6(simulated_ipv6(device.mac_address))],
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn wan_stats(&self) -> RouterApiResult<WanStats> {
+        let mut simulation = self.simulation.lock().await;
+        simulation.advance();
+
+        let online_devices = simulation
+            .devices
+            .iter()
+            .filter(|device| device.is_online)
+            .count();
+        let active_sessions = match simulation.wan_up_since {
+            Some(_) => online_devices * simulation.rng.random_range(5..40),
+            None => 0,
+        };
+
+        let wan = &simulation.scenario.wan;
+        Ok(WanStats {
+            download: Simulation::wan_stats_item(&simulation.download, wan.max_download),
+            upload: Simulation::wan_stats_item(&simulation.upload, wan.max_upload),
+            active_sessions,
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn list_port_forwards(&self) -> RouterApiResult<Vec<PortForward>> {
+        Ok(self.simulation.lock().await.port_forwards.clone())
+    }
+
+    #[instrument(skip(self))]
+    async fn create_port_forward(&self, rule: PortForwardRule) -> RouterApiResult<PortForward> {
+        let mut simulation = self.simulation.lock().await;
+
+        let port_forward = PortForward {
+            port_forward_id: simulation.next_port_forward_id.to_string(),
+            rule,
+        };
+        simulation.next_port_forward_id += 1;
+        simulation.port_forwards.push(port_forward.clone());
+
+        Ok(port_forward)
+    }
+
+    #[instrument(skip(self))]
+    async fn update_port_forward(
+        &self,
+        port_forward_id: &str,
+        rule: PortForwardRule,
+    ) -> RouterApiResult<PortForward> {
+        let mut simulation = self.simulation.lock().await;
+
+        let port_forward = simulation
+            .port_forwards
+            .iter_mut()
+            .find(|port_forward| port_forward.port_forward_id == port_forward_id)
+            .ok_or(RouterApiError::NotFound)?;
+        port_forward.rule = rule;
+
+        Ok(port_forward.clone())
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_port_forward(&self, port_forward_id: &str) -> RouterApiResult<()> {
+        let mut simulation = self.simulation.lock().await;
+
+        let index = simulation
+            .port_forwards
+            .iter()
+            .position(|port_forward| port_forward.port_forward_id == port_forward_id)
+            .ok_or(RouterApiError::NotFound)?;
+        simulation.port_forwards.remove(index);
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn lan_network(&self) -> RouterApiResult<IpNetwork> {
+        let subnet = self.simulation.lock().await.scenario.subnet;
+
+        // The router has the first address of the subnet
+        Ok(IpNetwork::V4(
+            Ipv4Network::new(subnet.iter().nth(1).unwrap(), subnet.prefix()).unwrap(),
+        ))
+    }
+
+    #[instrument(skip(self))]
+    async fn list_static_leases(&self) -> RouterApiResult<Vec<StaticLease>> {
+        Ok(self.simulation.lock().await.static_leases.clone())
+    }
+
+    #[instrument(skip(self))]
+    async fn reserve_ip(
+        &self,
+        mac_address: MacAddress,
+        ip_address: IpAddr,
+    ) -> RouterApiResult<StaticLease> {
+        let mut simulation = self.simulation.lock().await;
+
+        let lease = StaticLease {
+            mac_address,
+            ip_address,
+        };
+        simulation
+            .static_leases
+            .retain(|lease| lease.mac_address != mac_address);
+        simulation.static_leases.push(lease.clone());
+
+        Ok(lease)
+    }
+
+    #[instrument(skip(self))]
+    async fn release_ip(&self, mac_address: MacAddress) -> RouterApiResult<()> {
+        let mut simulation = self.simulation.lock().await;
+
+        let index = simulation
+            .static_leases
+            .iter()
+            .position(|lease| lease.mac_address == mac_address)
+            .ok_or(RouterApiError::NotFound)?;
+        simulation.static_leases.remove(index);
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_access(&self) -> RouterApiResult<Vec<DeviceAccess>> {
+        Ok(self
+            .simulation
+            .lock()
+            .await
+            .device_access
+            .values()
+            .filter(|access| access.is_restricted())
+            .cloned()
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn set_device_blocked(
+        &self,
+        mac_address: MacAddress,
+        is_blocked: bool,
+    ) -> RouterApiResult<()> {
+        self.simulation
+            .lock()
+            .await
+            .device_access
+            .entry(mac_address)
+            .or_insert_with(|| DeviceAccess::allowed(mac_address))
+            .is_blocked = is_blocked;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn set_device_schedule(
+        &self,
+        mac_address: MacAddress,
+        schedule: Vec<AccessSlot>,
+    ) -> RouterApiResult<()> {
+        self.simulation
+            .lock()
+            .await
+            .device_access
+            .entry(mac_address)
+            .or_insert_with(|| DeviceAccess::allowed(mac_address))
+            .schedule = schedule;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_wifi_radios(&self) -> RouterApiResult<Vec<WifiRadio>> {
+        let simulation = self.simulation.lock().await;
+
+        Ok([(WifiBand::Band2_4GHz, 6), (WifiBand::Band5GHz, 36)]
+            .into_iter()
+            .map(|(band, channel)| WifiRadio {
+                band,
+                is_enabled: true,
+                channel: Some(channel),
+                networks: std::iter::once(WifiNetwork {
+                    ssid: simulation.scenario.ssid.clone(),
+                    bssid: Some(simulated_bssid(band)),
+                    is_enabled: true,
+                    is_hidden: false,
+                    security: Some("WPA2".to_string()),
+                })
+                .chain(simulation.guest_network.is_enabled.then(|| {
+                    WifiNetwork {
+                        ssid: simulation.guest_network.ssid.clone(),
+                        bssid: None,
+                        is_enabled: true,
+                        is_hidden: false,
+                        security: (!simulation.guest_network.passphrase.is_empty())
+                            .then(|| "WPA2".to_string()),
+                    }
+                }))
+                .collect(),
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_connections(&self) -> RouterApiResult<Vec<DeviceConnection>> {
+        let mut simulation = self.simulation.lock().await;
+        simulation.advance();
+
+        Ok(simulation
+            .devices
+            .iter()
+            .filter(|device| device.is_online)
+            .map(SimulatedDevice::connection)
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_device_traffic(&self) -> RouterApiResult<Vec<DeviceTrafficCounters>> {
+        let mut simulation = self.simulation.lock().await;
+        simulation.advance();
+
+        Ok(simulation
+            .devices
+            .iter()
+            .map(|device| DeviceTrafficCounters {
+                mac_address: device.mac_address,
+                rx_bytes: device.rx_bytes as u64,
+                tx_bytes: device.tx_bytes as u64,
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn guest_network(&self) -> RouterApiResult<GuestNetwork> {
+        Ok(self.simulation.lock().await.guest_network.clone())
+    }
+
+    #[instrument(skip(self))]
+    async fn set_guest_network_enabled(&self, is_enabled: bool) -> RouterApiResult<()> {
+        self.simulation.lock().await.guest_network.is_enabled = is_enabled;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, passphrase))]
+    async fn set_guest_network_credentials(
+        &self,
+        ssid: &str,
+        passphrase: &str,
+    ) -> RouterApiResult<()> {
+        let guest_network = &mut self.simulation.lock().await.guest_network;
+        guest_network.ssid = ssid.to_string();
+        guest_network.passphrase = passphrase.to_string();
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn router_info(&self) -> RouterApiResult<RouterInfo> {
+        let simulation = self.simulation.lock().await;
+
+        Ok(RouterInfo {
+            model: "Helios Simulated Router".to_string(),
+            firmware_version: env!("CARGO_PKG_VERSION").to_string(),
+            serial_number: None,
+            uptime: Some(Utc::now() - simulation.booted_at),
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn reboot(&self) -> RouterApiResult<()> {
+        let mut simulation = self.simulation.lock().await;
+        let now = Utc::now();
+        simulation.booted_at = now;
+        simulation.wan_up_since = Some(now);
+        simulation.download.total = 0.0;
+        simulation.upload.total = 0.0;
+        for device in &mut simulation.devices {
+            device.rx_bytes = 0.0;
+            device.tx_bytes = 0.0;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn export_config(&self) -> RouterApiResult<String> {
+        let simulation = self.simulation.lock().await;
+        let mut device_access = simulation.device_access.values().collect::<Vec<_>>();
+        device_access.sort_by_key(|access| access.mac_address.bytes());
+
+        serde_json::to_string_pretty(&serde_json::json!({
+            "portForwards": simulation.port_forwards,
+            "staticLeases": simulation.static_leases,
+            "deviceAccess": device_access,
+            // The passphrase is a secret, it is left out of the backups
+            "guestNetwork": {
+                "isEnabled": simulation.guest_network.is_enabled,
+                "ssid": simulation.guest_network.ssid,
+                "expiresAt": simulation.guest_network.expires_at,
+            },
+        }))
+        .map_err(|err| RouterApiError::InvalidResponse(err.to_string()))
+    }
+}
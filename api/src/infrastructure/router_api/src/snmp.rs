@@ -0,0 +1,944 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::Duration,
+};
+
+use common::{SnmpAuthProtocol, SnmpPrivacyProtocol};
+use entities::{Device, WanConnectivity, WanStats, WanStatsItem, WanStatus};
+use mac_address::MacAddress;
+use ports::api::{RouterApi, RouterApiError, RouterApiResult};
+use snmp2::{AsyncSession, Oid, Value, v3};
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tracing::{error, instrument, warn};
+
+use crate::bandwidth::BandwidthMeter;
+
+const SYS_UP_TIME: &[u64] = &[1, 3, 6, 1, 2, 1, 1, 3, 0];
+const IF_IN_DISCARDS: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 13];
+const IF_IN_ERRORS: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 14];
+const IF_OUT_DISCARDS: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 19];
+const IF_OUT_ERRORS: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 20];
+const IF_OPER_STATUS: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 8];
+const IF_HC_IN_OCTETS: &[u64] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6];
+const IF_HC_OUT_OCTETS: &[u64] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 10];
+const IF_HIGH_SPEED: &[u64] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 15];
+const IP_AD_ENT_IF_INDEX: &[u64] = &[1, 3, 6, 1, 2, 1, 4, 20, 1, 2];
+const IP_ROUTE_NEXT_HOP_DEFAULT: &[u64] = &[1, 3, 6, 1, 2, 1, 4, 21, 1, 7, 0, 0, 0, 0];
+const IP_NET_TO_MEDIA_PHYS_ADDRESS: &[u64] = &[1, 3, 6, 1, 2, 1, 4, 22, 1, 2];
+const IP_NET_TO_MEDIA_TYPE: &[u64] = &[1, 3, 6, 1, 2, 1, 4, 22, 1, 4];
+const IP_ADDRESS_IF_INDEX: &[u64] = &[1, 3, 6, 1, 2, 1, 4, 34, 1, 3];
+const IP_NET_TO_PHYSICAL_PHYS_ADDRESS: &[u64] = &[1, 3, 6, 1, 2, 1, 4, 35, 1, 4];
+const IP_NET_TO_PHYSICAL_STATE: &[u64] = &[1, 3, 6, 1, 2, 1, 4, 35, 1, 7];
+
+/// `ipNetToMediaType` value of entries that must be ignored.
+const MEDIA_TYPE_INVALID: i64 = 2;
+/// `ipNetToPhysicalState` values of entries that must be ignored (invalid, incomplete).
+const PHYSICAL_STATES_INVALID: [i64; 2] = [5, 7];
+/// `InetAddressType` values.
+const INET_ADDRESS_IPV4: u64 = 1;
+const INET_ADDRESS_IPV6: u64 = 2;
+/// SNMP error status returned when the request is not allowed for the community or user.
+const ERRSTATUS_AUTHORIZATION_ERROR: u32 = 16;
+
+/// Number of rows requested per GETBULK when walking a table.
+const MAX_REPETITIONS: u32 = 25;
+
+#[derive(Error, Debug)]
+enum SnmpRouterApiError {
+    #[error("Failed to reach the SNMP agent: {0}")]
+    ConnectionFailed(#[from] std::io::Error),
+
+    #[error("The SNMP agent did not answer in time")]
+    Timeout,
+
+    #[error("SNMP error: {0}")]
+    ProtocolError(snmp2::Error),
+
+    #[error("The SNMP agent returned error status {0}")]
+    ErrorStatus(u32),
+
+    #[error("Missing required field: {0}")]
+    MissingField(String),
+}
+
+impl From<snmp2::Error> for SnmpRouterApiError {
+    fn from(err: snmp2::Error) -> Self {
+        SnmpRouterApiError::ProtocolError(err)
+    }
+}
+
+impl From<SnmpRouterApiError> for RouterApiError {
+    fn from(err: SnmpRouterApiError) -> Self {
+        error!(
+            error = err.to_string(),
+            "An error occurred while using the router API"
+        );
+        match err {
+            SnmpRouterApiError::ConnectionFailed(_)
+            | SnmpRouterApiError::Timeout
+            | SnmpRouterApiError::ProtocolError(snmp2::Error::Send | snmp2::Error::Receive) => {
+                RouterApiError::Unavailable
+            }
+            SnmpRouterApiError::ProtocolError(
+                snmp2::Error::AuthFailure(_) | snmp2::Error::CommunityMismatch,
+            )
+            | SnmpRouterApiError::ErrorStatus(ERRSTATUS_AUTHORIZATION_ERROR) => {
+                RouterApiError::AuthenticationFailed
+            }
+            SnmpRouterApiError::ProtocolError(_)
+            | SnmpRouterApiError::ErrorStatus(_)
+            | SnmpRouterApiError::MissingField(_) => {
+                RouterApiError::InvalidResponse(err.to_string())
+            }
+        }
+    }
+}
+
+/// Owned copy of the values we care about, as `snmp2` values borrow the session buffer.
+#[derive(Debug, Clone)]
+enum SnmpValue {
+    Integer(i64),
+    Bytes(Vec<u8>),
+    IpAddress(Ipv4Addr),
+    Counter(u64),
+    Timeticks(u32),
+    Missing,
+    Unsupported,
+}
+
+impl From<Value<'_>> for SnmpValue {
+    fn from(value: Value<'_>) -> Self {
+        match value {
+            Value::Integer(value) => SnmpValue::Integer(value),
+            Value::OctetString(value) => SnmpValue::Bytes(value.to_vec()),
+            Value::IpAddress(value) => SnmpValue::IpAddress(Ipv4Addr::from(value)),
+            Value::Counter32(value) | Value::Unsigned32(value) => SnmpValue::Counter(value as u64),
+            Value::Counter64(value) => SnmpValue::Counter(value),
+            Value::Timeticks(value) => SnmpValue::Timeticks(value),
+            Value::NoSuchObject | Value::NoSuchInstance | Value::EndOfMibView => SnmpValue::Missing,
+            _ => SnmpValue::Unsupported,
+        }
+    }
+}
+
+impl SnmpValue {
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            SnmpValue::Counter(value) => Some(*value),
+            SnmpValue::Timeticks(value) => Some(*value as u64),
+            SnmpValue::Integer(value) => u64::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+}
+
+type Varbind = (Vec<u64>, SnmpValue);
+
+enum SnmpRequest<'a> {
+    Get(&'a [Vec<u64>]),
+    GetBulk(&'a [u64]),
+}
+
+/// How to open a session with the agent.
+pub enum SnmpCredentials {
+    V2c {
+        community: String,
+    },
+    V3 {
+        username: String,
+        auth_protocol: SnmpAuthProtocol,
+        auth_password: String,
+        privacy_protocol: SnmpPrivacyProtocol,
+        privacy_password: String,
+    },
+}
+
+impl SnmpCredentials {
+    fn security(&self) -> Option<v3::Security> {
+        let SnmpCredentials::V3 {
+            username,
+            auth_protocol,
+            auth_password,
+            privacy_protocol,
+            privacy_password,
+        } = self
+        else {
+            return None;
+        };
+
+        let auth = match (auth_password.is_empty(), privacy_protocol) {
+            (true, _) => v3::Auth::NoAuthNoPriv,
+            (false, SnmpPrivacyProtocol::None) => v3::Auth::AuthNoPriv,
+            (false, privacy_protocol) => v3::Auth::AuthPriv {
+                cipher: match privacy_protocol {
+                    SnmpPrivacyProtocol::Des => v3::Cipher::Des,
+                    SnmpPrivacyProtocol::Aes192 => v3::Cipher::Aes192,
+                    SnmpPrivacyProtocol::Aes256 => v3::Cipher::Aes256,
+                    SnmpPrivacyProtocol::Aes128 | SnmpPrivacyProtocol::None => v3::Cipher::Aes128,
+                },
+                privacy_password: privacy_password.as_bytes().to_vec(),
+            },
+        };
+
+        Some(
+            v3::Security::new(username.as_bytes(), auth_password.as_bytes())
+                .with_auth_protocol(match auth_protocol {
+                    SnmpAuthProtocol::Md5 => v3::AuthProtocol::Md5,
+                    SnmpAuthProtocol::Sha1 => v3::AuthProtocol::Sha1,
+                    SnmpAuthProtocol::Sha224 => v3::AuthProtocol::Sha224,
+                    SnmpAuthProtocol::Sha256 => v3::AuthProtocol::Sha256,
+                    SnmpAuthProtocol::Sha384 => v3::AuthProtocol::Sha384,
+                    SnmpAuthProtocol::Sha512 => v3::AuthProtocol::Sha512,
+                })
+                .with_auth(auth),
+        )
+    }
+}
+
+pub struct SnmpRouterApi {
+    agent_address: String,
+    credentials: SnmpCredentials,
+    wan_if_index: u32,
+    timeout: Duration,
+    session: Mutex<Option<AsyncSession>>,
+    bandwidth_meter: BandwidthMeter,
+}
+
+fn oid(components: &[u64]) -> Oid<'static> {
+    Oid::from(components).expect("OID components must be valid")
+}
+
+fn with_index(prefix: &[u64], index: &[u64]) -> Vec<u64> {
+    prefix.iter().chain(index).copied().collect()
+}
+
+/// Parses an IPv4 or IPv6 address encoded as `InetAddressType.length.bytes...` in a table index.
+fn parse_inet_address(index: &[u64]) -> Option<IpAddr> {
+    let [address_type, length, address @ ..] = index else {
+        return None;
+    };
+
+    let bytes = address
+        .iter()
+        .map(|byte| u8::try_from(*byte).ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    match (*address_type, *length as usize, bytes.len()) {
+        (INET_ADDRESS_IPV4, 4, 4) => {
+            Some(IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?)))
+        }
+        (INET_ADDRESS_IPV6, 16, 16) => Some(IpAddr::V6(Ipv6Addr::from(
+            <[u8; 16]>::try_from(bytes).ok()?,
+        ))),
+        _ => None,
+    }
+}
+
+impl SnmpRouterApi {
+    pub async fn new(
+        agent_address: String,
+        credentials: SnmpCredentials,
+        wan_if_index: u32,
+        timeout: Duration,
+    ) -> Result<Self, RouterApiError> {
+        let api = Self {
+            agent_address,
+            credentials,
+            wan_if_index,
+            timeout,
+            session: Mutex::new(None),
+            bandwidth_meter: BandwidthMeter::new(),
+        };
+
+        // Make sure the agent answers with the given credentials
+        api.request(SnmpRequest::Get(&[SYS_UP_TIME.to_vec()]))
+            .await?;
+
+        Ok(api)
+    }
+
+    async fn connect(&self) -> Result<AsyncSession, SnmpRouterApiError> {
+        let mut session = match self.credentials.security() {
+            None => {
+                let SnmpCredentials::V2c { community } = &self.credentials else {
+                    unreachable!("SNMPv3 credentials always provide a security context");
+                };
+                AsyncSession::new_v2c(self.agent_address.as_str(), community.as_bytes(), 0).await?
+            }
+            Some(security) => {
+                AsyncSession::new_v3(self.agent_address.as_str(), 0, security).await?
+            }
+        };
+
+        // Discovers the engine ID for SNMPv3, no-op for v2c
+        tokio::time::timeout(self.timeout, session.init())
+            .await
+            .map_err(|_| SnmpRouterApiError::Timeout)??;
+
+        Ok(session)
+    }
+
+    async fn request_once(
+        &self,
+        session: &mut Option<AsyncSession>,
+        request: &SnmpRequest<'_>,
+    ) -> Result<Vec<Varbind>, SnmpRouterApiError> {
+        if session.is_none() {
+            *session = Some(self.connect().await?);
+        }
+
+        let session = session.as_mut().unwrap();
+        let response = match request {
+            SnmpRequest::Get(oids) => {
+                let oids = oids
+                    .iter()
+                    .map(|components| oid(components))
+                    .collect::<Vec<_>>();
+                tokio::time::timeout(
+                    self.timeout,
+                    session.get_many(&oids.iter().collect::<Vec<_>>()),
+                )
+                .await
+            }
+            SnmpRequest::GetBulk(start) => {
+                tokio::time::timeout(
+                    self.timeout,
+                    session.getbulk(&[&oid(start)], 0, MAX_REPETITIONS),
+                )
+                .await
+            }
+        }
+        .map_err(|_| SnmpRouterApiError::Timeout)??;
+
+        if response.error_status != snmp2::snmp::ERRSTATUS_NOERROR {
+            return Err(SnmpRouterApiError::ErrorStatus(response.error_status));
+        }
+
+        Ok(response
+            .varbinds
+            .map(|(name, value)| {
+                (
+                    name.iter()
+                        .map(|components| components.collect())
+                        .unwrap_or_default(),
+                    SnmpValue::from(value),
+                )
+            })
+            .collect())
+    }
+
+    /// Sends a request to the agent, reconnecting and retrying once if the session went bad (the
+    /// agent restarted, a reply was lost, or the SNMPv3 engine counters were updated).
+    async fn request(&self, request: SnmpRequest<'_>) -> Result<Vec<Varbind>, SnmpRouterApiError> {
+        let mut session = self.session.lock().await;
+
+        match self.request_once(&mut session, &request).await {
+            Err(SnmpRouterApiError::ProtocolError(snmp2::Error::AuthUpdated)) => {
+                self.request_once(&mut session, &request).await
+            }
+            Err(
+                SnmpRouterApiError::Timeout
+                | SnmpRouterApiError::ProtocolError(
+                    snmp2::Error::Send | snmp2::Error::Receive | snmp2::Error::RequestIdMismatch,
+                ),
+            ) => {
+                *session = None;
+                self.request_once(&mut session, &request).await
+            }
+            result => result,
+        }
+    }
+
+    async fn get(&self, oids: &[Vec<u64>]) -> Result<Vec<SnmpValue>, SnmpRouterApiError> {
+        Ok(self
+            .request(SnmpRequest::Get(oids))
+            .await?
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect())
+    }
+
+    /// Walks a subtree with GETBULK requests, returning the index of each row (the OID without
+    /// the prefix) along with its value.
+    async fn walk(&self, prefix: &[u64]) -> Result<Vec<Varbind>, SnmpRouterApiError> {
+        let mut rows = Vec::new();
+        let mut current = prefix.to_vec();
+
+        'walk: loop {
+            let varbinds = self.request(SnmpRequest::GetBulk(&current)).await?;
+            if varbinds.is_empty() {
+                break;
+            }
+
+            for (name, value) in varbinds {
+                if !name.starts_with(prefix)
+                    || matches!(value, SnmpValue::Missing)
+                    || name <= current
+                {
+                    break 'walk;
+                }
+
+                rows.push((name[prefix.len()..].to_vec(), value));
+                current = name;
+            }
+        }
+
+        Ok(rows)
+    }
+
+    /// Returns the neighbours known by the agent, from `ipNetToPhysicalTable` and the deprecated
+    /// `ipNetToMediaTable` (which older agents only implement), excluding the WAN interface.
+    async fn neighbours(&self) -> Result<Vec<(IpAddr, MacAddress)>, SnmpRouterApiError> {
+        let mut neighbours = Vec::new();
+
+        let states = self
+            .walk(IP_NET_TO_PHYSICAL_STATE)
+            .await?
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        for (index, value) in self.walk(IP_NET_TO_PHYSICAL_PHYS_ADDRESS).await? {
+            // index = ifIndex.InetAddressType.length.address...
+            if index.first() == Some(&(self.wan_if_index as u64)) {
+                continue;
+            }
+
+            if let Some(SnmpValue::Integer(state)) = states.get(&index)
+                && PHYSICAL_STATES_INVALID.contains(state)
+            {
+                continue;
+            }
+
+            if let (Some(ip_address), SnmpValue::Bytes(mac_address)) =
+                (parse_inet_address(&index[1..]), value)
+                && let Ok(mac_address) = <[u8; 6]>::try_from(mac_address)
+            {
+                neighbours.push((ip_address, MacAddress::new(mac_address)));
+            }
+        }
+
+        let types = self
+            .walk(IP_NET_TO_MEDIA_TYPE)
+            .await?
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        for (index, value) in self.walk(IP_NET_TO_MEDIA_PHYS_ADDRESS).await? {
+            // index = ifIndex.a.b.c.d
+            let [if_index, a, b, c, d] = index[..] else {
+                continue;
+            };
+
+            if if_index == self.wan_if_index as u64 {
+                continue;
+            }
+
+            if let Some(SnmpValue::Integer(MEDIA_TYPE_INVALID)) = types.get(&index) {
+                continue;
+            }
+
+            if let SnmpValue::Bytes(mac_address) = value
+                && let Ok(mac_address) = <[u8; 6]>::try_from(mac_address)
+            {
+                neighbours.push((
+                    IpAddr::V4(Ipv4Addr::new(a as u8, b as u8, c as u8, d as u8)),
+                    MacAddress::new(mac_address),
+                ));
+            }
+        }
+
+        Ok(neighbours)
+    }
+}
+
+#[async_trait::async_trait]
+impl RouterApi for SnmpRouterApi {
+    #[instrument(skip(self))]
+    async fn wan_connectivity(&self) -> RouterApiResult<WanConnectivity> {
+        let wan_if_index = [self.wan_if_index as u64];
+        let values = self
+            .get(&[
+                SYS_UP_TIME.to_vec(),
+                with_index(IF_OPER_STATUS, &wan_if_index),
+                IP_ROUTE_NEXT_HOP_DEFAULT.to_vec(),
+            ])
+            .await?;
+
+        let [uptime, oper_status, gateway] = &values[..] else {
+            return Err(SnmpRouterApiError::MissingField("sysUpTime".to_string()).into());
+        };
+
+        let ipv4 = self
+            .walk(IP_AD_ENT_IF_INDEX)
+            .await?
+            .into_iter()
+            .find_map(|(index, value)| match (&index[..], value.as_u64()) {
+                ([a, b, c, d], Some(if_index)) if if_index == self.wan_if_index as u64 => {
+                    Some(Ipv4Addr::new(*a as u8, *b as u8, *c as u8, *d as u8))
+                }
+                _ => None,
+            })
+            .unwrap_or(Ipv4Addr::UNSPECIFIED);
+
+        // ipAddressTable is optional, older agents only expose IPv4 addresses
+        let ipv6 = match self.walk(IP_ADDRESS_IF_INDEX).await {
+            Ok(rows) => rows.into_iter().find_map(|(index, value)| {
+                match (parse_inet_address(&index), value.as_u64()) {
+                    (Some(IpAddr::V6(ip)), Some(if_index))
+                        if if_index == self.wan_if_index as u64 =>
+                    {
+                        Some(ip)
+                    }
+                    _ => None,
+                }
+            }),
+            Err(err) => {
+                warn!(error = err.to_string(), "Failed to walk ipAddressTable");
+                None
+            }
+        };
+
+        Ok(WanConnectivity {
+            ipv4,
+            ipv6,
+            gateway: match gateway {
+                SnmpValue::IpAddress(ip) => IpAddr::V4(*ip),
+                _ => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            },
+            status: match oper_status {
+                SnmpValue::Integer(1) => WanStatus::Up,
+                _ => WanStatus::Down,
+            },
+            // sysUpTime is expressed in hundredths of a second
+            uptime: uptime
+                .as_u64()
+                .map(|ticks| chrono::Duration::milliseconds(ticks as i64 * 10)),
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn list_devices(&self) -> RouterApiResult<Vec<Device>> {
+        let now = chrono::Utc::now();
+        let mut devices: HashMap<MacAddress, Device> = HashMap::new();
+
+        for (ip_address, mac_address) in self.neighbours().await? {
+            if mac_address.bytes() == [0; 6] {
+                continue;
+            }
+
+            let device = devices.entry(mac_address).or_insert_with(|| Device {
+                mac_address,
+                last_known_ip: ip_address,
+                display_name: ip_address.to_string(),
+                is_name_custom: false,
+                notes: String::new(),
+                is_online: true,
+                last_seen: now,
+                last_scanned: now,
+                other_ips: Vec::new(),
+            });
+
+            // Prefer IPv4 addresses, which are more meaningful to users
+            if device.last_known_ip.is_ipv6() && ip_address.is_ipv4() {
+                let ipv6_address = std::mem::replace(&mut device.last_known_ip, ip_address);
+                device.other_ips.push(ipv6_address);
+                device.display_name = ip_address.to_string();
+            } else if device.last_known_ip != ip_address && !device.other_ips.contains(&ip_address)
+            {
+                device.other_ips.push(ip_address);
+            }
+        }
+
+        if devices.is_empty() {
+            warn!("Router API returned an empty list of devices. This should never happen!");
+        }
+
+        Ok(devices.into_values().collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn wan_stats(&self) -> RouterApiResult<WanStats> {
+        let wan_if_index = [self.wan_if_index as u64];
+        let values = self
+            .get(&[
+                with_index(IF_HC_IN_OCTETS, &wan_if_index),
+                with_index(IF_HC_OUT_OCTETS, &wan_if_index),
+                with_index(IF_HIGH_SPEED, &wan_if_index),
+                with_index(IF_IN_DISCARDS, &wan_if_index),
+                with_index(IF_IN_ERRORS, &wan_if_index),
+                with_index(IF_OUT_DISCARDS, &wan_if_index),
+                with_index(IF_OUT_ERRORS, &wan_if_index),
+            ])
+            .await?
+            .into_iter()
+            .map(|value| value.as_u64())
+            .collect::<Vec<_>>();
+
+        let [
+            Some(rx_bytes),
+            Some(tx_bytes),
+            high_speed,
+            in_discards,
+            in_errors,
+            out_discards,
+            out_errors,
+        ] = values[..]
+        else {
+            return Err(
+                SnmpRouterApiError::MissingField("ifHCInOctets/ifHCOutOctets".to_string()).into(),
+            );
+        };
+
+        let (download_bandwidth, upload_bandwidth) =
+            self.bandwidth_meter.sample(rx_bytes, tx_bytes).await;
+
+        // ifHighSpeed is expressed in Mbps
+        let max_bandwidth = high_speed.unwrap_or(0) as usize * 1000;
+
+        Ok(WanStats {
+            download: WanStatsItem {
+                max_bandwidth,
+                current_bandwidth: download_bandwidth,
+                total_since_last_reboot: rx_bytes as usize,
+                packets_lost: (in_discards.unwrap_or(0) + in_errors.unwrap_or(0)) as usize,
+            },
+            upload: WanStatsItem {
+                max_bandwidth,
+                current_bandwidth: upload_bandwidth,
+                total_since_last_reboot: tx_bytes as usize,
+                packets_lost: (out_discards.unwrap_or(0) + out_errors.unwrap_or(0)) as usize,
+            },
+            active_sessions: 0, // Not exposed by standard MIBs
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        ops::Bound,
+        sync::{Arc, Mutex as StdMutex},
+    };
+
+    use snmp2::{
+        MessageType, Pdu,
+        asn1::{TYPE_INTEGER, TYPE_OBJECTIDENTIFIER, TYPE_OCTETSTRING, TYPE_SEQUENCE},
+        snmp::{
+            MSG_RESPONSE, SNMP_ENDOFMIBVIEW, SNMP_NOSUCHINSTANCE, TYPE_COUNTER64, TYPE_GAUGE32,
+            TYPE_TIMETICKS,
+        },
+    };
+    use tokio::net::UdpSocket;
+
+    use super::*;
+
+    const WAN_IF_INDEX: u64 = 1;
+    const LAN_IF_INDEX: u64 = 2;
+
+    #[derive(Debug, Clone)]
+    enum StubValue {
+        Integer(i64),
+        OctetString(Vec<u8>),
+        Gauge(u32),
+        Counter64(u64),
+        Timeticks(u32),
+        NoSuchInstance,
+        EndOfMibView,
+    }
+
+    type Mib = BTreeMap<Vec<u64>, StubValue>;
+
+    /// In-process SNMPv2c agent answering GET and GETBULK requests from an in-memory MIB.
+    struct StubAgent {
+        address: String,
+        mib: Arc<StdMutex<Mib>>,
+    }
+
+    impl StubAgent {
+        async fn start(mib: Mib) -> Self {
+            let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let address = socket.local_addr().unwrap().to_string();
+            let mib = Arc::new(StdMutex::new(mib));
+
+            let agent_mib = mib.clone();
+            tokio::spawn(async move {
+                let mut request = vec![0; 65535];
+                while let Ok((length, peer)) = socket.recv_from(&mut request).await {
+                    let Ok(pdu) = Pdu::from_bytes(&request[..length]) else {
+                        continue;
+                    };
+                    let response = respond(pdu, &agent_mib.lock().unwrap());
+                    let _ = socket.send_to(&response, peer).await;
+                }
+            });
+
+            Self { address, mib }
+        }
+
+        fn set(&self, name: Vec<u64>, value: StubValue) {
+            self.mib.lock().unwrap().insert(name, value);
+        }
+
+        async fn router_api(&self) -> SnmpRouterApi {
+            SnmpRouterApi::new(
+                self.address.clone(),
+                SnmpCredentials::V2c {
+                    community: "public".to_string(),
+                },
+                WAN_IF_INDEX as u32,
+                Duration::from_secs(1),
+            )
+            .await
+            .unwrap()
+        }
+    }
+
+    fn respond(pdu: Pdu<'_>, mib: &Mib) -> Vec<u8> {
+        let (message_type, req_id, community) = (pdu.message_type, pdu.req_id, pdu.community);
+        // For GETBULK requests, the error index holds max-repetitions
+        let max_repetitions = pdu.error_index as usize;
+        let names = pdu
+            .varbinds
+            .map(|(name, _)| name.iter().unwrap().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut varbinds = Vec::new();
+        for name in names {
+            match message_type {
+                MessageType::GetBulkRequest => {
+                    let rows = mib
+                        .range((Bound::Excluded(name.clone()), Bound::Unbounded))
+                        .take(max_repetitions)
+                        .map(|(name, value)| (name.clone(), value.clone()))
+                        .collect::<Vec<_>>();
+                    if rows.len() < max_repetitions {
+                        let last = rows.last().map_or(name, |(name, _)| name.clone());
+                        varbinds.extend(rows);
+                        varbinds.push((last, StubValue::EndOfMibView));
+                    } else {
+                        varbinds.extend(rows);
+                    }
+                }
+                _ => {
+                    let value = mib.get(&name).cloned();
+                    varbinds.push((name, value.unwrap_or(StubValue::NoSuchInstance)));
+                }
+            }
+        }
+
+        let varbinds = varbinds
+            .iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    StubValue::Integer(value) => integer(TYPE_INTEGER, *value),
+                    StubValue::OctetString(value) => tlv(TYPE_OCTETSTRING, value),
+                    StubValue::Gauge(value) => unsigned(TYPE_GAUGE32, u64::from(*value)),
+                    StubValue::Counter64(value) => unsigned(TYPE_COUNTER64, *value),
+                    StubValue::Timeticks(value) => unsigned(TYPE_TIMETICKS, u64::from(*value)),
+                    StubValue::NoSuchInstance => tlv(SNMP_NOSUCHINSTANCE, &[]),
+                    StubValue::EndOfMibView => tlv(SNMP_ENDOFMIBVIEW, &[]),
+                };
+                tlv(
+                    TYPE_SEQUENCE,
+                    &[tlv(TYPE_OBJECTIDENTIFIER, oid(name).as_bytes()), value].concat(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .concat();
+
+        let response = [
+            integer(TYPE_INTEGER, i64::from(req_id)),
+            integer(TYPE_INTEGER, 0), // error-status
+            integer(TYPE_INTEGER, 0), // error-index
+            tlv(TYPE_SEQUENCE, &varbinds),
+        ]
+        .concat();
+
+        tlv(
+            TYPE_SEQUENCE,
+            &[
+                integer(TYPE_INTEGER, 1), // SNMPv2c
+                tlv(TYPE_OCTETSTRING, community),
+                tlv(MSG_RESPONSE, &response),
+            ]
+            .concat(),
+        )
+    }
+
+    /// BER encodes a value with its type and length.
+    fn tlv(ident: u8, content: &[u8]) -> Vec<u8> {
+        let mut encoded = vec![ident];
+        match content.len() {
+            length @ 0..0x80 => encoded.push(length as u8),
+            length => {
+                let bytes = length.to_be_bytes();
+                let bytes = &bytes[bytes.iter().take_while(|byte| **byte == 0).count()..];
+                encoded.push(0x80 | bytes.len() as u8);
+                encoded.extend(bytes);
+            }
+        }
+        encoded.extend(content);
+        encoded
+    }
+
+    fn integer(ident: u8, value: i64) -> Vec<u8> {
+        let bytes = value.to_be_bytes();
+        // Drop the leading bytes that only repeat the sign bit
+        let skipped = (0..7)
+            .take_while(|i| {
+                (bytes[*i] == 0 && bytes[i + 1] < 0x80)
+                    || (bytes[*i] == 0xff && bytes[i + 1] >= 0x80)
+            })
+            .count();
+        tlv(ident, &bytes[skipped..])
+    }
+
+    fn unsigned(ident: u8, value: u64) -> Vec<u8> {
+        let bytes = [[0].as_slice(), &value.to_be_bytes()].concat();
+        let skipped = (0..8)
+            .take_while(|i| bytes[*i] == 0 && bytes[i + 1] < 0x80)
+            .count();
+        tlv(ident, &bytes[skipped..])
+    }
+
+    /// Index of an `ipNetToPhysicalTable` row: ifIndex.InetAddressType.length.address...
+    fn physical_index(if_index: u64, ip_address: IpAddr) -> Vec<u64> {
+        let (address_type, bytes) = match ip_address {
+            IpAddr::V4(ip) => (INET_ADDRESS_IPV4, ip.octets().to_vec()),
+            IpAddr::V6(ip) => (INET_ADDRESS_IPV6, ip.octets().to_vec()),
+        };
+
+        [if_index, address_type, bytes.len() as u64]
+            .into_iter()
+            .chain(bytes.into_iter().map(u64::from))
+            .collect()
+    }
+
+    fn neighbour(mib: &mut Mib, if_index: u64, ip_address: &str, mac_address: [u8; 6], state: i64) {
+        let index = physical_index(if_index, ip_address.parse().unwrap());
+        mib.insert(
+            with_index(IP_NET_TO_PHYSICAL_PHYS_ADDRESS, &index),
+            StubValue::OctetString(mac_address.to_vec()),
+        );
+        mib.insert(
+            with_index(IP_NET_TO_PHYSICAL_STATE, &index),
+            StubValue::Integer(state),
+        );
+    }
+
+    fn base_mib() -> Mib {
+        let wan = [WAN_IF_INDEX];
+        BTreeMap::from([
+            (SYS_UP_TIME.to_vec(), StubValue::Timeticks(123_456)),
+            (with_index(IF_OPER_STATUS, &wan), StubValue::Integer(1)),
+            (
+                with_index(IF_HC_IN_OCTETS, &wan),
+                StubValue::Counter64(1_000_000),
+            ),
+            (
+                with_index(IF_HC_OUT_OCTETS, &wan),
+                StubValue::Counter64(500_000),
+            ),
+            (with_index(IF_HIGH_SPEED, &wan), StubValue::Gauge(1000)),
+            (
+                with_index(IP_AD_ENT_IF_INDEX, &[203, 0, 113, 10]),
+                StubValue::Integer(WAN_IF_INDEX as i64),
+            ),
+            (
+                with_index(IP_AD_ENT_IF_INDEX, &[192, 168, 1, 1]),
+                StubValue::Integer(LAN_IF_INDEX as i64),
+            ),
+        ])
+    }
+
+    #[tokio::test]
+    async fn list_devices_parses_ip_net_to_physical_table() {
+        let mut mib = base_mib();
+        let laptop = [0x02, 0, 0, 0, 0, 0x01];
+        let phone = [0x02, 0, 0, 0, 0, 0x02];
+        neighbour(&mut mib, LAN_IF_INDEX, "192.168.1.10", laptop, 1);
+        neighbour(&mut mib, LAN_IF_INDEX, "fe80::1", laptop, 2);
+        neighbour(&mut mib, LAN_IF_INDEX, "fe80::2", phone, 1);
+        // Incomplete entry
+        neighbour(
+            &mut mib,
+            LAN_IF_INDEX,
+            "192.168.1.11",
+            [0x02, 0, 0, 0, 0, 0x03],
+            7,
+        );
+        // Upstream gateway, seen on the WAN interface
+        neighbour(
+            &mut mib,
+            WAN_IF_INDEX,
+            "203.0.113.1",
+            [0x02, 0, 0, 0, 0, 0x04],
+            1,
+        );
+        let agent = StubAgent::start(mib).await;
+
+        let mut devices = agent.router_api().await.list_devices().await.unwrap();
+        devices.sort_by_key(|device| device.mac_address);
+
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].mac_address, MacAddress::new(laptop));
+        assert_eq!(
+            devices[0].last_known_ip,
+            "192.168.1.10".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(devices[0].other_ips, ["fe80::1".parse::<IpAddr>().unwrap()]);
+        assert_eq!(devices[1].mac_address, MacAddress::new(phone));
+        assert_eq!(
+            devices[1].last_known_ip,
+            "fe80::2".parse::<IpAddr>().unwrap()
+        );
+        assert!(devices[1].other_ips.is_empty());
+    }
+
+    #[tokio::test]
+    async fn wan_stats_computes_bandwidth_from_counter_delta() {
+        let agent = StubAgent::start(base_mib()).await;
+        let router_api = agent.router_api().await;
+        let wan = [WAN_IF_INDEX];
+
+        let first = router_api.wan_stats().await.unwrap();
+        assert_eq!(first.download.current_bandwidth, 0);
+        assert_eq!(first.download.total_since_last_reboot, 1_000_000);
+        assert_eq!(first.download.max_bandwidth, 1_000_000);
+
+        agent.set(
+            with_index(IF_HC_IN_OCTETS, &wan),
+            StubValue::Counter64(1_125_000),
+        );
+        agent.set(
+            with_index(IF_HC_OUT_OCTETS, &wan),
+            StubValue::Counter64(750_000),
+        );
+
+        // Samples taken less than a second apart are averaged over a second
+        let second = router_api.wan_stats().await.unwrap();
+        assert_eq!(second.download.current_bandwidth, 1000);
+        assert_eq!(second.upload.current_bandwidth, 2000);
+        assert_eq!(second.download.total_since_last_reboot, 1_125_000);
+        assert_eq!(second.upload.total_since_last_reboot, 750_000);
+        assert_eq!(second.download.packets_lost, 0);
+    }
+
+    #[tokio::test]
+    async fn wan_connectivity_reads_sys_up_time() {
+        let agent = StubAgent::start(base_mib()).await;
+
+        let connectivity = agent.router_api().await.wan_connectivity().await.unwrap();
+
+        assert_eq!(
+            connectivity.uptime,
+            Some(chrono::Duration::milliseconds(1_234_560))
+        );
+        assert_eq!(connectivity.status, WanStatus::Up);
+        assert_eq!(connectivity.ipv4, Ipv4Addr::new(203, 0, 113, 10));
+        assert_eq!(connectivity.ipv6, None);
+    }
+}
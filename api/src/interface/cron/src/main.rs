@@ -1,11 +1,18 @@
-use std::{sync::Arc, time::Instant};
+use std::time::{Duration, Instant};
 
-use common::{CONFIG, RouterKind};
-use domain::{PeriodicUseCase, SyncDevicesUseCase};
-use repositories::{PostgresDevicesRepository, PostgresUWP};
-use router_api::bouygues::BboxRouterApi;
-use sqlx::PgPool;
-use tokio::sync::Mutex;
+use common::CONFIG;
+use domain::{
+    ExpireGuestNetworkUseCase, PeriodicUseCase, RecordDataUsageUseCase, RecordDeviceTrafficUseCase,
+    RecordWanOutagesUseCase, RecordWanStatsUseCase, SyncDevicesUseCase, TakeConfigSnapshotsUseCase,
+};
+use entities::WanStatsBucket;
+use repositories::{
+    PostgresConfigSnapshotsRepository, PostgresDataUsageRepository, PostgresDeviceAccessRepository,
+    PostgresDeviceConnectionsRepository, PostgresDeviceTrafficRepository,
+    PostgresDevicesRepository, PostgresGuestNetworkRepository, PostgresIpHistoryRepository,
+    PostgresPresenceEventsRepository, PostgresUWP, PostgresWanOutagesRepository,
+    PostgresWanStatsRepository,
+};
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -36,62 +43,140 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("Starting cron service");
 
-    let router_api = Arc::new(match CONFIG.router_api.kind {
-        RouterKind::Bbox => {
-            BboxRouterApi::new(
-                CONFIG.router_api.base_url.clone(),
-                CONFIG.router_api.password.clone(),
-            )
-            .await?
-        }
-    });
-
-    let pg_pool = Arc::new(Mutex::new(
-        PgPool::connect(CONFIG.database.url.as_str()).await?,
-    ));
+    let router_api = router_api::from_config(&CONFIG.router_api).await?;
 
-    let unit_of_work_provider = PostgresUWP::new(pg_pool);
+    let unit_of_work_provider = PostgresUWP::connect(&CONFIG.database).await?;
 
-    let mut jobs: Vec<CronJob> = vec![CronJob::new(
-        "Sync Devices",
-        Box::new(
-            SyncDevicesUseCase::<PostgresDevicesRepository, PostgresUWP>::new(
+    let mut jobs: Vec<CronJob> = vec![
+        CronJob::new(
+            "Sync Devices",
+            Box::new(SyncDevicesUseCase::<
+                PostgresDevicesRepository,
+                PostgresDeviceAccessRepository,
+                PostgresDeviceConnectionsRepository,
+                PostgresPresenceEventsRepository,
+                PostgresIpHistoryRepository,
+                PostgresUWP,
+            >::new(
+                unit_of_work_provider.clone(), router_api.clone()
+            )),
+        ),
+        CronJob::new(
+            "Record WAN Outages",
+            Box::new(RecordWanOutagesUseCase::<
+                PostgresWanOutagesRepository,
+                PostgresUWP,
+            >::new(
+                unit_of_work_provider.clone(),
+                router_api.clone(),
+                Duration::from_secs(CONFIG.scanning.wan_status_delay),
+            )),
+        ),
+        CronJob::new(
+            "Record WAN Stats",
+            Box::new(RecordWanStatsUseCase::<
+                PostgresWanStatsRepository,
+                PostgresUWP,
+            >::new(
+                unit_of_work_provider.clone(),
+                router_api.clone(),
+                Duration::from_secs(CONFIG.wan_history.interval),
+                chrono::Duration::days(CONFIG.wan_history.samples_retention),
+                vec![
+                    (
+                        WanStatsBucket::Minute,
+                        chrono::Duration::days(CONFIG.wan_history.minute_retention),
+                    ),
+                    (
+                        WanStatsBucket::Hour,
+                        chrono::Duration::days(CONFIG.wan_history.hour_retention),
+                    ),
+                    (
+                        WanStatsBucket::Day,
+                        chrono::Duration::days(CONFIG.wan_history.day_retention),
+                    ),
+                ],
+            )),
+        ),
+        CronJob::new(
+            "Record Data Usage",
+            Box::new(RecordDataUsageUseCase::<
+                PostgresDataUsageRepository,
+                PostgresUWP,
+            >::new(
+                unit_of_work_provider.clone(),
+                router_api.clone(),
+                Duration::from_secs(CONFIG.data_usage.interval),
+                CONFIG.data_usage.reset_day,
+                CONFIG.data_usage.quota,
+                CONFIG.data_usage.alert_thresholds.to_vec(),
+            )),
+        ),
+        CronJob::new(
+            "Record Device Traffic",
+            Box::new(RecordDeviceTrafficUseCase::<
+                PostgresDevicesRepository,
+                PostgresDeviceTrafficRepository,
+                PostgresUWP,
+            >::new(
+                unit_of_work_provider.clone(),
+                router_api.clone(),
+                Duration::from_secs(CONFIG.scanning.device_traffic_delay),
+            )),
+        ),
+        CronJob::new(
+            "Expire Guest Network",
+            Box::new(ExpireGuestNetworkUseCase::<
+                PostgresGuestNetworkRepository,
+                PostgresUWP,
+            >::new(
+                unit_of_work_provider.clone(), router_api.clone()
+            )),
+        ),
+        CronJob::new(
+            "Take Config Snapshots",
+            Box::new(TakeConfigSnapshotsUseCase::<
+                PostgresConfigSnapshotsRepository,
+                PostgresUWP,
+            >::new(
                 unit_of_work_provider,
                 router_api,
-            ),
+                Duration::from_secs(CONFIG.config_snapshots.interval),
+                chrono::Duration::days(CONFIG.config_snapshots.retention),
+            )),
         ),
-    )];
+    ];
 
     loop {
         let now = Instant::now();
-        let mut next_execution = jobs[0]
-            .next_execution
-            .ok_or_else(|| anyhow::anyhow!("No next execution found"))?;
 
         for job in &mut jobs {
-            if let Some(next_exec) = job.next_execution {
-                if next_exec <= now {
-                    info!("Executing cron job {}", job.name);
-                    job.job.execute().await;
-                    let next_exec_optional = job.job.next_execution();
-
-                    if let Some(next_exec) = next_exec_optional {
-                        info!(
-                            "Job finished, next execution in {}s",
-                            (next_exec - now).as_secs()
-                        );
-                        if next_exec < next_execution {
-                            next_execution = next_exec;
-                        }
-                    } else {
-                        info!("Job finished, no next execution");
-                    }
+            if let Some(next_exec) = job.next_execution
+                && next_exec <= now
+            {
+                info!("Executing cron job {}", job.name);
+                job.job.execute().await;
+                let next_exec_optional = job.job.next_execution();
 
-                    job.next_execution = next_exec_optional;
+                if let Some(next_exec) = next_exec_optional {
+                    info!(
+                        "Job finished, next execution in {}s",
+                        (next_exec - now).as_secs()
+                    );
+                } else {
+                    info!("Job finished, no next execution");
                 }
+
+                job.next_execution = next_exec_optional;
             }
         }
 
+        let next_execution = jobs
+            .iter()
+            .filter_map(|job| job.next_execution)
+            .min()
+            .ok_or_else(|| anyhow::anyhow!("No next execution found"))?;
+
         tokio::time::sleep_until(next_execution.into()).await;
     }
 }
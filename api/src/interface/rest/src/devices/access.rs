@@ -0,0 +1,55 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use domain::{DeviceAccessError, UpdateDeviceAccess};
+use entities::DeviceAccess;
+use mac_address::MacAddress;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    devices::Devices,
+    extractors::ValidJson,
+    response::{ApiError, ApiResponse, ApiResult},
+};
+
+impl From<DeviceAccessError> for ApiError {
+    fn from(err: DeviceAccessError) -> Self {
+        match err {
+            DeviceAccessError::DeviceNotFound => {
+                ApiError::new("device-not-found", err.to_string(), StatusCode::NOT_FOUND)
+            }
+            DeviceAccessError::InvalidSchedule => {
+                ApiError::new("invalid-schedule", err.to_string(), StatusCode::BAD_REQUEST)
+            }
+            DeviceAccessError::RouterApiError(err) => err.into(),
+            DeviceAccessError::DatabaseError(err) => err.into(),
+        }
+    }
+}
+
+route!(
+    method = GET,
+    group = Devices,
+    path = "/{mac_address:MacAddress}/access",
+
+    #[instrument(skip(state))]
+    async fetch_access(state: State<PostgresAppState>) -> ApiResult<DeviceAccess> {
+        Ok(state.fetch_device_access.execute(mac_address).await.map(|access| {
+            ApiResponse::new(access, StatusCode::OK)
+        })?)
+    }
+);
+
+route!(
+    method = PUT,
+    group = Devices,
+    path = "/{mac_address:MacAddress}/access",
+    body = ValidJson<UpdateDeviceAccess>,
+
+    #[instrument(skip(state))]
+    async update_access(state: State<PostgresAppState>) -> ApiResult<DeviceAccess> {
+        Ok(state.update_device_access.execute(mac_address, body.0).await.map(|access| {
+            ApiResponse::new(access, StatusCode::OK)
+        })?)
+    }
+);
@@ -0,0 +1,65 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use domain::{DeviceError, UpdateDevice};
+use entities::{Device, FullDevice};
+use mac_address::MacAddress;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    devices::Devices,
+    extractors::ValidJson,
+    response::{ApiError, ApiResponse, ApiResult},
+};
+
+impl From<DeviceError> for ApiError {
+    fn from(err: DeviceError) -> Self {
+        match err {
+            DeviceError::DeviceNotFound => {
+                ApiError::new("device-not-found", err.to_string(), StatusCode::NOT_FOUND)
+            }
+            DeviceError::RouterApiError(err) => err.into(),
+            DeviceError::DatabaseError(err) => err.into(),
+        }
+    }
+}
+
+route!(
+    method = GET,
+    group = Devices,
+    path = "/{mac_address:MacAddress}",
+
+    #[instrument(skip(state))]
+    async fetch_device(state: State<PostgresAppState>) -> ApiResult<FullDevice> {
+        Ok(state.fetch_device.execute(mac_address).await.map(|device| {
+            ApiResponse::new(device, StatusCode::OK)
+        })?)
+    }
+);
+
+route!(
+    method = PATCH,
+    group = Devices,
+    path = "/{mac_address:MacAddress}",
+    body = ValidJson<UpdateDevice>,
+
+    #[instrument(skip(state, body))]
+    async update_device(state: State<PostgresAppState>) -> ApiResult<Device> {
+        Ok(state.update_device.execute(mac_address, body.0).await.map(|device| {
+            ApiResponse::new(device, StatusCode::OK)
+        })?)
+    }
+);
+
+route!(
+    method = DELETE,
+    group = Devices,
+    path = "/{mac_address:MacAddress}",
+
+    #[instrument(skip(state))]
+    async delete_device(state: State<PostgresAppState>) -> ApiResult<()> {
+        Ok(state.delete_device.execute(mac_address).await.map(|_| {
+            ApiResponse::new((), StatusCode::OK)
+        })?)
+    }
+);
@@ -1,6 +1,9 @@
 use axum::{extract::State, http::StatusCode};
 use axum_distributed_routing::route;
-use entities::{FullDevice, Pagination};
+use chrono::{DateTime, Utc};
+use entities::{
+    Cursor, DeviceFilter, DeviceQuery, DeviceSortField, FullDevice, ServiceKind, Sort, SortOrder,
+};
 use serde::Deserialize;
 use tracing::instrument;
 use validator::Validate;
@@ -9,16 +12,52 @@ use crate::{
     PostgresAppState,
     devices::Devices,
     extractors::ValidQuery,
-    response::{ApiResponse, ApiResult},
+    pagination::paging,
+    response::{ApiError, ApiResponse, ApiResult},
 };
 
 #[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
 pub struct ListDeviceQuery {
-    #[serde(flatten, deserialize_with = "entities::deserialize_option_pagination")]
-    pub pagination: Option<Pagination>,
+    #[validate(range(min = 1))]
+    pub page: Option<u32>, // defaults to the first page when a limit is set
+    pub after: Option<Cursor>, // keyset pagination, instead of a page
+    #[validate(range(min = 1, max = 1000))]
+    pub limit: Option<u32>,
 
     #[serde(default)]
     pub full: bool,
+
+    pub online: Option<bool>,
+    #[validate(length(min = 1, max = 255))]
+    pub search: Option<String>,
+    pub last_seen_from: Option<DateTime<Utc>>,
+    pub last_seen_to: Option<DateTime<Utc>>,
+    pub service_kind: Option<ServiceKind>,
+
+    #[serde(default)]
+    pub sort: DeviceSortField,
+    #[serde(default)]
+    pub order: SortOrder,
+}
+
+impl ListDeviceQuery {
+    fn to_device_query(&self) -> Result<DeviceQuery, ApiError> {
+        Ok(DeviceQuery {
+            filter: DeviceFilter {
+                is_online: self.online,
+                search: self.search.clone(),
+                last_seen_from: self.last_seen_from,
+                last_seen_to: self.last_seen_to,
+                service_kind: self.service_kind,
+            },
+            sort: Sort {
+                field: self.sort,
+                order: self.order,
+            },
+            paging: paging(self.page, self.after.clone(), self.limit, self.sort)?,
+        })
+    }
 }
 
 route!(
@@ -29,11 +68,12 @@ route!(
 
     #[instrument(skip(state, query), fields(
         full = %query.full,
-        pagination.page = query.pagination.map(|p| p.page),
-        pagination.limit = query.pagination.map(|p| p.limit),
+        pagination.page = query.page,
+        pagination.after = ?query.after,
+        pagination.limit = query.limit,
     ))]
     async fetch_devices(state: State<PostgresAppState>) -> ApiResult<Vec<FullDevice>> {
-        Ok(ApiResponse::new(match state.list_devices.execute(query.pagination, query.full).await {
+        Ok(ApiResponse::paged(match state.list_devices.execute(query.to_device_query()?, query.full).await {
             Ok(devices) => devices,
             Err(err) => return Err(err.into()),
         }, StatusCode::OK))
@@ -4,4 +4,9 @@ use crate::{PostgresAppState, RestV1};
 
 route_group!(pub Devices, PostgresAppState, RestV1, "/devices");
 
+mod access;
+mod details;
 pub mod list;
+mod presence;
+mod reservation;
+mod usage;
@@ -0,0 +1,68 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use chrono::{DateTime, Utc};
+use domain::ListDevicePresenceError;
+use entities::PresenceHistory;
+use mac_address::MacAddress;
+use serde::Deserialize;
+use tracing::instrument;
+use validator::Validate;
+
+use crate::{
+    PostgresAppState,
+    devices::Devices,
+    extractors::ValidQuery,
+    response::{ApiError, ApiResponse, ApiResult},
+};
+
+impl From<ListDevicePresenceError> for ApiError {
+    fn from(err: ListDevicePresenceError) -> Self {
+        match err {
+            ListDevicePresenceError::InvalidRange => {
+                ApiError::new("invalid-range", err.to_string(), StatusCode::BAD_REQUEST)
+            }
+            ListDevicePresenceError::DeviceNotFound => {
+                ApiError::new("device-not-found", err.to_string(), StatusCode::NOT_FOUND)
+            }
+            ListDevicePresenceError::DatabaseError(err) => err.into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct PresenceQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+route!(
+    method = GET,
+    group = Devices,
+    path = "/presence",
+    query = ValidQuery<PresenceQuery>,
+
+    #[instrument(skip(state, query), fields(from = ?query.from, to = ?query.to))]
+    async list_presence(state: State<PostgresAppState>) -> ApiResult<PresenceHistory> {
+        Ok(state
+            .list_device_presence
+            .execute(None, query.from, query.to)
+            .await
+            .map(|presence| ApiResponse::new(presence, StatusCode::OK))?)
+    }
+);
+
+route!(
+    method = GET,
+    group = Devices,
+    path = "/{mac_address:MacAddress}/presence",
+    query = ValidQuery<PresenceQuery>,
+
+    #[instrument(skip(state, query), fields(from = ?query.from, to = ?query.to))]
+    async fetch_presence(state: State<PostgresAppState>) -> ApiResult<PresenceHistory> {
+        Ok(state
+            .list_device_presence
+            .execute(Some(mac_address), query.from, query.to)
+            .await
+            .map(|presence| ApiResponse::new(presence, StatusCode::OK))?)
+    }
+);
@@ -0,0 +1,78 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use domain::{DeviceReservationError, ReserveIp};
+use entities::StaticLease;
+use mac_address::MacAddress;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    devices::Devices,
+    extractors::ValidJson,
+    response::{ApiError, ApiResponse, ApiResult},
+};
+
+impl From<DeviceReservationError> for ApiError {
+    fn from(err: DeviceReservationError) -> Self {
+        match err {
+            DeviceReservationError::DeviceNotFound => {
+                ApiError::new("device-not-found", err.to_string(), StatusCode::NOT_FOUND)
+            }
+            DeviceReservationError::ReservationNotFound => ApiError::new(
+                "reservation-not-found",
+                err.to_string(),
+                StatusCode::NOT_FOUND,
+            ),
+            DeviceReservationError::AddressOutsideLan => ApiError::new(
+                "address-outside-lan",
+                err.to_string(),
+                StatusCode::BAD_REQUEST,
+            ),
+            DeviceReservationError::AddressTaken => {
+                ApiError::new("address-taken", err.to_string(), StatusCode::CONFLICT)
+            }
+            DeviceReservationError::RouterApiError(err) => err.into(),
+            DeviceReservationError::DatabaseError(err) => err.into(),
+        }
+    }
+}
+
+route!(
+    method = GET,
+    group = Devices,
+    path = "/{mac_address:MacAddress}/reservation",
+
+    #[instrument(skip(state))]
+    async fetch_reservation(state: State<PostgresAppState>) -> ApiResult<StaticLease> {
+        Ok(state.fetch_device_reservation.execute(mac_address).await.map(|lease| {
+            ApiResponse::new(lease, StatusCode::OK)
+        })?)
+    }
+);
+
+route!(
+    method = PUT,
+    group = Devices,
+    path = "/{mac_address:MacAddress}/reservation",
+    body = ValidJson<ReserveIp>,
+
+    #[instrument(skip(state))]
+    async reserve_ip(state: State<PostgresAppState>) -> ApiResult<StaticLease> {
+        Ok(state.reserve_device_ip.execute(mac_address, body.0).await.map(|lease| {
+            ApiResponse::new(lease, StatusCode::OK)
+        })?)
+    }
+);
+
+route!(
+    method = DELETE,
+    group = Devices,
+    path = "/{mac_address:MacAddress}/reservation",
+
+    #[instrument(skip(state))]
+    async release_ip(state: State<PostgresAppState>) -> ApiResult<()> {
+        Ok(state.release_device_ip.execute(mac_address).await.map(|_| {
+            ApiResponse::new((), StatusCode::OK)
+        })?)
+    }
+);
@@ -0,0 +1,53 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use chrono::{DateTime, Utc};
+use domain::DeviceTrafficError;
+use entities::{DeviceUsageHistory, UsageInterval};
+use mac_address::MacAddress;
+use serde::Deserialize;
+use tracing::instrument;
+use validator::Validate;
+
+use crate::{
+    PostgresAppState,
+    devices::Devices,
+    extractors::ValidQuery,
+    response::{ApiError, ApiResponse, ApiResult},
+};
+
+impl From<DeviceTrafficError> for ApiError {
+    fn from(err: DeviceTrafficError) -> Self {
+        match err {
+            DeviceTrafficError::InvalidRange => {
+                ApiError::new("invalid-range", err.to_string(), StatusCode::BAD_REQUEST)
+            }
+            DeviceTrafficError::DeviceNotFound => {
+                ApiError::new("device-not-found", err.to_string(), StatusCode::NOT_FOUND)
+            }
+            DeviceTrafficError::DatabaseError(err) => err.into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct DeviceUsageQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub interval: Option<UsageInterval>, // hours for ranges up to two days, days otherwise
+}
+
+route!(
+    method = GET,
+    group = Devices,
+    path = "/{mac_address:MacAddress}/usage",
+    query = ValidQuery<DeviceUsageQuery>,
+
+    #[instrument(skip(state, query), fields(from = ?query.from, to = ?query.to))]
+    async fetch_usage(state: State<PostgresAppState>) -> ApiResult<DeviceUsageHistory> {
+        Ok(state
+            .fetch_device_usage
+            .execute(mac_address, query.from, query.to, query.interval)
+            .await
+            .map(|usage| ApiResponse::new(usage, StatusCode::OK))?)
+    }
+);
@@ -17,14 +17,9 @@ where
 {
     type Rejection = ApiError;
 
-    fn from_request(
-        req: Request,
-        state: &S,
-    ) -> impl Future<Output = Result<Self, Self::Rejection>> + Send {
-        async {
-            let Json(value) = Json::<T>::from_request(req, state).await?;
-            value.validate()?;
-            Ok(Self(value))
-        }
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state).await?;
+        value.validate()?;
+        Ok(Self(value))
     }
 }
@@ -19,15 +19,13 @@ where
 {
     type Rejection = ApiError;
 
-    fn from_request_parts(
+    async fn from_request_parts(
         parts: &mut http::request::Parts,
         state: &S,
-    ) -> impl Future<Output = Result<Self, Self::Rejection>> + Send {
-        async {
-            let Query(value) = Query::<T>::from_request_parts(parts, state).await?;
-            value.validate()?;
-            Ok(Self(value))
-        }
+    ) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state).await?;
+        value.validate()?;
+        Ok(Self(value))
     }
 }
 
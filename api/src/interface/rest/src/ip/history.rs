@@ -0,0 +1,38 @@
+use std::net::IpAddr;
+
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use chrono::{DateTime, Utc};
+use entities::IpHistory;
+use serde::Deserialize;
+use tracing::instrument;
+use validator::Validate;
+
+use crate::{
+    PostgresAppState,
+    extractors::ValidQuery,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::Ip;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct IpHistoryQuery {
+    pub at: Option<DateTime<Utc>>,
+}
+
+route!(
+    method = GET,
+    group = Ip,
+    path = "/{ip_address:IpAddr}/history",
+    query = ValidQuery<IpHistoryQuery>,
+
+    #[instrument(skip(state, query), fields(at = ?query.at))]
+    async fetch_ip_history(state: State<PostgresAppState>) -> ApiResult<IpHistory> {
+        Ok(state
+            .fetch_ip_history
+            .execute(ip_address, query.at)
+            .await
+            .map(|history| ApiResponse::new(history, StatusCode::OK))?)
+    }
+);
@@ -0,0 +1,7 @@
+use axum_distributed_routing::route_group;
+
+use crate::{PostgresAppState, RestV1};
+
+route_group!(Ip, PostgresAppState, RestV1, "/ip");
+
+mod history;
@@ -1,5 +1,4 @@
 use axum::http::Request;
-use std::sync::Arc;
 use tower_http::{
     request_id::{MakeRequestId, PropagateRequestIdLayer, RequestId, SetRequestIdLayer},
     trace::TraceLayer,
@@ -9,34 +8,107 @@ use tracing_subscriber::util::SubscriberInitExt;
 use uuid::Uuid;
 
 use axum_distributed_routing::{create_router, route_group};
-use common::{CONFIG, RouterKind};
+use common::CONFIG;
 use domain::{
-    CreateServiceUseCase, FetchNetworkStatusUseCase, GenerateInstallScriptUseCase,
-    ListDevicesUseCase, ListServiceTemplatesUseCase,
+    CreatePortForwardUseCase, CreateServiceUseCase, DeleteDeviceUseCase, DeletePortForwardUseCase,
+    DiffConfigSnapshotsUseCase, FetchConfigSnapshotUseCase, FetchDataUsageUseCase,
+    FetchDeviceAccessUseCase, FetchDeviceReservationUseCase, FetchDeviceUsageUseCase,
+    FetchDeviceUseCase, FetchGuestNetworkUseCase, FetchIpHistoryUseCase, FetchNetworkStatusUseCase,
+    FetchRouterInfoUseCase, GenerateGuestNetworkQrUseCase, GenerateInstallScriptUseCase,
+    ListConfigSnapshotsUseCase, ListDevicePresenceUseCase, ListDevicesUseCase,
+    ListPortForwardsUseCase, ListServiceTemplatesUseCase, ListServicesUseCase,
+    ListTopTalkersUseCase, ListWanHistoryUseCase, ListWanOutagesUseCase, ListWifiRadiosUseCase,
+    RebootRouterUseCase, ReleaseDeviceIpUseCase, ReserveDeviceIpUseCase, UpdateDeviceAccessUseCase,
+    UpdateDeviceUseCase, UpdateGuestNetworkUseCase, UpdatePortForwardUseCase,
 };
-use ports::repositories::{DevicesRepository, ServicesRepository, UnitOfWorkProvider};
-use repositories::{PostgresDevicesRepository, PostgresServicesRepository, PostgresUWP};
-use router_api::bouygues::BboxRouterApi;
-use sqlx::PgPool;
-use tokio::{net::TcpListener, sync::Mutex};
+use ports::repositories::{
+    ConfigSnapshotsRepository, DataUsageRepository, DeviceAccessRepository,
+    DeviceConnectionsRepository, DeviceTrafficRepository, DevicesRepository,
+    GuestNetworkRepository, IpHistoryRepository, PortForwardLinksRepository,
+    PresenceEventsRepository, ServicesRepository, UnitOfWorkProvider, WanOutagesRepository,
+    WanStatsRepository,
+};
+use repositories::{
+    PostgresConfigSnapshotsRepository, PostgresDataUsageRepository, PostgresDeviceAccessRepository,
+    PostgresDeviceConnectionsRepository, PostgresDeviceTrafficRepository,
+    PostgresDevicesRepository, PostgresGuestNetworkRepository, PostgresIpHistoryRepository,
+    PostgresPortForwardLinksRepository, PostgresPresenceEventsRepository,
+    PostgresServicesRepository, PostgresUWP, PostgresWanOutagesRepository,
+    PostgresWanStatsRepository,
+};
+use tokio::net::TcpListener;
 use tracing_subscriber::layer::SubscriberExt;
 
 #[derive(Clone)]
-pub struct AppState<DR, SR, UWP>
+pub struct AppState<DR, SR, OR, LR, AR, CR, GR, PR, TR, WR, UR, ER, IR, UWP>
 where
     DR: DevicesRepository<UWP>,
     SR: ServicesRepository<UWP>,
+    OR: WanOutagesRepository<UWP>,
+    LR: PortForwardLinksRepository<UWP>,
+    AR: DeviceAccessRepository<UWP>,
+    CR: DeviceConnectionsRepository<UWP>,
+    GR: GuestNetworkRepository<UWP>,
+    PR: ConfigSnapshotsRepository<UWP>,
+    TR: DeviceTrafficRepository<UWP>,
+    WR: WanStatsRepository<UWP>,
+    UR: DataUsageRepository<UWP>,
+    ER: PresenceEventsRepository<UWP>,
+    IR: IpHistoryRepository<UWP>,
     UWP: UnitOfWorkProvider,
 {
-    list_devices: ListDevicesUseCase<DR, SR, UWP>,
+    list_devices: ListDevicesUseCase<DR, SR, AR, CR, UWP>,
+    fetch_device: FetchDeviceUseCase<DR, SR, AR, CR, UWP>,
+    update_device: UpdateDeviceUseCase<DR, UWP>,
+    delete_device: DeleteDeviceUseCase<DR, UWP>,
+    fetch_device_access: FetchDeviceAccessUseCase<DR, AR, UWP>,
+    update_device_access: UpdateDeviceAccessUseCase<DR, AR, UWP>,
+    fetch_device_usage: FetchDeviceUsageUseCase<DR, TR, UWP>,
+    list_device_presence: ListDevicePresenceUseCase<DR, ER, UWP>,
+    fetch_ip_history: FetchIpHistoryUseCase<IR, UWP>,
+    fetch_device_reservation: FetchDeviceReservationUseCase,
+    reserve_device_ip: ReserveDeviceIpUseCase<DR, UWP>,
+    release_device_ip: ReleaseDeviceIpUseCase,
     fetch_network_status: FetchNetworkStatusUseCase,
+    list_wifi_radios: ListWifiRadiosUseCase,
+    list_top_talkers: ListTopTalkersUseCase<TR, UWP>,
+    fetch_guest_network: FetchGuestNetworkUseCase<GR, UWP>,
+    update_guest_network: UpdateGuestNetworkUseCase<GR, UWP>,
+    generate_guest_network_qr: GenerateGuestNetworkQrUseCase,
+    fetch_router_info: FetchRouterInfoUseCase,
+    reboot_router: RebootRouterUseCase,
+    list_config_snapshots: ListConfigSnapshotsUseCase<PR, UWP>,
+    fetch_config_snapshot: FetchConfigSnapshotUseCase<PR, UWP>,
+    diff_config_snapshots: DiffConfigSnapshotsUseCase<PR, UWP>,
     list_service_templates: ListServiceTemplatesUseCase,
+    list_services: ListServicesUseCase<SR, UWP>,
     create_service: CreateServiceUseCase<SR, UWP>,
     generate_install_script: GenerateInstallScriptUseCase<SR, UWP>,
+    list_wan_outages: ListWanOutagesUseCase<OR, UWP>,
+    list_wan_history: ListWanHistoryUseCase<WR, UWP>,
+    fetch_data_usage: FetchDataUsageUseCase<UR, UWP>,
+    list_port_forwards: ListPortForwardsUseCase<LR, UWP>,
+    create_port_forward: CreatePortForwardUseCase<LR, SR, DR, UWP>,
+    update_port_forward: UpdatePortForwardUseCase<LR, SR, DR, UWP>,
+    delete_port_forward: DeletePortForwardUseCase<LR, UWP>,
 }
 
-type PostgresAppState =
-    AppState<PostgresDevicesRepository, PostgresServicesRepository, PostgresUWP>;
+type PostgresAppState = AppState<
+    PostgresDevicesRepository,
+    PostgresServicesRepository,
+    PostgresWanOutagesRepository,
+    PostgresPortForwardLinksRepository,
+    PostgresDeviceAccessRepository,
+    PostgresDeviceConnectionsRepository,
+    PostgresGuestNetworkRepository,
+    PostgresConfigSnapshotsRepository,
+    PostgresDeviceTrafficRepository,
+    PostgresWanStatsRepository,
+    PostgresDataUsageRepository,
+    PostgresPresenceEventsRepository,
+    PostgresIpHistoryRepository,
+    PostgresUWP,
+>;
 
 route_group!(pub Base, PostgresAppState);
 route_group!(pub RestV1, PostgresAppState, Base, "/api/v1");
@@ -44,8 +116,11 @@ route_group!(pub RestV1, PostgresAppState, Base, "/api/v1");
 mod agents;
 mod devices;
 mod extractors;
+mod ip;
 mod network;
+mod pagination;
 mod response;
+mod router;
 mod service_templates;
 mod services;
 
@@ -71,28 +146,70 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("Starting REST server");
 
-    let router_api = Arc::new(match CONFIG.router_api.kind {
-        RouterKind::Bbox => {
-            BboxRouterApi::new(
-                CONFIG.router_api.base_url.clone(),
-                CONFIG.router_api.password.clone(),
-            )
-            .await?
-        }
-    });
-
-    let pg_pool = Arc::new(Mutex::new(
-        PgPool::connect(CONFIG.database.url.as_str()).await?,
-    ));
+    let router_api = router_api::from_config(&CONFIG.router_api).await?;
 
-    let unit_of_work_provider = PostgresUWP::new(pg_pool);
+    let unit_of_work_provider = PostgresUWP::connect(&CONFIG.database).await?;
 
     let app_state = AppState {
         list_devices: ListDevicesUseCase::new(unit_of_work_provider.clone()),
-        fetch_network_status: FetchNetworkStatusUseCase::new(router_api),
+        fetch_device: FetchDeviceUseCase::new(unit_of_work_provider.clone()),
+        update_device: UpdateDeviceUseCase::new(unit_of_work_provider.clone(), router_api.clone()),
+        delete_device: DeleteDeviceUseCase::new(unit_of_work_provider.clone()),
+        fetch_device_access: FetchDeviceAccessUseCase::new(unit_of_work_provider.clone()),
+        update_device_access: UpdateDeviceAccessUseCase::new(
+            unit_of_work_provider.clone(),
+            router_api.clone(),
+        ),
+        fetch_device_usage: FetchDeviceUsageUseCase::new(unit_of_work_provider.clone()),
+        list_device_presence: ListDevicePresenceUseCase::new(unit_of_work_provider.clone()),
+        fetch_ip_history: FetchIpHistoryUseCase::new(unit_of_work_provider.clone()),
+        fetch_device_reservation: FetchDeviceReservationUseCase::new(router_api.clone()),
+        reserve_device_ip: ReserveDeviceIpUseCase::new(
+            unit_of_work_provider.clone(),
+            router_api.clone(),
+        ),
+        release_device_ip: ReleaseDeviceIpUseCase::new(router_api.clone()),
+        fetch_network_status: FetchNetworkStatusUseCase::new(router_api.clone()),
+        list_wifi_radios: ListWifiRadiosUseCase::new(router_api.clone()),
+        list_top_talkers: ListTopTalkersUseCase::new(unit_of_work_provider.clone()),
+        fetch_guest_network: FetchGuestNetworkUseCase::new(
+            unit_of_work_provider.clone(),
+            router_api.clone(),
+        ),
+        update_guest_network: UpdateGuestNetworkUseCase::new(
+            unit_of_work_provider.clone(),
+            router_api.clone(),
+        ),
+        generate_guest_network_qr: GenerateGuestNetworkQrUseCase::new(router_api.clone()),
+        fetch_router_info: FetchRouterInfoUseCase::new(router_api.clone()),
+        reboot_router: RebootRouterUseCase::new(router_api.clone()),
+        list_config_snapshots: ListConfigSnapshotsUseCase::new(unit_of_work_provider.clone()),
+        fetch_config_snapshot: FetchConfigSnapshotUseCase::new(unit_of_work_provider.clone()),
+        diff_config_snapshots: DiffConfigSnapshotsUseCase::new(unit_of_work_provider.clone()),
         list_service_templates: ListServiceTemplatesUseCase,
+        list_services: ListServicesUseCase::new(unit_of_work_provider.clone()),
         create_service: CreateServiceUseCase::new(unit_of_work_provider.clone()),
         generate_install_script: GenerateInstallScriptUseCase::new(unit_of_work_provider.clone()),
+        list_wan_outages: ListWanOutagesUseCase::new(unit_of_work_provider.clone()),
+        list_wan_history: ListWanHistoryUseCase::new(unit_of_work_provider.clone()),
+        fetch_data_usage: FetchDataUsageUseCase::new(
+            unit_of_work_provider.clone(),
+            CONFIG.data_usage.reset_day,
+            CONFIG.data_usage.quota,
+        ),
+        list_port_forwards: ListPortForwardsUseCase::new(
+            unit_of_work_provider.clone(),
+            router_api.clone(),
+        ),
+        create_port_forward: CreatePortForwardUseCase::new(
+            unit_of_work_provider.clone(),
+            router_api.clone(),
+        ),
+        update_port_forward: UpdatePortForwardUseCase::new(
+            unit_of_work_provider.clone(),
+            router_api.clone(),
+        ),
+        delete_port_forward: DeletePortForwardUseCase::new(unit_of_work_provider, router_api),
     };
 
     let router = create_router!(Base)
@@ -111,7 +228,7 @@ async fn main() -> anyhow::Result<()> {
                     .extensions()
                     .get::<RequestId>()
                     .map(|id| id.header_value().to_str().unwrap())
-                    .unwrap_or_else(|| "unknown".into());
+                    .unwrap_or("unknown");
 
                 // Create a span with all the desired fields
                 span!(
@@ -129,9 +246,8 @@ async fn main() -> anyhow::Result<()> {
     Ok(axum::serve(
         TcpListener::bind((CONFIG.api.listen_address, CONFIG.api.listen_port))
             .await
-            .map(|listener| {
+            .inspect(|listener| {
                 info!("Listening on {}", listener.local_addr().unwrap());
-                listener
             })?,
         router,
     )
@@ -0,0 +1,24 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use entities::DataUsage;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::Network;
+
+route!(
+    method = GET,
+    group = Network,
+    path = "/data-usage",
+
+    #[instrument(skip(state))]
+    async fetch_data_usage(state: State<PostgresAppState>) -> ApiResult<DataUsage> {
+        Ok(state.fetch_data_usage.execute().await.map(|usage| {
+            ApiResponse::new(usage, StatusCode::OK)
+        })?)
+    }
+);
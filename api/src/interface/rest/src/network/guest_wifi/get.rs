@@ -0,0 +1,24 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use entities::GuestNetwork;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::GuestWifi;
+
+route!(
+    method = GET,
+    group = GuestWifi,
+    path = "/",
+
+    #[instrument(skip(state))]
+    async fetch_guest_network(state: State<PostgresAppState>) -> ApiResult<GuestNetwork> {
+        Ok(state.fetch_guest_network.execute().await.map(|guest_network| {
+            ApiResponse::new(guest_network, StatusCode::OK)
+        })?)
+    }
+);
@@ -0,0 +1,30 @@
+use axum::http::StatusCode;
+use axum_distributed_routing::route_group;
+use domain::GuestNetworkError;
+
+use crate::{PostgresAppState, response::ApiError};
+
+use super::Network;
+
+route_group!(GuestWifi, PostgresAppState, Network, "/guest-wifi");
+
+mod get;
+mod qr;
+mod update;
+
+impl From<GuestNetworkError> for ApiError {
+    fn from(err: GuestNetworkError) -> Self {
+        match err {
+            GuestNetworkError::Disabled => ApiError::new(
+                "guest-network-disabled",
+                err.to_string(),
+                StatusCode::CONFLICT,
+            ),
+            GuestNetworkError::ExpiryInPast => {
+                ApiError::new("expiry-in-past", err.to_string(), StatusCode::BAD_REQUEST)
+            }
+            GuestNetworkError::RouterApiError(err) => err.into(),
+            GuestNetworkError::DatabaseError(err) => err.into(),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::GuestWifi;
+
+route!(
+    method = GET,
+    group = GuestWifi,
+    path = "/qr",
+
+    #[instrument(skip(state))]
+    async generate_guest_network_qr(state: State<PostgresAppState>) -> ApiResult<String> {
+        Ok(state.generate_guest_network_qr.execute().await.map(|payload| {
+            ApiResponse::new(payload, StatusCode::OK)
+        })?)
+    }
+);
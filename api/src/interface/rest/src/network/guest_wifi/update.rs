@@ -0,0 +1,27 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use domain::UpdateGuestNetwork;
+use entities::GuestNetwork;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    extractors::ValidJson,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::GuestWifi;
+
+route!(
+    method = PUT,
+    group = GuestWifi,
+    path = "/",
+    body = ValidJson<UpdateGuestNetwork>,
+
+    #[instrument(skip(state))]
+    async update_guest_network(state: State<PostgresAppState>) -> ApiResult<GuestNetwork> {
+        Ok(state.update_guest_network.execute(body.0).await.map(|guest_network| {
+            ApiResponse::new(guest_network, StatusCode::OK)
+        })?)
+    }
+);
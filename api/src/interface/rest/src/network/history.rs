@@ -0,0 +1,48 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use chrono::{DateTime, Utc};
+use domain::ListWanHistoryError;
+use entities::{WanHistory, WanStatsBucket};
+use serde::Deserialize;
+use tracing::instrument;
+use validator::Validate;
+
+use crate::{
+    PostgresAppState,
+    extractors::ValidQuery,
+    response::{ApiError, ApiResponse, ApiResult},
+};
+
+use super::Network;
+
+impl From<ListWanHistoryError> for ApiError {
+    fn from(err: ListWanHistoryError) -> Self {
+        match err {
+            ListWanHistoryError::InvalidRange => {
+                ApiError::new("invalid-range", err.to_string(), StatusCode::BAD_REQUEST)
+            }
+            ListWanHistoryError::DatabaseError(err) => err.into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct WanHistoryQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub bucket: Option<WanStatsBucket>,
+}
+
+route!(
+    method = GET,
+    group = Network,
+    path = "/history",
+    query = ValidQuery<WanHistoryQuery>,
+
+    #[instrument(skip(state, query), fields(from = ?query.from, to = ?query.to, bucket = ?query.bucket))]
+    async list_wan_history(state: State<PostgresAppState>) -> ApiResult<WanHistory> {
+        Ok(state.list_wan_history.execute(query.from, query.to, query.bucket).await.map(|history| {
+            ApiResponse::new(history, StatusCode::OK)
+        })?)
+    }
+);
@@ -4,4 +4,11 @@ use crate::{PostgresAppState, RestV1};
 
 route_group!(Network, PostgresAppState, RestV1, "/network");
 
+mod data_usage;
 mod get;
+mod guest_wifi;
+mod history;
+mod outages;
+mod port_forwards;
+mod top_talkers;
+mod wifi;
@@ -0,0 +1,47 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use chrono::{DateTime, Utc};
+use domain::ListWanOutagesError;
+use entities::WanAvailability;
+use serde::Deserialize;
+use tracing::instrument;
+use validator::Validate;
+
+use crate::{
+    PostgresAppState,
+    extractors::ValidQuery,
+    response::{ApiError, ApiResponse, ApiResult},
+};
+
+use super::Network;
+
+impl From<ListWanOutagesError> for ApiError {
+    fn from(err: ListWanOutagesError) -> Self {
+        match err {
+            ListWanOutagesError::InvalidRange => {
+                ApiError::new("invalid-range", err.to_string(), StatusCode::BAD_REQUEST)
+            }
+            ListWanOutagesError::DatabaseError(err) => err.into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ListOutagesQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+route!(
+    method = GET,
+    group = Network,
+    path = "/outages",
+    query = ValidQuery<ListOutagesQuery>,
+
+    #[instrument(skip(state, query), fields(from = ?query.from, to = ?query.to))]
+    async list_outages(state: State<PostgresAppState>) -> ApiResult<WanAvailability> {
+        Ok(state.list_wan_outages.execute(query.from, query.to).await.map(|availability| {
+            ApiResponse::new(availability, StatusCode::OK)
+        })?)
+    }
+);
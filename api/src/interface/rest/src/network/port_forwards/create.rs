@@ -0,0 +1,27 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use domain::SavePortForward;
+use entities::FullPortForward;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    extractors::ValidJson,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::PortForwards;
+
+route!(
+    method = POST,
+    group = PortForwards,
+    path = "/",
+    body = ValidJson<SavePortForward>,
+
+    #[instrument(skip(state))]
+    async create_port_forward(state: State<PostgresAppState>) -> ApiResult<FullPortForward> {
+        Ok(state.create_port_forward.execute(body.0).await.map(|port_forward| {
+            ApiResponse::new(port_forward, StatusCode::CREATED)
+        })?)
+    }
+);
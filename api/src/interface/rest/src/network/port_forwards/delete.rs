@@ -0,0 +1,23 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::PortForwards;
+
+route!(
+    method = DELETE,
+    group = PortForwards,
+    path = "/{port_forward_id:String}",
+
+    #[instrument(skip(state))]
+    async delete_port_forward(state: State<PostgresAppState>) -> ApiResult<()> {
+        Ok(state.delete_port_forward.execute(port_forward_id).await.map(|_| {
+            ApiResponse::new((), StatusCode::OK)
+        })?)
+    }
+);
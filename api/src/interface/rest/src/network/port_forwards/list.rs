@@ -0,0 +1,24 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use entities::FullPortForward;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::PortForwards;
+
+route!(
+    method = GET,
+    group = PortForwards,
+    path = "/",
+
+    #[instrument(skip(state))]
+    async list_port_forwards(state: State<PostgresAppState>) -> ApiResult<Vec<FullPortForward>> {
+        Ok(state.list_port_forwards.execute().await.map(|port_forwards| {
+            ApiResponse::list(port_forwards, StatusCode::OK)
+        })?)
+    }
+);
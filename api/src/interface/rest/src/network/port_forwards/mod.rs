@@ -0,0 +1,36 @@
+use axum::http::StatusCode;
+use axum_distributed_routing::route_group;
+use domain::PortForwardError;
+
+use crate::{PostgresAppState, response::ApiError};
+
+use super::Network;
+
+route_group!(PortForwards, PostgresAppState, Network, "/port-forwards");
+
+mod create;
+mod delete;
+mod list;
+mod update;
+
+impl From<PortForwardError> for ApiError {
+    fn from(err: PortForwardError) -> Self {
+        match err {
+            PortForwardError::ServiceNotFound => {
+                ApiError::new("service-not-found", err.to_string(), StatusCode::NOT_FOUND)
+            }
+            PortForwardError::ServicePortNotFound => ApiError::new(
+                "service-port-not-found",
+                err.to_string(),
+                StatusCode::BAD_REQUEST,
+            ),
+            PortForwardError::ServicePortMismatch => ApiError::new(
+                "service-port-mismatch",
+                err.to_string(),
+                StatusCode::BAD_REQUEST,
+            ),
+            PortForwardError::RouterApiError(err) => err.into(),
+            PortForwardError::DatabaseError(err) => err.into(),
+        }
+    }
+}
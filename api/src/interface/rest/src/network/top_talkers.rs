@@ -0,0 +1,37 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use chrono::{DateTime, Utc};
+use entities::TopTalkers;
+use serde::Deserialize;
+use tracing::instrument;
+use validator::Validate;
+
+use crate::{
+    PostgresAppState,
+    extractors::ValidQuery,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::Network;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct TopTalkersQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    #[validate(range(min = 1, max = 100))]
+    pub limit: Option<usize>,
+}
+
+route!(
+    method = GET,
+    group = Network,
+    path = "/top-talkers",
+    query = ValidQuery<TopTalkersQuery>,
+
+    #[instrument(skip(state, query), fields(from = ?query.from, to = ?query.to))]
+    async list_top_talkers(state: State<PostgresAppState>) -> ApiResult<TopTalkers> {
+        Ok(state.list_top_talkers.execute(query.from, query.to, query.limit).await.map(|top_talkers| {
+            ApiResponse::new(top_talkers, StatusCode::OK)
+        })?)
+    }
+);
@@ -0,0 +1,24 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use entities::WifiRadio;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::Network;
+
+route!(
+    method = GET,
+    group = Network,
+    path = "/wifi",
+
+    #[instrument(skip(state))]
+    async list_wifi_radios(state: State<PostgresAppState>) -> ApiResult<Vec<WifiRadio>> {
+        Ok(state.list_wifi_radios.execute().await.map(|radios| {
+            ApiResponse::list(radios, StatusCode::OK)
+        })?)
+    }
+);
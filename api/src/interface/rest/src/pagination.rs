@@ -0,0 +1,43 @@
+use axum::http::StatusCode;
+use entities::{Cursor, Pagination, Paging, SortField};
+
+use crate::response::ApiError;
+
+/// Builds the paging of a list from its query parameters. Either a page or a cursor may be given,
+/// both requiring a limit. A limit alone selects the first page. The cursor must come from a list
+/// sorted by the same field, its value is compared to the rows.
+pub fn paging<F: SortField>(
+    page: Option<u32>,
+    after: Option<Cursor>,
+    limit: Option<u32>,
+    sort_field: F,
+) -> Result<Option<Paging>, ApiError> {
+    if let Some(cursor) = &after
+        && !sort_field.accepts(cursor)
+    {
+        return Err(ApiError::new(
+            "invalid-cursor",
+            format!("The cursor does not belong to a list sorted by {sort_field}."),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    match (page, after, limit) {
+        (Some(_), Some(_), _) => Err(ApiError::new(
+            "invalid-pagination",
+            "A page and a cursor cannot be used together.",
+            StatusCode::BAD_REQUEST,
+        )),
+        (page, None, Some(limit)) => Ok(Some(Paging::Offset(Pagination {
+            page: page.unwrap_or(1),
+            limit,
+        }))),
+        (None, after @ Some(_), Some(limit)) => Ok(Some(Paging::Keyset { after, limit })),
+        (None, None, None) => Ok(None),
+        (_, _, None) => Err(ApiError::new(
+            "invalid-pagination",
+            "A limit is required to paginate.",
+            StatusCode::BAD_REQUEST,
+        )),
+    }
+}
@@ -4,6 +4,7 @@ use axum::{
     http::{Response, StatusCode, header},
     response::IntoResponse,
 };
+use entities::{PageMeta, Paged};
 use ports::{api::RouterApiError, repositories::RepositoryError};
 use serde::Serialize;
 use serde_json::json;
@@ -15,6 +16,7 @@ pub type ApiResult<T> = Result<ApiResponse<T>, ApiError>;
 #[derive(Debug)]
 pub struct ApiResponse<T: Serialize> {
     data: T,
+    meta: Option<PageMeta>, // only set for lists
     status_code: StatusCode,
 }
 
@@ -27,7 +29,31 @@ pub struct ApiError {
 
 impl<T: Serialize> ApiResponse<T> {
     pub fn new(data: T, status_code: StatusCode) -> Self {
-        Self { data, status_code }
+        Self {
+            data,
+            meta: None,
+            status_code,
+        }
+    }
+}
+
+impl<T: Serialize> ApiResponse<Vec<T>> {
+    /// Responds with a list returned at once.
+    pub fn list(data: Vec<T>, status_code: StatusCode) -> Self {
+        Self {
+            meta: Some(PageMeta::whole(data.len())),
+            data,
+            status_code,
+        }
+    }
+
+    /// Responds with a page of a list.
+    pub fn paged(page: Paged<T>, status_code: StatusCode) -> Self {
+        Self {
+            data: page.items,
+            meta: Some(page.meta),
+            status_code,
+        }
     }
 }
 
@@ -43,10 +69,15 @@ impl ApiError {
 
 impl<T: Serialize> IntoResponse for ApiResponse<T> {
     fn into_response(self) -> Response<Body> {
-        match serde_json::to_vec(&json!({
+        let mut body = json!({
             "success": true,
             "data": Some(json!(self.data)),
-        })) {
+        });
+        if let Some(meta) = self.meta {
+            body["meta"] = json!(meta);
+        }
+
+        match serde_json::to_vec(&body) {
             Ok(body) => Response::builder()
                 .status(self.status_code)
                 .header(header::CONTENT_TYPE, "application/json")
@@ -107,64 +138,72 @@ impl IntoResponse for ApiError {
     }
 }
 
-impl Into<ApiError> for RouterApiError {
-    fn into(self) -> ApiError {
-        match self {
+impl From<RouterApiError> for ApiError {
+    fn from(err: RouterApiError) -> Self {
+        match err {
             RouterApiError::Unavailable => ApiError::new(
                 "router-api-unavailable",
-                self.to_string(),
+                err.to_string(),
                 StatusCode::SERVICE_UNAVAILABLE,
             ),
             RouterApiError::InvalidResponse(_) => ApiError::new(
                 "router-api-invalid-response",
-                self.to_string(),
+                err.to_string(),
                 StatusCode::BAD_GATEWAY,
             ),
             RouterApiError::AuthenticationFailed => ApiError::new(
                 "router-api-authentication-failed",
-                self.to_string(),
+                err.to_string(),
                 StatusCode::BAD_GATEWAY,
             ),
+            RouterApiError::NotFound => ApiError::new(
+                "router-resource-not-found",
+                err.to_string(),
+                StatusCode::NOT_FOUND,
+            ),
+            RouterApiError::Unsupported => ApiError::new(
+                "router-api-unsupported",
+                err.to_string(),
+                StatusCode::NOT_IMPLEMENTED,
+            ),
             RouterApiError::Unknown(_) => ApiError::new(
                 "router-api-unknown-error",
-                self.to_string(),
+                err.to_string(),
                 StatusCode::INTERNAL_SERVER_ERROR,
             ),
         }
     }
 }
 
-impl Into<ApiError> for RepositoryError {
-    fn into(self) -> ApiError {
-        match self {
-            RepositoryError::NotFound => ApiError::new(
-                "resource-not-found",
-                self.to_string(),
-                StatusCode::NOT_FOUND,
-            ),
+impl From<RepositoryError> for ApiError {
+    fn from(err: RepositoryError) -> Self {
+        match err {
+            RepositoryError::NotFound => {
+                ApiError::new("resource-not-found", err.to_string(), StatusCode::NOT_FOUND)
+            }
             RepositoryError::CheckViolation => ApiError::new(
                 "resource-check-violation",
-                self.to_string(),
+                err.to_string(),
                 StatusCode::BAD_REQUEST,
             ),
             RepositoryError::UniqueViolation => ApiError::new(
                 "resource-unique-violation",
-                self.to_string(),
+                err.to_string(),
                 StatusCode::CONFLICT,
             ),
             RepositoryError::ForeignKeyViolation => ApiError::new(
                 "resource-foreign-key-violation",
-                self.to_string(),
+                err.to_string(),
                 StatusCode::BAD_REQUEST,
             ),
             RepositoryError::ConnectionFailed => ApiError::new(
                 "database-connection-failed",
-                self.to_string(),
+                err.to_string(),
                 StatusCode::SERVICE_UNAVAILABLE,
             ),
             RepositoryError::Unknown => ApiError::new(
                 "database-unknown-error",
-                self.to_string(),
+                err.to_string(),
                 StatusCode::INTERNAL_SERVER_ERROR,
             ),
         }
@@ -187,7 +226,7 @@ impl From<JsonRejection> for ApiError {
             ),
             _ => (
                 StatusCode::BAD_REQUEST,
-                format!("An unknown error occurred with the JSON payload."),
+                "An unknown error occurred with the JSON payload.".to_string(),
             ),
         };
 
@@ -0,0 +1,34 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use entities::ConfigSnapshotDiff;
+use serde::Deserialize;
+use tracing::instrument;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    PostgresAppState,
+    extractors::ValidQuery,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::ConfigSnapshots;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct DiffConfigSnapshotQuery {
+    pub against: Option<Uuid>, // defaults to the previous snapshot
+}
+
+route!(
+    method = GET,
+    group = ConfigSnapshots,
+    path = "/{snapshot_id:Uuid}/diff",
+    query = ValidQuery<DiffConfigSnapshotQuery>,
+
+    #[instrument(skip(state, query), fields(against = ?query.against))]
+    async diff_config_snapshot(state: State<PostgresAppState>) -> ApiResult<ConfigSnapshotDiff> {
+        Ok(state.diff_config_snapshots.execute(snapshot_id, query.against).await.map(|diff| {
+            ApiResponse::new(diff, StatusCode::OK)
+        })?)
+    }
+);
@@ -0,0 +1,25 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use entities::FullConfigSnapshot;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    PostgresAppState,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::ConfigSnapshots;
+
+route!(
+    method = GET,
+    group = ConfigSnapshots,
+    path = "/{snapshot_id:Uuid}",
+
+    #[instrument(skip(state))]
+    async fetch_config_snapshot(state: State<PostgresAppState>) -> ApiResult<FullConfigSnapshot> {
+        Ok(state.fetch_config_snapshot.execute(snapshot_id).await.map(|snapshot| {
+            ApiResponse::new(snapshot, StatusCode::OK)
+        })?)
+    }
+);
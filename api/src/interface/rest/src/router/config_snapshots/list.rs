@@ -0,0 +1,24 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use entities::ConfigSnapshot;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::ConfigSnapshots;
+
+route!(
+    method = GET,
+    group = ConfigSnapshots,
+    path = "/",
+
+    #[instrument(skip(state))]
+    async list_config_snapshots(state: State<PostgresAppState>) -> ApiResult<Vec<ConfigSnapshot>> {
+        Ok(state.list_config_snapshots.execute().await.map(|snapshots| {
+            ApiResponse::list(snapshots, StatusCode::OK)
+        })?)
+    }
+);
@@ -0,0 +1,32 @@
+use axum::http::StatusCode;
+use axum_distributed_routing::route_group;
+use domain::ConfigSnapshotError;
+
+use crate::{PostgresAppState, response::ApiError};
+
+use super::Router;
+
+route_group!(
+    ConfigSnapshots,
+    PostgresAppState,
+    Router,
+    "/config-snapshots"
+);
+
+mod diff;
+mod get;
+mod list;
+
+impl From<ConfigSnapshotError> for ApiError {
+    fn from(err: ConfigSnapshotError) -> Self {
+        match err {
+            ConfigSnapshotError::SnapshotNotFound => ApiError::new(
+                "config-snapshot-not-found",
+                err.to_string(),
+                StatusCode::NOT_FOUND,
+            ),
+            ConfigSnapshotError::RouterApiError(err) => err.into(),
+            ConfigSnapshotError::DatabaseError(err) => err.into(),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use entities::RouterInfo;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::Router;
+
+route!(
+    method = GET,
+    group = Router,
+    path = "/",
+
+    #[instrument(skip(state))]
+    async fetch_router_info(state: State<PostgresAppState>) -> ApiResult<RouterInfo> {
+        Ok(state.fetch_router_info.execute().await.map(|info| {
+            ApiResponse::new(info, StatusCode::OK)
+        })?)
+    }
+);
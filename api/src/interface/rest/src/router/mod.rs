@@ -0,0 +1,9 @@
+use axum_distributed_routing::route_group;
+
+use crate::{PostgresAppState, RestV1};
+
+route_group!(Router, PostgresAppState, RestV1, "/router");
+
+mod config_snapshots;
+mod get;
+mod reboot;
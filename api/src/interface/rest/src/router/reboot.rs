@@ -0,0 +1,23 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use tracing::instrument;
+
+use crate::{
+    PostgresAppState,
+    response::{ApiResponse, ApiResult},
+};
+
+use super::Router;
+
+route!(
+    method = POST,
+    group = Router,
+    path = "/reboot",
+
+    #[instrument(skip(state))]
+    async reboot_router(state: State<PostgresAppState>) -> ApiResult<()> {
+        Ok(state.reboot_router.execute().await.map(|_| {
+            ApiResponse::new((), StatusCode::ACCEPTED)
+        })?)
+    }
+);
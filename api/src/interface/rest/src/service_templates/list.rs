@@ -12,6 +12,6 @@ route!(
 
     #[instrument(skip(state))]
     async list_service_templates(state: State<PostgresAppState>) -> ApiResponse<Vec<ServiceTemplate>> {
-        ApiResponse::new(state.list_service_templates.execute().await, StatusCode::OK)
+        ApiResponse::list(state.list_service_templates.execute().await, StatusCode::OK)
     }
 );
@@ -0,0 +1,74 @@
+use axum::{extract::State, http::StatusCode};
+use axum_distributed_routing::route;
+use entities::{
+    Cursor, Service, ServiceFilter, ServiceKind, ServiceQuery, ServiceSortField, Sort, SortOrder,
+};
+use mac_address::MacAddress;
+use serde::Deserialize;
+use tracing::instrument;
+use validator::Validate;
+
+use crate::{
+    PostgresAppState,
+    extractors::ValidQuery,
+    pagination::paging,
+    response::{ApiError, ApiResponse, ApiResult},
+    services::Services,
+};
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ListServicesQuery {
+    #[validate(range(min = 1))]
+    pub page: Option<u32>, // defaults to the first page when a limit is set
+    pub after: Option<Cursor>, // keyset pagination, instead of a page
+    #[validate(range(min = 1, max = 1000))]
+    pub limit: Option<u32>,
+
+    pub device_mac: Option<MacAddress>,
+    pub kind: Option<ServiceKind>,
+    pub online: Option<bool>,
+    #[validate(length(min = 1, max = 255))]
+    pub search: Option<String>,
+
+    #[serde(default)]
+    pub sort: ServiceSortField,
+    #[serde(default)]
+    pub order: SortOrder,
+}
+
+impl ListServicesQuery {
+    fn to_service_query(&self) -> Result<ServiceQuery, ApiError> {
+        Ok(ServiceQuery {
+            filter: ServiceFilter {
+                device_mac: self.device_mac,
+                kind: self.kind,
+                is_online: self.online,
+                search: self.search.clone(),
+            },
+            sort: Sort {
+                field: self.sort,
+                order: self.order,
+            },
+            paging: paging(self.page, self.after.clone(), self.limit, self.sort)?,
+        })
+    }
+}
+
+route!(
+    method = GET,
+    group = Services,
+    path = "/",
+    query = ValidQuery<ListServicesQuery>,
+
+    #[instrument(skip(state, query), fields(
+        pagination.page = query.page,
+        pagination.after = ?query.after,
+        pagination.limit = query.limit,
+    ))]
+    async list_services(state: State<PostgresAppState>) -> ApiResult<Vec<Service>> {
+        Ok(state.list_services.execute(query.to_service_query()?).await.map(|services| {
+            ApiResponse::paged(services, StatusCode::OK)
+        })?)
+    }
+);
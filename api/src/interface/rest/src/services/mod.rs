@@ -6,3 +6,4 @@ route_group!(Services, PostgresAppState, RestV1, "/services");
 
 mod create;
 mod install_script;
+mod list;
@@ -99,6 +99,10 @@ export function NetworkStats({
     );
   }
 
+  if (!wan.stats) {
+    return null;
+  }
+
   // Helper to calculate progress safely
   const getProgress = (current: number, max: number) => {
     if (!max || max === 0) return 0;
@@ -199,7 +203,9 @@ export function NetworkStats({
           <div className="flex items-center justify-between">
             <span className="text-sm text-muted-foreground">Uptime</span>
             <span className="text-lg font-semibold text-success">
-              {formatDuration(wan.connectivity.uptime)}
+              {wan.connectivity?.uptime != null
+                ? formatDuration(wan.connectivity.uptime)
+                : "Unknown"}
             </span>
           </div>
         </div>
@@ -9,6 +9,10 @@ import { cn } from "@/lib/utils";
 import { NetworkWan } from "@/models";
 
 const WanIpDisplaySkeleton = () => {
+  if (!wan.connectivity) {
+    return null;
+  }
+
   return (
     <Card className="p-6 border-border bg-card">
       {/* Header Placeholder */}
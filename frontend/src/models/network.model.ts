@@ -17,10 +17,11 @@ export interface NetworkConnectivity {
   ipv6: string;
   gateway: string;
   status: "Up" | "Down";
-  uptime: number;
+  uptime: number | null; // Null when the router does not report it
 }
 
+// Null when the router API cannot report it (e.g. router-less discovery)
 export interface NetworkWan {
-  connectivity: NetworkConnectivity;
-  stats: NetworkStats;
+  connectivity: NetworkConnectivity | null;
+  stats: NetworkStats | null;
 }